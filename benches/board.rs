@@ -0,0 +1,60 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use sudoku::{format_line, parse_line, solve, Board};
+
+// Compare the field orders of the board with `RUSTFLAGS="--cfg sudoku_row_major" cargo bench --bench board`
+// and the scalar conflict checks with `RUSTFLAGS="--cfg sudoku_scalar" cargo bench --bench board`
+
+const PUZZLE: &str = "..468..19..3..92.5.6......46.....7.2.....7......9....18...5...7.413.8....2..91...";
+
+fn bench_parse(c: &mut Criterion) {
+    c.bench_function("board parse", |b| b.iter(|| parse_line(black_box(PUZZLE))));
+}
+
+fn bench_format(c: &mut Criterion) {
+    let board = parse_line(PUZZLE).unwrap();
+    c.bench_function("board format", |b| b.iter(|| format_line(black_box(&board))));
+}
+
+fn bench_rows(c: &mut Criterion) {
+    let board = parse_line(PUZZLE).unwrap();
+    c.bench_function("board rows", |b| {
+        b.iter(|| {
+            let board: &Board = black_box(&board);
+            (0..9)
+                .flat_map(|y| board.row_iter(y))
+                .filter_map(|field| field.get())
+                .map(|value| u32::from(value.get()))
+                .sum::<u32>()
+        })
+    });
+}
+
+fn bench_has_conflicts(c: &mut Criterion) {
+    let board = parse_line(PUZZLE).unwrap();
+    c.bench_function("board has_conflicts", |b| b.iter(|| black_box(&board).has_conflicts()));
+}
+
+fn bench_first_empty(c: &mut Criterion) {
+    let board = solve(parse_line(PUZZLE).unwrap()).unwrap();
+    let mut almost_filled = board;
+    almost_filled.field_mut(8, 8).set(None);
+    c.bench_function("board first_empty_field_index", |b| {
+        b.iter(|| black_box(&almost_filled).first_empty_field_index())
+    });
+}
+
+fn bench_solve(c: &mut Criterion) {
+    let board = parse_line(PUZZLE).unwrap();
+    c.bench_function("board solve", |b| b.iter(|| solve(black_box(board))));
+}
+
+criterion_group!(
+    benches,
+    bench_parse,
+    bench_format,
+    bench_rows,
+    bench_has_conflicts,
+    bench_first_empty,
+    bench_solve
+);
+criterion_main!(benches);
@@ -2,11 +2,11 @@ use criterion::{criterion_group, criterion_main, Criterion};
 use sudoku::{generate_solved, generate};
 
 fn bench_generate_solved(c: &mut Criterion) {
-    c.bench_function("generate solved", |b| b.iter(|| generate_solved()));
+    c.bench_function("generate solved", |b| b.iter(generate_solved));
 }
 
 fn bench_generate_unsolved(c: &mut Criterion) {
-    c.bench_function("generate unsolved", |b| b.iter(|| generate()));
+    c.bench_function("generate unsolved", |b| b.iter(generate));
 }
 
 criterion_group!(
@@ -1,4 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::str::FromStr;
 use sudoku::{solve, Board};
 
 fn solve_empty(c: &mut Criterion) {
@@ -21,7 +22,8 @@ fn solve_solvable(c: &mut Criterion) {
         _41 3_8 ___
         _2_ _91 ___
     ",
-    );
+    )
+    .unwrap();
     c.bench_function("solve solvable", |b| b.iter(|| solve(black_box(board))));
 }
 
@@ -40,7 +42,8 @@ fn solve_not_solvable(c: &mut Criterion) {
         _41 3_8 ___
         _2_ _91 ___
     ",
-    );
+    )
+    .unwrap();
     c.bench_function("solve not-solvable", |b| b.iter(|| solve(black_box(board))));
 }
 
@@ -59,15 +62,40 @@ fn solve_ambigious(c: &mut Criterion) {
         _41 3_8 ___
         _2_ _91 ___
     ",
-    );
+    )
+    .unwrap();
     c.bench_function("solve ambigious", |b| b.iter(|| solve(black_box(board))));
 }
 
+#[cfg(feature = "corpus")]
+fn solve_corpus(c: &mut Criterion) {
+    use sudoku::corpus;
+
+    for (name, puzzles) in [
+        ("easy", corpus::easy()),
+        ("medium", corpus::medium()),
+        ("hard", corpus::hard()),
+        ("17 clues", corpus::seventeen_clues()),
+    ] {
+        c.bench_function(&format!("solve corpus {name}"), |b| {
+            b.iter(|| {
+                for &puzzle in &puzzles {
+                    let _ = solve(black_box(puzzle));
+                }
+            })
+        });
+    }
+}
+
+#[cfg(not(feature = "corpus"))]
+fn solve_corpus(_c: &mut Criterion) {}
+
 criterion_group!(
     benches,
     solve_empty,
     solve_solvable,
     solve_not_solvable,
-    solve_ambigious
+    solve_ambigious,
+    solve_corpus
 );
 criterion_main!(benches);
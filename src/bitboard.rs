@@ -0,0 +1,97 @@
+//! A bitboard is a set of fields stored in a `u128`, where bit `x * HEIGHT + y` stands for the field (x, y).
+//! This is the same column-by-column order as in [Board](crate::Board), so whole rows, columns and regions can be
+//! compared with a few shifts and popcounts instead of looking at each field.
+
+use crate::board::{HEIGHT, NUM_FIELDS, WIDTH};
+
+/// A bitboard with the bits of all fields set
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub const ALL_FIELDS: u128 = (1 << NUM_FIELDS) - 1;
+
+/// A bitboard with the bits of the first field of each column set, i.e. the first row
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+const FIRST_ROW: u128 = {
+    let mut bitboard = 0;
+    let mut x = 0;
+    while x < WIDTH {
+        bitboard |= 1 << (x * HEIGHT);
+        x += 1;
+    }
+    bitboard
+};
+
+/// The fields of a row as a bitboard
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub const fn row_bitboard(y: usize) -> u128 {
+    FIRST_ROW << y
+}
+
+/// The fields of a column as a bitboard
+pub const fn column_bitboard(x: usize) -> u128 {
+    ((1 << HEIGHT) - 1) << (x * HEIGHT)
+}
+
+/// The fields of a 3x3 region as a bitboard. Regions are numbered left-to-right, then top-to-bottom.
+pub const fn region_bitboard(region: usize) -> u128 {
+    let column = 0b111 << (3 * (region / 3));
+    (column | column << HEIGHT | column << (2 * HEIGHT)) << (3 * HEIGHT * (region % 3))
+}
+
+/// A mask with bit `y` set for each row that has a field in the bitboard
+pub fn rows_of_bitboard(bitboard: u128) -> u16 {
+    (0..WIDTH).fold(0, |rows, x| rows | (bitboard >> (x * HEIGHT)) as u16 & ((1 << HEIGHT) - 1))
+}
+
+/// A mask with bit `x` set for each column that has a field in the bitboard
+pub fn columns_of_bitboard(bitboard: u128) -> u16 {
+    lines_of_bitboard(bitboard, column_bitboard)
+}
+
+/// A mask with bit `region` set for each region that has a field in the bitboard
+pub fn regions_of_bitboard(bitboard: u128) -> u16 {
+    lines_of_bitboard(bitboard, region_bitboard)
+}
+
+fn lines_of_bitboard(bitboard: u128, line: fn(usize) -> u128) -> u16 {
+    (0..9).fold(0, |lines, index| {
+        if bitboard & line(index) != 0 {
+            lines | 1 << index
+        } else {
+            lines
+        }
+    })
+}
+
+/// The fields of a bitboard, column by column
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub fn fields_of_bitboard(mut bitboard: u128) -> impl Iterator<Item = (usize, usize)> {
+    core::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let index = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+        Some((index / HEIGHT, index % HEIGHT))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines() {
+        let bitboard = row_bitboard(2) & column_bitboard(7) | row_bitboard(5) & column_bitboard(0);
+        assert_eq!(vec![(0, 5), (7, 2)], fields_of_bitboard(bitboard).collect::<Vec<_>>());
+        assert_eq!(0b100100, rows_of_bitboard(bitboard));
+        assert_eq!(0b10000001, columns_of_bitboard(bitboard));
+        assert_eq!(0b1100, regions_of_bitboard(bitboard));
+        assert_eq!(ALL_FIELDS, (0..HEIGHT).fold(0, |all, y| all | row_bitboard(y)));
+        assert_eq!(ALL_FIELDS, (0..WIDTH).fold(0, |all, x| all | column_bitboard(x)));
+        assert_eq!(ALL_FIELDS, (0..9).fold(0, |all, region| all | region_bitboard(region)));
+        assert_eq!(
+            vec![(6, 3), (6, 4), (6, 5), (7, 3), (7, 4), (7, 5), (8, 3), (8, 4), (8, 5)],
+            fields_of_bitboard(region_bitboard(5)).collect::<Vec<_>>()
+        );
+    }
+}
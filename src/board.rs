@@ -1,6 +1,8 @@
+use crate::bitboard::{columns_of_bitboard, regions_of_bitboard, rows_of_bitboard, ALL_FIELDS};
+use crate::constraints::Constraint;
 use crate::utils::div_ceil;
-use std::fmt::{self, Debug};
-use std::num::NonZeroU8;
+use core::fmt::{self, Debug};
+use core::num::NonZeroU8;
 
 pub const WIDTH: usize = 9;
 pub const HEIGHT: usize = 9;
@@ -9,12 +11,47 @@ pub const MAX_VALUE: u8 = 9;
 
 const NUM_BYTES: usize = div_ceil(NUM_FIELDS, 2);
 
+/// The order in which [Board] stores its fields. This is only an internal parameter to compare the performance of both
+/// orders with `RUSTFLAGS="--cfg sudoku_row_major" cargo bench`, the accessors behave the same for both.
+/// Column by column is faster, mostly because the solver looks for empty fields in that order, see the `board` benchmark.
+const ROW_MAJOR: bool = cfg!(sudoku_row_major);
+
+/// Whether [Board::has_conflicts] and [Board::is_subset_of] check one field at a time instead of using bitboards and
+/// 64 bit words. This is the fallback for targets where the word tricks are slow, and like [ROW_MAJOR] it can be
+/// compared with `RUSTFLAGS="--cfg sudoku_scalar" cargo bench`. Both are tested against each other either way.
+const SCALAR: bool = cfg!(sudoku_scalar);
+
+/// The position of a field in the packed board, see [ROW_MAJOR]
+const fn storage_index(x: usize, y: usize) -> usize {
+    if ROW_MAJOR {
+        y * WIDTH + x
+    } else {
+        x * HEIGHT + y
+    }
+}
+
+/// For each position in the packed board, the bit of that field in a [bitboard](crate::bitboard)
+const BITBOARD_BITS: [u8; NUM_FIELDS] = {
+    let mut bits = [0; NUM_FIELDS];
+    let mut x = 0;
+    while x < WIDTH {
+        let mut y = 0;
+        while y < HEIGHT {
+            bits[storage_index(x, y)] = (x * HEIGHT + y) as u8;
+            y += 1;
+        }
+        x += 1;
+    }
+    bits
+};
+
 /// A [Board] is a 9x9 sudoku board.
 /// Each cell can contain a value in 0..=9 where 0 means the cell is empty.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Board {
     // Every byte stores two cells. The first 4 bits the first cell, the second 4 bits the second cell.
-    // Cells are ordered by columns, first top-to-bottom, then next column left-to-right
+    // Cells are ordered by columns, first top-to-bottom, then next column left-to-right, unless [ROW_MAJOR] is set.
+    // The unused half byte at the end is always zero, so the derived PartialEq and Hash can compare whole bytes.
     compressed_board: [u8; NUM_BYTES],
 }
 
@@ -24,6 +61,27 @@ enum FieldSubindex {
     SecondHalfByte,
 }
 
+/// For each field, the byte it's stored in and the half of that byte, see [Board::index]
+const INDICES: [[(u8, FieldSubindex); HEIGHT]; WIDTH] = {
+    let mut indices = [[(0, FieldSubindex::FirstHalfByte); HEIGHT]; WIDTH];
+    let mut x = 0;
+    while x < WIDTH {
+        let mut y = 0;
+        while y < HEIGHT {
+            let index = storage_index(x, y);
+            let subindex = if index.is_multiple_of(2) {
+                FieldSubindex::FirstHalfByte
+            } else {
+                FieldSubindex::SecondHalfByte
+            };
+            indices[x][y] = ((index / 2) as u8, subindex);
+            y += 1;
+        }
+        x += 1;
+    }
+    indices
+};
+
 pub struct FieldRef<T> {
     field: T,
     subindex: FieldSubindex,
@@ -84,6 +142,10 @@ impl Board {
         }
     }
 
+    /// Parses a board with `_` for empty fields and panics on malformed input, so tests can write boards inline.
+    /// Everything else parses boards with [FromStr](core::str::FromStr), which returns an error instead.
+    #[cfg(test)]
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(board: &str) -> Self {
         let mut chars = board.chars().filter(|x| !x.is_whitespace());
         let mut board = Board::new_empty();
@@ -107,13 +169,8 @@ impl Board {
     fn index(x: usize, y: usize) -> (usize, FieldSubindex) {
         assert!(x < WIDTH);
         assert!(y < HEIGHT);
-        let index = x * HEIGHT + y;
-        let subindex = if index % 2 == 0 {
-            FieldSubindex::FirstHalfByte
-        } else {
-            FieldSubindex::SecondHalfByte
-        };
-        (index / 2, subindex)
+        let (index, subindex) = INDICES[x][y];
+        (usize::from(index), subindex)
     }
 
     #[inline]
@@ -130,18 +187,26 @@ impl Board {
         FieldRef { field, subindex }
     }
 
-    // TODO Test
+    /// The first empty field, column by column
     pub fn first_empty_field_index(&self) -> Option<(usize, usize)> {
-        // TODO Do this with iterators
-        // TODO Better would be to iterate over `self.compressed_board` and `FieldRef::subindex`
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
-                if self.field(x, y).is_empty() {
-                    return Some((x, y));
-                }
-            }
+        if ROW_MAJOR {
+            // Scanning the bytes would find the first empty field row by row
+            let filled = self.value_bitboards().into_iter().fold(0, |filled, bitboard| filled | bitboard);
+            let empty = ALL_FIELDS & !filled;
+            let index = empty.trailing_zeros() as usize;
+            return (empty != 0).then_some((index / HEIGHT, index % HEIGHT));
         }
-        None
+        let index = self.compressed_board.iter().enumerate().find_map(|(byte_index, byte)| {
+            if byte & 0x0F == 0 {
+                Some(2 * byte_index)
+            } else if byte >> 4 == 0 {
+                Some(2 * byte_index + 1)
+            } else {
+                None
+            }
+        })?;
+        // The unused half byte at the end is always zero, so we have to check that the field exists
+        (index < NUM_FIELDS).then_some((index / HEIGHT, index % HEIGHT))
     }
 
     // TODO Test
@@ -169,100 +234,164 @@ impl Board {
             .flat_map(move |x| (0..3).map(move |y| self.field(region_x * 3 + x, region_y * 3 + y)))
     }
 
-    // TODO Test
     pub fn has_conflicts(&self) -> bool {
-        for row in 0..HEIGHT {
-            if self.has_conflicts_in_fields(self.row_iter(row)) {
-                return true;
-            }
-        }
-        for col in 0..WIDTH {
-            if self.has_conflicts_in_fields(self.col_iter(col)) {
-                return true;
-            }
+        if SCALAR {
+            return self.has_conflicts_scalar();
         }
-        for region_x in 0..3 {
-            for region_y in 0..3 {
-                if self.has_conflicts_in_fields(self.region_iter(region_x, region_y)) {
-                    return true;
+        // A value conflicts with itself if it occupies less rows, columns or regions than it has fields
+        self.value_bitboards().into_iter().any(|bitboard| {
+            let num_fields = bitboard.count_ones();
+            num_fields > 1
+                && (rows_of_bitboard(bitboard).count_ones() < num_fields
+                    || columns_of_bitboard(bitboard).count_ones() < num_fields
+                    || regions_of_bitboard(bitboard).count_ones() < num_fields)
+        })
+    }
+
+    /// [Board::has_conflicts], one field at a time, see [SCALAR]
+    fn has_conflicts_scalar(&self) -> bool {
+        // The values seen so far in each row, then each column, then each region
+        let mut houses = [0u16; HEIGHT + WIDTH + 9];
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                if let Some(value) = self.field(x, y).get() {
+                    let bit = 1 << (value.get() - 1);
+                    let field_houses = [y, HEIGHT + x, HEIGHT + WIDTH + 3 * (y / 3) + x / 3];
+                    if field_houses.iter().any(|&house| houses[house] & bit != 0) {
+                        return true;
+                    }
+                    for house in field_houses {
+                        houses[house] |= bit;
+                    }
                 }
             }
         }
-        return false;
-    }
-
-    fn has_conflicts_in_fields<'a>(
-        &'a self,
-        fields: impl Iterator<Item = FieldRef<&'a u8>>,
-    ) -> bool {
-        let mut seen = [false; 9];
-        for field in fields {
-            if let Some(value) = field.get() {
-                let value = value.get() as usize - 1;
-                if seen[value] {
+        false
+    }
+
+    /// True if a value appears more than once in the given fields, e.g. in a house added by a [Constraint]
+    pub(crate) fn has_conflicts_in_fields(&self, fields: &[(usize, usize)]) -> bool {
+        let mut seen: u16 = 0;
+        for &(x, y) in fields {
+            if let Some(value) = self.field(x, y).get() {
+                let bit = 1 << (value.get() - 1);
+                if seen & bit != 0 {
                     return true;
                 }
-                seen[value] = true;
+                seen |= bit;
             }
         }
         false
     }
 
-    // TODO Test
-    pub fn is_subset_of(&self, rhs: &Board) -> bool {
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
-                if let Some(lhs_value) = self.field(x,y).get() {
-                    if Some(lhs_value) != rhs.field(x,y).get() {
-                        return false;
-                    }
+    /// For each value, the fields containing it as a [bitboard](crate::bitboard)
+    pub(crate) fn value_bitboards(&self) -> [u128; MAX_VALUE as usize] {
+        let mut bitboards = [0; MAX_VALUE as usize];
+        for (byte_index, byte) in self.compressed_board.iter().enumerate() {
+            for (field_index, value) in [(2 * byte_index, byte & 0x0F), (2 * byte_index + 1, byte >> 4)] {
+                if value != 0 {
+                    let bit = if ROW_MAJOR {
+                        usize::from(BITBOARD_BITS[field_index])
+                    } else {
+                        field_index
+                    };
+                    bitboards[usize::from(value - 1)] |= 1 << bit;
                 }
             }
         }
-        return true;
+        bitboards
     }
 
-    // TODO Test
-    pub fn num_empty(&self) -> usize {
-        let mut num_empty = 0;
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
-                if self.field(x,y).is_empty() {
-                    num_empty += 1;
-                }
-            }
+    /// Like [Board::has_conflicts], but also checks the additional constraints of a sudoku variant
+    pub fn has_conflicts_with(&self, constraints: &[Constraint]) -> bool {
+        self.has_conflicts() || constraints.iter().any(|constraint| constraint.has_conflicts(self))
+    }
+
+    /// True if every field with a value has the same value in `rhs`
+    pub fn is_subset_of(&self, rhs: &Board) -> bool {
+        if SCALAR {
+            return self.is_subset_of_scalar(rhs);
         }
-        num_empty
+        self.words().zip(rhs.words()).all(|(lhs, rhs)| {
+            // Spread the lowest bit of each non-empty half byte to the whole half byte
+            let filled = nonzero_half_bytes(lhs) * 0xF;
+            (lhs ^ rhs) & filled == 0
+        })
+    }
+
+    /// [Board::is_subset_of], one field at a time, see [SCALAR]
+    fn is_subset_of_scalar(&self, rhs: &Board) -> bool {
+        (0..WIDTH).all(|x| {
+            (0..HEIGHT).all(|y| self.field(x, y).is_empty() || self.field(x, y).get() == rhs.field(x, y).get())
+        })
+    }
+
+    pub fn num_empty(&self) -> usize {
+        NUM_FIELDS - self.num_filled()
+    }
+
+    /// The number of fields with a value, e.g. the number of clues of a puzzle
+    pub fn num_filled(&self) -> usize {
+        self.words().map(|word| nonzero_half_bytes(word).count_ones() as usize).sum()
+    }
+
+    /// The packed fields, 8 bytes at a time. The last word is padded with zeroes, like the unused half byte at the end.
+    fn words(&self) -> impl Iterator<Item = u64> + '_ {
+        self.compressed_board.chunks(8).map(|chunk| {
+            let mut bytes = [0; 8];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(bytes)
+        })
     }
 }
 
+/// Sets the lowest bit of each half byte of the word that has any bit set, and clears all other bits.
+/// After or-ing each bit with the three bits above it, the lowest bit of a half byte is set if any of its bits are set.
+fn nonzero_half_bytes(word: u64) -> u64 {
+    let word = word | (word >> 1);
+    let word = word | (word >> 2);
+    word & 0x1111_1111_1111_1111
+}
+
 impl Debug for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for y in 0..HEIGHT {
             if y == 3 || y == 6 {
                 // Add a separator line between every 3 rows
-                write!(f, "\n")?;
+                writeln!(f)?;
             }
             for x in 0..WIDTH {
                 if x == 3 || x == 6 {
                     // Add a separate between every 3 cols
                     write!(f, " ")?;
                 }
-                write!(
-                    f,
-                    "{}",
-                    self.field(x, y)
-                        .get()
-                        .map(|c| c.to_string())
-                        .unwrap_or_else(|| "_".to_string())
-                )?;
+                match self.field(x, y).get() {
+                    Some(value) => write!(f, "{value}")?,
+                    None => write!(f, "_")?,
+                }
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
+/// Boards are serialized as a string in the line format, see [crate::format_line]
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&crate::formats::format_line(self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let line = alloc::string::String::deserialize(deserializer)?;
+        crate::formats::parse_line(&line).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -276,6 +405,8 @@ mod tests {
                 assert!(board.field(x, y).is_empty());
             }
         }
+        assert_eq!(NUM_FIELDS, board.num_empty());
+        assert_eq!(0, board.num_filled());
         let mut board = board;
         for x in 0..WIDTH {
             for y in 0..HEIGHT {
@@ -299,6 +430,7 @@ mod tests {
         }
 
         let mut rng = StdRng::seed_from_u64(0);
+        let mut num_empty = 0;
         for x in 0..WIDTH {
             for y in 0..HEIGHT {
                 let expected = NonZeroU8::new(rng.gen_range(0..=9));
@@ -306,8 +438,118 @@ mod tests {
                 assert_eq!(expected, board.field_mut(x, y).get());
                 assert_eq!(expected.is_none(), board.field(x, y).is_empty());
                 assert_eq!(expected.is_none(), board.field_mut(x, y).is_empty());
+                num_empty += usize::from(expected.is_none());
+            }
+        }
+        assert_eq!(num_empty, board.num_empty());
+        assert_eq!(NUM_FIELDS - num_empty, board.num_filled());
+    }
+
+    #[test]
+    fn has_conflicts() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let naive_has_conflicts = |board: &Board| {
+            let houses = (0..9).flat_map(|index| {
+                [
+                    (0..9).map(|i| (i, index)).collect::<Vec<_>>(),
+                    (0..9).map(|i| (index, i)).collect(),
+                    (0..9).map(|i| (3 * (index % 3) + i % 3, 3 * (index / 3) + i / 3)).collect(),
+                ]
+            });
+            houses.into_iter().any(|house| {
+                let values: Vec<_> = house.iter().filter_map(|&(x, y)| board.field(x, y).get()).collect();
+                (0..values.len()).any(|i| values[i + 1..].contains(&values[i]))
+            })
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        for num_filled in 0..30 {
+            let mut board = Board::new_empty();
+            for _ in 0..num_filled {
+                board
+                    .field_mut(rng.gen_range(0..WIDTH), rng.gen_range(0..HEIGHT))
+                    .set(NonZeroU8::new(rng.gen_range(1..=9)));
+            }
+            assert_eq!(naive_has_conflicts(&board), board.has_conflicts(), "{board:?}");
+            assert_eq!(naive_has_conflicts(&board), board.has_conflicts_scalar(), "{board:?}");
+        }
+        let mut board = Board::new_empty();
+        board.field_mut(0, 0).set(NonZeroU8::new(5));
+        board.field_mut(4, 4).set(NonZeroU8::new(5));
+        board.field_mut(8, 8).set(NonZeroU8::new(5));
+        assert!(!board.has_conflicts());
+        board.field_mut(2, 2).set(NonZeroU8::new(5));
+        assert!(board.has_conflicts());
+    }
+
+    #[test]
+    fn is_subset_of() {
+        let mut lhs = Board::new_empty();
+        let mut rhs = Board::new_empty();
+        assert!(lhs.is_subset_of(&rhs));
+        rhs.field_mut(8, 8).set(NonZeroU8::new(9));
+        rhs.field_mut(3, 4).set(NonZeroU8::new(1));
+        assert!(lhs.is_subset_of(&rhs));
+        assert!(!rhs.is_subset_of(&lhs));
+        lhs.field_mut(8, 8).set(NonZeroU8::new(9));
+        assert!(lhs.is_subset_of(&rhs));
+        lhs.field_mut(3, 4).set(NonZeroU8::new(2));
+        assert!(!lhs.is_subset_of(&rhs));
+        lhs.field_mut(3, 4).set(NonZeroU8::new(1));
+        assert!(lhs.is_subset_of(&rhs));
+        assert!(rhs.is_subset_of(&lhs));
+        assert_eq!(lhs, rhs);
+        lhs.field_mut(3, 5).set(NonZeroU8::new(8));
+        assert!(!lhs.is_subset_of(&rhs));
+        assert_ne!(lhs, rhs);
+    }
+
+    #[test]
+    fn is_subset_of_scalar() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..200 {
+            let mut rhs = Board::new_empty();
+            for x in 0..WIDTH {
+                for y in 0..HEIGHT {
+                    rhs.field_mut(x, y).set(NonZeroU8::new(rng.gen_range(0..=9)));
+                }
+            }
+            let mut lhs = rhs;
+            for _ in 0..rng.gen_range(0..3) {
+                lhs.field_mut(rng.gen_range(0..WIDTH), rng.gen_range(0..HEIGHT))
+                    .set(NonZeroU8::new(rng.gen_range(0..=9)));
+            }
+            let naive_is_subset_of = (0..NUM_FIELDS).all(|index| {
+                let (x, y) = (index % WIDTH, index / WIDTH);
+                lhs.field(x, y).is_empty() || lhs.field(x, y).get() == rhs.field(x, y).get()
+            });
+            assert_eq!(naive_is_subset_of, lhs.is_subset_of(&rhs), "{lhs:?}\n{rhs:?}");
+            assert_eq!(naive_is_subset_of, lhs.is_subset_of_scalar(&rhs), "{lhs:?}\n{rhs:?}");
+        }
+    }
+
+    #[test]
+    fn first_empty_field_index() {
+        let mut board = Board::new_empty();
+        assert_eq!(Some((0, 0)), board.first_empty_field_index());
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                board.field_mut(x, y).set(NonZeroU8::new(9));
+                let expected = if (x, y) == (WIDTH - 1, HEIGHT - 1) {
+                    None
+                } else if y == HEIGHT - 1 {
+                    Some((x + 1, 0))
+                } else {
+                    Some((x, y + 1))
+                };
+                assert_eq!(expected, board.first_empty_field_index());
+                assert_eq!(expected.is_none(), board.is_filled());
             }
         }
+        board.field_mut(3, 4).set(None);
+        assert_eq!(Some((3, 4)), board.first_empty_field_index());
     }
 
     #[test]
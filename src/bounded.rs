@@ -0,0 +1,290 @@
+//! A solver that only uses fixed-size storage on the stack, for targets without an allocator.
+//! It only knows the classic rules and is slower than [solve](crate::solve), which also applies more strategies
+//! and reuses its state between guesses instead of copying it.
+
+use core::num::NonZeroU8;
+use thiserror::Error;
+
+use crate::board::{Board, HEIGHT, MAX_VALUE, NUM_FIELDS, WIDTH};
+use crate::geometry::{field_in_region, peers};
+
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundedSolverError {
+    #[error("Sudoku is not solvable")]
+    NotSolvable,
+
+    #[error("Sudoku has multiple valid solutions")]
+    Ambigious,
+
+    #[error("Sudoku has conflicting entries")]
+    Conflicting,
+
+    #[error("Solving the sudoku needs more than {0} nested guesses")]
+    TooManyGuesses(usize),
+}
+
+/// Like [solve](crate::solve), but only uses fixed-size storage on the stack, so it works without `alloc`.
+/// At most `MAX_DEPTH` guesses can be nested, each of them needs about 250 bytes of stack.
+/// Since every guess fills a field, a `MAX_DEPTH` of [NUM_FIELDS] always suffices, but most sudokus need much less.
+/// If the bound is exceeded, this returns [BoundedSolverError::TooManyGuesses].
+pub fn solve_bounded<const MAX_DEPTH: usize>(board: &Board) -> Result<Board, BoundedSolverError> {
+    if board.has_conflicts() {
+        return Err(BoundedSolverError::Conflicting);
+    }
+    let mut search = Search::<MAX_DEPTH>::new(board);
+    let solution = search.next_solution()?.ok_or(BoundedSolverError::NotSolvable)?;
+    match search.next_solution()? {
+        Some(_) => Err(BoundedSolverError::Ambigious),
+        None => Ok(solution),
+    }
+}
+
+/// The fields of each row, column and 3x3 region, as indices like in [State]
+const HOUSES: [[u8; 9]; 27] = {
+    let mut houses = [[0; 9]; 27];
+    let mut house = 0;
+    while house < 9 {
+        let mut i = 0;
+        while i < 9 {
+            houses[house][i] = (i * HEIGHT + house) as u8;
+            houses[9 + house][i] = (house * HEIGHT + i) as u8;
+            let (x, y) = field_in_region(house, i);
+            houses[18 + house][i] = (x * HEIGHT + y) as u8;
+            i += 1;
+        }
+        house += 1;
+    }
+    houses
+};
+
+/// The values and possible values of all fields, indexed by `x * HEIGHT + y`
+#[derive(Clone, Copy)]
+struct State {
+    values: [u8; NUM_FIELDS],
+
+    // Bit `value - 1` is set if the value is still possible. Filled fields have no possible values.
+    masks: [u16; NUM_FIELDS],
+}
+
+impl State {
+    const EMPTY: State = State {
+        values: [0; NUM_FIELDS],
+        masks: [0; NUM_FIELDS],
+    };
+
+    /// The state of a board without conflicts, before applying any strategies
+    fn new(board: &Board) -> Self {
+        let mut state = State {
+            values: [0; NUM_FIELDS],
+            masks: [(1 << MAX_VALUE) - 1; NUM_FIELDS],
+        };
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                if let Some(value) = board.field(x, y).get() {
+                    state.place(x * HEIGHT + y, value.get());
+                }
+            }
+        }
+        state
+    }
+
+    fn place(&mut self, index: usize, value: u8) {
+        self.values[index] = value;
+        self.masks[index] = 0;
+        for (x, y) in peers(index / HEIGHT, index % HEIGHT) {
+            self.masks[x * HEIGHT + y] &= !(1 << (value - 1));
+        }
+    }
+
+    /// Places naked and hidden singles until there are none left. Returns false if that shows the board isn't solvable.
+    fn propagate(&mut self) -> bool {
+        loop {
+            let mut found_something = false;
+            for index in 0..NUM_FIELDS {
+                if self.values[index] == 0 {
+                    match self.masks[index].count_ones() {
+                        0 => return false,
+                        1 => {
+                            self.place(index, self.masks[index].trailing_zeros() as u8 + 1);
+                            found_something = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            for house in &HOUSES {
+                for value in 1..=MAX_VALUE {
+                    let bit = 1 << (value - 1);
+                    if house.iter().any(|&index| self.values[usize::from(index)] == value) {
+                        continue;
+                    }
+                    let mut candidates = house.iter().filter(|&&index| self.masks[usize::from(index)] & bit != 0);
+                    match (candidates.next(), candidates.next()) {
+                        (None, _) => return false,
+                        (Some(&index), None) => {
+                            self.place(usize::from(index), value);
+                            found_something = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            if !found_something {
+                return true;
+            }
+        }
+    }
+
+    /// The empty field with the fewest possible values, or `None` if the board is filled
+    fn field_to_guess(&self) -> Option<usize> {
+        (0..NUM_FIELDS)
+            .filter(|&index| self.values[index] == 0)
+            .min_by_key(|&index| self.masks[index].count_ones())
+    }
+
+    fn board(&self) -> Board {
+        let mut board = Board::new_empty();
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                board.field_mut(x, y).set(NonZeroU8::new(self.values[x * HEIGHT + y]));
+            }
+        }
+        board
+    }
+}
+
+/// A branching point of the search, i.e. the state before guessing a value for `index`,
+/// and the values that haven't been tried yet
+#[derive(Clone, Copy)]
+struct Frame {
+    state: State,
+    index: u8,
+    remaining: u16,
+}
+
+struct Search<const MAX_DEPTH: usize> {
+    stack: [Frame; MAX_DEPTH],
+    depth: usize,
+
+    // The state after the last successful guess, with the strategies applied
+    state: State,
+
+    // True if [state] still has to be checked for being solved, or for the next field to guess
+    is_new: bool,
+}
+
+impl<const MAX_DEPTH: usize> Search<MAX_DEPTH> {
+    fn new(board: &Board) -> Self {
+        let mut state = State::new(board);
+        let is_new = state.propagate();
+        Self {
+            stack: [Frame {
+                state: State::EMPTY,
+                index: 0,
+                remaining: 0,
+            }; MAX_DEPTH],
+            depth: 0,
+            state,
+            is_new,
+        }
+    }
+
+    fn next_solution(&mut self) -> Result<Option<Board>, BoundedSolverError> {
+        loop {
+            if self.is_new {
+                self.is_new = false;
+                let Some(index) = self.state.field_to_guess() else {
+                    return Ok(Some(self.state.board()));
+                };
+                if self.depth == MAX_DEPTH {
+                    return Err(BoundedSolverError::TooManyGuesses(MAX_DEPTH));
+                }
+                self.stack[self.depth] = Frame {
+                    state: self.state,
+                    index: index as u8,
+                    remaining: self.state.masks[index],
+                };
+                self.depth += 1;
+            }
+
+            // Try the next value at the innermost branching point, or go back to the previous one if none is left
+            let Some(frame) = self.depth.checked_sub(1).map(|top| &mut self.stack[top]) else {
+                return Ok(None);
+            };
+            if frame.remaining == 0 {
+                self.depth -= 1;
+                continue;
+            }
+            let value = frame.remaining.trailing_zeros() as u8 + 1;
+            frame.remaining &= frame.remaining - 1;
+            let mut state = frame.state;
+            state.place(usize::from(frame.index), value);
+            if state.propagate() {
+                self.state = state;
+                self.is_new = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLVABLE: &str = "
+        __4 68_ _19
+        __3 __9 2_5
+        _6_ ___ __4
+
+        6__ ___ 7_2
+        ___ __7 ___
+        ___ 9__ __1
+
+        8__ _5_ __7
+        _41 3_8 ___
+        _2_ _91 ___
+    ";
+
+    #[test]
+    fn solvable() {
+        let board = Board::from_str(SOLVABLE);
+        let solution = solve_bounded::<NUM_FIELDS>(&board).unwrap();
+        assert!(solution.is_filled());
+        assert!(!solution.has_conflicts());
+        assert!(board.is_subset_of(&solution));
+    }
+
+    #[test]
+    fn errors() {
+        let mut not_solvable = Board::from_str(SOLVABLE);
+        not_solvable.field_mut(4, 4).set(NonZeroU8::new(2));
+        assert_eq!(Err(BoundedSolverError::NotSolvable), solve_bounded::<NUM_FIELDS>(&not_solvable));
+
+        let mut conflicting = Board::from_str(SOLVABLE);
+        conflicting.field_mut(1, 3).set(NonZeroU8::new(7));
+        assert_eq!(Err(BoundedSolverError::Conflicting), solve_bounded::<NUM_FIELDS>(&conflicting));
+
+        assert_eq!(Err(BoundedSolverError::Ambigious), solve_bounded::<NUM_FIELDS>(&Board::new_empty()));
+        assert_eq!(Err(BoundedSolverError::TooManyGuesses(2)), solve_bounded::<2>(&Board::new_empty()));
+    }
+
+    #[cfg(all(feature = "std", feature = "rand"))]
+    #[test]
+    fn agrees_with_solve() {
+        for seed in 0..20 {
+            let board = crate::generate_symmetric_seeded(crate::Symmetry::None, seed);
+            let mut ambigious = board;
+            let (x, y) = (0..NUM_FIELDS)
+                .map(|index| (index / HEIGHT, index % HEIGHT))
+                .find(|&(x, y)| board.field(x, y).get().is_some())
+                .unwrap();
+            ambigious.field_mut(x, y).set(None);
+            for board in [board, ambigious] {
+                assert_eq!(
+                    crate::solve(board).map_err(|error| error.to_string()),
+                    solve_bounded::<NUM_FIELDS>(&board).map_err(|error| error.to_string())
+                );
+            }
+        }
+    }
+}
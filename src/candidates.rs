@@ -0,0 +1,98 @@
+use core::num::NonZeroU8;
+
+use crate::bitboard::fields_of_bitboard;
+use crate::board::Board;
+use crate::constraints::Constraint;
+use crate::solver::possible_values::PossibleValues;
+
+/// [Candidates] are the values that are still possible in each empty cell of a board, after removing the values of
+/// all peers, i.e. the same eliminations the solver starts with. Filled cells don't have any candidates.
+///
+/// This is read-only, so UIs and other tools can show candidates without reimplementing the elimination rules.
+#[derive(Clone)]
+pub struct Candidates {
+    possible_values: PossibleValues,
+}
+
+impl Candidates {
+    /// The candidates of a classic sudoku
+    pub fn new(board: &Board) -> Self {
+        Self::with_constraints(board, &[])
+    }
+
+    /// The candidates of a sudoku variant, which also takes the peers added by the constraints into account
+    pub fn with_constraints(board: &Board, constraints: &[Constraint]) -> Self {
+        Self {
+            possible_values: PossibleValues::from_board(board, constraints),
+        }
+    }
+
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn from_possible_values(possible_values: PossibleValues) -> Self {
+        Self { possible_values }
+    }
+
+    /// The candidates of the cell in ascending order
+    pub fn get(&self, x: usize, y: usize) -> impl Iterator<Item = NonZeroU8> + '_ {
+        self.possible_values.possible_values_for_field(x, y)
+    }
+
+    /// The candidates of the cell as a mask where bit `value - 1` is set if the value is a candidate
+    pub fn mask(&self, x: usize, y: usize) -> u16 {
+        self.possible_values.mask(x, y)
+    }
+
+    /// The number of candidates of the cell
+    pub fn count(&self, x: usize, y: usize) -> usize {
+        self.possible_values.candidate_count(x, y)
+    }
+
+    pub fn contains(&self, x: usize, y: usize, value: NonZeroU8) -> bool {
+        self.possible_values.is_possible(x, y, value)
+    }
+
+    /// The cells that have the value as a candidate, column by column
+    pub fn cells_with(&self, value: NonZeroU8) -> impl Iterator<Item = (usize, usize)> {
+        fields_of_bitboard(self.possible_values.fields_with_value(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(value: u8) -> NonZeroU8 {
+        NonZeroU8::new(value).unwrap()
+    }
+
+    #[test]
+    fn eliminates_peers() {
+        let board = Board::from_str(
+            "
+            53_ _7_ ___
+            6__ 195 ___
+            _98 ___ _6_
+
+            8__ _6_ __3
+            4__ 8_3 __1
+            7__ _2_ __6
+
+            _6_ ___ 28_
+            ___ 419 __5
+            ___ _8_ _79
+        ",
+        );
+        let candidates = Candidates::new(&board);
+        assert_eq!(0, candidates.count(0, 0));
+        assert_eq!(vec![value(1), value(2), value(4)], candidates.get(2, 0).collect::<Vec<_>>());
+        assert_eq!(0b1011, candidates.mask(2, 0));
+        assert!(candidates.contains(2, 0, value(4)));
+        assert!(!candidates.contains(2, 0, value(3)));
+        assert!(candidates.cells_with(value(5)).all(|(x, y)| board.field(x, y).is_empty()));
+        assert!(!candidates.cells_with(value(5)).any(|(x, y)| y == 0 || x == 0));
+
+        let anti_king = Candidates::with_constraints(&board, &[Constraint::AntiKing]);
+        // The 1 in r2c4 touches r1c3
+        assert_eq!(vec![value(2), value(4)], anti_king.get(2, 0).collect::<Vec<_>>());
+    }
+}
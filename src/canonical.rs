@@ -0,0 +1,223 @@
+use std::fmt::{self, Display};
+use std::num::NonZeroU8;
+use std::str::FromStr;
+
+use crate::board::{Board, HEIGHT, NUM_FIELDS, WIDTH};
+
+const PERMUTATIONS_OF_3: [[usize; 3]; 6] = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+
+type Grid = [[u8; WIDTH]; HEIGHT];
+
+/// Returns the canonical form of a board. Two boards have the same canonical form if and only if one can be transformed
+/// into the other with the transformations that keep a sudoku valid: Relabeling the values, transposing the board,
+/// swapping bands (rows of 3x3 regions) or stacks (columns of 3x3 regions), and swapping rows within a band or
+/// columns within a stack.
+///
+/// The canonical form is the transformed board that is the smallest when read row by row, with empty cells being
+/// smaller than values and values relabeled in the order they first appear.
+/// This tries all 3,359,232 transformations, but most of them are discarded after looking at the first few cells.
+pub fn canonical_form(board: &Board) -> Board {
+    let grid = to_grid(board);
+    let line_orders = line_orders();
+    let mut best = [u8::MAX; NUM_FIELDS];
+    for grid in [grid, transpose(&grid)] {
+        for rows in &line_orders {
+            for columns in &line_orders {
+                keep_if_smaller(&grid, rows, columns, &mut best);
+            }
+        }
+    }
+    let mut canonical = Board::new_empty();
+    for (index, value) in best.into_iter().enumerate() {
+        canonical.field_mut(index % WIDTH, index / WIDTH).set(NonZeroU8::new(value));
+    }
+    canonical
+}
+
+/// Returns true if one of the boards can be transformed into the other, see [canonical_form]
+pub fn is_equivalent(lhs: &Board, rhs: &Board) -> bool {
+    lhs.num_empty() == rhs.num_empty() && canonical_form(lhs) == canonical_form(rhs)
+}
+
+/// A short hash of the [canonical_form] of a board, so boards that can be transformed into each other have the same
+/// fingerprint. Unlike the [Hash] of the board, it doesn't depend on the platform or the version of this crate,
+/// so it can be stored, e.g. to find puzzles that were republished in a transformed way.
+/// Displayed as 16 hex digits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fingerprint(pub u64);
+
+impl Board {
+    /// The [Fingerprint] of the board. This needs the [canonical_form], so it's just as expensive.
+    pub fn fingerprint(&self) -> Fingerprint {
+        // 64 bit FNV-1a over the canonical form read row by row
+        let canonical = canonical_form(self);
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                hash ^= u64::from(canonical.field(x, y).get().map(NonZeroU8::get).unwrap_or(0));
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        Fingerprint(hash)
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 16 {
+            return Err(format!("Invalid fingerprint '{s}', expected 16 hex digits"));
+        }
+        u64::from_str_radix(s, 16)
+            .map(Fingerprint)
+            .map_err(|_| format!("Invalid fingerprint '{s}', expected 16 hex digits"))
+    }
+}
+
+fn to_grid(board: &Board) -> Grid {
+    let mut grid = [[0; WIDTH]; HEIGHT];
+    for (y, row) in grid.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            *cell = board.field(x, y).get().map(NonZeroU8::get).unwrap_or(0);
+        }
+    }
+    grid
+}
+
+fn transpose(grid: &Grid) -> Grid {
+    let mut transposed = [[0; WIDTH]; HEIGHT];
+    for (y, row) in grid.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            transposed[x][y] = *cell;
+        }
+    }
+    transposed
+}
+
+/// All 1296 orders of the 9 rows (or columns) that keep a sudoku valid, i.e. the bands can be reordered
+/// and the rows within each band can be reordered
+fn line_orders() -> Vec<[usize; 9]> {
+    let mut orders = Vec::with_capacity(1296);
+    for bands in PERMUTATIONS_OF_3 {
+        for first in PERMUTATIONS_OF_3 {
+            for second in PERMUTATIONS_OF_3 {
+                for third in PERMUTATIONS_OF_3 {
+                    let mut order = [0; 9];
+                    for (band_index, lines) in [first, second, third].into_iter().enumerate() {
+                        for (line_index, line) in lines.into_iter().enumerate() {
+                            order[3 * band_index + line_index] = 3 * bands[band_index] + line;
+                        }
+                    }
+                    orders.push(order);
+                }
+            }
+        }
+    }
+    orders
+}
+
+/// Applies the row and column order to the grid, relabels the values in order of appearance,
+/// and overwrites `best` with the result if it is smaller.
+fn keep_if_smaller(grid: &Grid, rows: &[usize; 9], columns: &[usize; 9], best: &mut [u8; NUM_FIELDS]) {
+    let mut labels = [0u8; 10];
+    let mut next_label = 1;
+    let mut is_smaller = false;
+    for (index, best_value) in best.iter_mut().enumerate() {
+        let value = grid[rows[index / WIDTH]][columns[index % WIDTH]];
+        let label = if value == 0 {
+            0
+        } else {
+            let label = &mut labels[usize::from(value)];
+            if *label == 0 {
+                *label = next_label;
+                next_label += 1;
+            }
+            *label
+        };
+        if !is_smaller {
+            if label > *best_value {
+                return;
+            }
+            is_smaller = label < *best_value;
+        }
+        // Since the prefix so far is equal, we can overwrite `best` as we go. Once we found a smaller value,
+        // the rest of `best` doesn't matter anymore.
+        *best_value = label;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = "
+        __4 68_ _19
+        __3 __9 2_5
+        _6_ ___ __4
+
+        6__ ___ 7_2
+        ___ __7 ___
+        ___ 9__ __1
+
+        8__ _5_ __7
+        _41 3_8 ___
+        _2_ _91 ___
+    ";
+
+    // [BOARD] transposed, with the first two bands swapped, the last two columns swapped, and 1 and 2 swapped
+    const TRANSFORMED: &str = "
+        6__ __9 __3
+        8__ ___ 59_
+        _9_ _7_ _28
+
+        ___ 6__ 8__
+        __6 ___ _14
+        43_ ___ __2
+
+        _1_ 7__ ___
+        2__ ___ ___
+        954 1_2 7__
+    ";
+
+    #[test]
+    fn transformed_boards_are_equivalent() {
+        let board = Board::from_str(BOARD);
+        let transformed = Board::from_str(TRANSFORMED);
+        assert_ne!(board, transformed);
+        assert_eq!(canonical_form(&board), canonical_form(&transformed));
+        assert!(is_equivalent(&board, &transformed));
+        assert_eq!(canonical_form(&board), canonical_form(&canonical_form(&board)));
+    }
+
+    #[test]
+    fn fingerprint() {
+        let board = Board::from_str(BOARD);
+        let fingerprint = board.fingerprint();
+        assert_eq!(fingerprint, Board::from_str(TRANSFORMED).fingerprint());
+        assert_eq!(fingerprint, canonical_form(&board).fingerprint());
+        // Stored fingerprints must stay valid
+        assert_eq!("0d8a29c02e1b1e0d", fingerprint.to_string());
+        assert_eq!(Ok(fingerprint), fingerprint.to_string().parse());
+        assert!("0d8a29c0".parse::<Fingerprint>().is_err());
+
+        let mut other = board;
+        other.field_mut(0, 0).set(NonZeroU8::new(1));
+        assert_ne!(fingerprint, other.fingerprint());
+    }
+
+    #[test]
+    fn different_boards_arent_equivalent() {
+        let board = Board::from_str(BOARD);
+        let mut other = board;
+        other.field_mut(0, 0).set(NonZeroU8::new(1));
+        assert!(!is_equivalent(&board, &other));
+    }
+}
@@ -0,0 +1,390 @@
+//! Searches that can run for hours, e.g. on preemptible machines. They run in steps and can be written to a
+//! checkpoint file between them. Loading the checkpoint continues the search where it was saved.
+//!
+//! A checkpoint is a text file with one `key value` pair per line, e.g.
+//! ```text
+//! search max-empty
+//! constraint anti-knight
+//! start 123456789...
+//! seed 1242
+//! attempts 96
+//! best 1.3..6...
+//! ```
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::fs;
+use std::io;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::board::{Board, HEIGHT, WIDTH};
+use crate::constraints::Constraint;
+use crate::formats::{format_line, parse_line};
+#[cfg(feature = "rand")]
+use crate::generator::{remove_fields, seeded_rng, Symmetry};
+use crate::solver::possible_values::PossibleValues;
+use crate::solver::{first_invalid, Solver};
+#[cfg(feature = "rand")]
+use crate::utils::SplitMix64;
+
+/// Boards with at most this many empty fields are searched for solutions in one go instead of being split further
+const MAX_EMPTY_TO_ENUMERATE: usize = 40;
+
+#[derive(Error, Debug)]
+pub enum CheckpointError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error("Line {line}: {message}")]
+    Invalid { line: usize, message: String },
+}
+
+/// Searches for a puzzle with as many empty fields as possible that still has a unique solution, like
+/// [generate_max_empty](crate::generate_max_empty): each attempt removes fields in a random order for as long as the
+/// solution stays unique, and the search keeps the puzzle with the most empty fields. No attempt proves that a better
+/// puzzle doesn't exist, so the search never ends by itself, it makes as many attempts as it's asked to.
+///
+/// The random order of an attempt only depends on the seed of the search and the index of the attempt, so a
+/// checkpoint is just the seed, the number of attempts so far and the best puzzle. A loaded search continues with
+/// the same attempts it would have made without the interruption.
+#[cfg(feature = "rand")]
+pub struct MaxEmptySearch {
+    constraints: Vec<Constraint>,
+
+    /// The board that each attempt removes fields from
+    start: Board,
+
+    seed: u64,
+    attempts: u64,
+    best: Board,
+}
+
+#[cfg(feature = "rand")]
+impl MaxEmptySearch {
+    /// Starts a search that removes fields from the given board, e.g. a solved one. It must have a unique solution.
+    pub fn new(board: Board, constraints: &[Constraint], seed: u64) -> Self {
+        Self {
+            constraints: constraints.to_vec(),
+            start: board,
+            seed,
+            attempts: 0,
+            best: board,
+        }
+    }
+
+    /// The puzzle with the most empty fields found so far
+    pub fn best(&self) -> Board {
+        self.best
+    }
+
+    /// The number of attempts so far, including before the checkpoint was saved
+    pub fn attempts(&self) -> u64 {
+        self.attempts
+    }
+
+    /// Makes the given number of attempts, in parallel with the `parallel` feature.
+    /// Returns true if one of them found a puzzle with more empty fields than the best one so far.
+    pub fn step(&mut self, attempts: usize) -> bool {
+        let indices = self.attempts..self.attempts + attempts as u64;
+        #[cfg(feature = "parallel")]
+        let indices = indices.into_par_iter();
+        let boards: Vec<Board> = indices
+            .map(|index| {
+                let mut rng = seeded_rng(attempt_seed(self.seed, index));
+                remove_fields(self.start, &self.constraints, Symmetry::None, &mut rng)
+            })
+            .collect();
+        self.attempts += attempts as u64;
+        // Keep the first of the boards with the most empty fields, so the result doesn't depend on the thread count
+        let mut found_better = false;
+        for board in boards {
+            if board.num_empty() > self.best.num_empty() {
+                self.best = board;
+                found_better = true;
+            }
+        }
+        found_better
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut lines = header("max-empty", &self.constraints);
+        lines.push(format!("start {}", format_line(&self.start)));
+        lines.push(format!("seed {}", self.seed));
+        lines.push(format!("attempts {}", self.attempts));
+        lines.push(format!("best {}", format_line(&self.best)));
+        write_atomically(path.as_ref(), &lines)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        let content = fs::read_to_string(path)?;
+        let mut constraints = vec![];
+        let (mut start, mut seed, mut attempts, mut best) = (None, None, 0, None);
+        for (line, key, value) in parse_lines(&content, "max-empty")? {
+            match key {
+                "constraint" => constraints.push(parse_constraint(line, value)?),
+                "start" => start = Some(parse_board(line, value)?),
+                "seed" => seed = Some(value.parse().map_err(|_| invalid(line, "Invalid seed"))?),
+                "attempts" => attempts = value.parse().map_err(|_| invalid(line, "Invalid number"))?,
+                "best" => best = Some(parse_board(line, value)?),
+                _ => return Err(invalid(line, &format!("Unknown key '{key}'"))),
+            }
+        }
+        let last_line = content.lines().count();
+        let start = start.ok_or_else(|| invalid(last_line, "Missing 'start'"))?;
+        Ok(Self {
+            constraints,
+            start,
+            seed: seed.ok_or_else(|| invalid(last_line, "Missing 'seed'"))?,
+            attempts,
+            best: best.unwrap_or(start),
+        })
+    }
+}
+
+/// Hashes the seed of the search and the index of the attempt with SplitMix64, like the puzzle seeds of a
+/// [Manifest](crate::Manifest). This must never change, otherwise loaded checkpoints continue with other attempts.
+#[cfg(feature = "rand")]
+fn attempt_seed(seed: u64, index: u64) -> u64 {
+    SplitMix64(SplitMix64(seed).next() ^ index).next()
+}
+
+/// Enumerates all solutions of a board, like [solutions_parallel](crate::solutions_parallel), but in steps that
+/// return the solutions they found, so that the search can be checkpointed between them.
+pub struct SolutionSearch {
+    constraints: Vec<Constraint>,
+
+    /// The number of solutions returned by earlier steps
+    found: u64,
+
+    /// Partially filled boards whose solutions haven't been searched yet. Their solutions are disjoint.
+    frontier: Vec<Board>,
+}
+
+impl SolutionSearch {
+    /// Starts the search. Boards with conflicts or an invalid constraint have no solutions.
+    pub fn new(board: Board, constraints: &[Constraint]) -> Self {
+        let solvable = first_invalid(constraints).is_none() && !board.has_conflicts_with(constraints);
+        let frontier = if solvable { vec![board] } else { vec![] };
+        Self {
+            constraints: constraints.to_vec(),
+            found: 0,
+            frontier,
+        }
+    }
+
+    /// The number of solutions returned by [SolutionSearch::step] so far, including before the checkpoint was saved
+    pub fn found(&self) -> u64 {
+        self.found
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.frontier.is_empty()
+    }
+
+    /// Searches up to `max_nodes` boards of the frontier, in parallel with the `parallel` feature, and returns the
+    /// solutions found. Handle the solutions before saving a checkpoint, since they won't be returned again after
+    /// loading it.
+    pub fn step(&mut self, max_nodes: usize) -> Vec<Board> {
+        let nodes = self.frontier.split_off(self.frontier.len().saturating_sub(max_nodes));
+        #[cfg(feature = "parallel")]
+        let nodes = nodes.into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let nodes = nodes.into_iter();
+        let results: Vec<(Vec<Board>, Vec<Board>)> =
+            nodes.map(|board| expand_solutions(board, &self.constraints)).collect();
+        let mut solutions = vec![];
+        for (node_solutions, children) in results.into_iter().rev() {
+            solutions.extend(node_solutions);
+            self.frontier.extend(children.into_iter().rev());
+        }
+        self.found += solutions.len() as u64;
+        solutions
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut lines = header("solutions", &self.constraints);
+        lines.push(format!("found {}", self.found));
+        lines.extend(self.frontier.iter().map(|board| format!("frontier {}", format_line(board))));
+        write_atomically(path.as_ref(), &lines)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        let content = fs::read_to_string(path)?;
+        let mut search = Self {
+            constraints: vec![],
+            found: 0,
+            frontier: vec![],
+        };
+        for (line, key, value) in parse_lines(&content, "solutions")? {
+            match key {
+                "constraint" => search.constraints.push(parse_constraint(line, value)?),
+                "found" => search.found = value.parse().map_err(|_| invalid(line, "Invalid number"))?,
+                "frontier" => search.frontier.push(parse_board(line, value)?),
+                _ => return Err(invalid(line, &format!("Unknown key '{key}'"))),
+            }
+        }
+        Ok(search)
+    }
+}
+
+/// Returns the solutions of a board with few empty fields, or splits it into one board per possible value of the
+/// field with the fewest possible values
+fn expand_solutions(mut board: Board, constraints: &[Constraint]) -> (Vec<Board>, Vec<Board>) {
+    if board.num_empty() <= MAX_EMPTY_TO_ENUMERATE {
+        let mut solver = Solver::new(board, constraints.to_vec());
+        return (core::iter::from_fn(|| solver.next_solution()).collect(), vec![]);
+    }
+    let possible_values = PossibleValues::from_board(&board, constraints);
+    let empty_fields = (0..HEIGHT).flat_map(|y| (0..WIDTH).map(move |x| (x, y)));
+    let Some((x, y)) = empty_fields
+        .filter(|&(x, y)| board.field(x, y).is_empty())
+        .min_by_key(|&(x, y)| possible_values.candidate_count(x, y))
+    else {
+        return (vec![], vec![]);
+    };
+    let children = possible_values
+        .possible_values_for_field(x, y)
+        .map(|value| {
+            board.field_mut(x, y).set(Some(value));
+            board
+        })
+        .collect();
+    (vec![], children)
+}
+
+fn header(search: &str, constraints: &[Constraint]) -> Vec<String> {
+    let mut lines = vec![format!("search {search}")];
+    lines.extend(constraints.iter().map(|constraint| format!("constraint {constraint}")));
+    lines
+}
+
+/// Writes to a temporary file first, so that an interruption while saving leaves the previous checkpoint intact
+fn write_atomically(path: &Path, lines: &[String]) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, lines.join("\n") + "\n")?;
+    fs::rename(temporary, path)
+}
+
+/// Returns the 1-based line number, key and value of each line after the `search` line, which must name the search
+fn parse_lines<'a>(content: &'a str, search: &str) -> Result<Vec<(usize, &'a str, &'a str)>, CheckpointError> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let (key, value) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+            (index + 1, key, value.trim())
+        });
+    match lines.next() {
+        Some((_, "search", value)) if value == search => Ok(lines.collect()),
+        Some((line, _, _)) => Err(invalid(line, &format!("Expected 'search {search}'"))),
+        None => Err(invalid(1, "Empty checkpoint")),
+    }
+}
+
+fn parse_board(line: usize, value: &str) -> Result<Board, CheckpointError> {
+    parse_line(value).map_err(|error| invalid(line, &error.to_string()))
+}
+
+fn parse_constraint(line: usize, value: &str) -> Result<Constraint, CheckpointError> {
+    value.parse().map_err(|error: crate::constraints::ConstraintParseError| invalid(line, &error.to_string()))
+}
+
+fn invalid(line: usize, message: &str) -> CheckpointError {
+    CheckpointError::Invalid {
+        line,
+        message: message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{find_solutions, solve};
+
+    fn temporary_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sudoku-checkpoint-{}-{name}", std::process::id()))
+    }
+
+    /// A puzzle with 17 given fields, none of which can be removed
+    const MINIMAL: &str = ".......1.4.........2...........5.4.7..8...3....1.9....3..4..2...5.1........8.6...";
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn max_empty_resumes() {
+        let puzzle = parse_line(MINIMAL).unwrap();
+        let solution = solve(puzzle).unwrap();
+        let mut start = puzzle;
+        for (x, y) in [(0, 0), (4, 1), (8, 2), (2, 4), (6, 6), (3, 8)] {
+            start.field_mut(x, y).set(solution.field(x, y).get());
+        }
+        let mut search = MaxEmptySearch::new(start, &[], 1242);
+        search.step(4);
+        let path = temporary_path("max-empty");
+        search.save(&path).unwrap();
+        let mut resumed = MaxEmptySearch::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(search.start, resumed.start);
+        assert_eq!(search.best(), resumed.best());
+        assert_eq!(4, resumed.attempts());
+        resumed.step(4);
+
+        let mut uninterrupted = MaxEmptySearch::new(start, &[], 1242);
+        uninterrupted.step(8);
+        assert_eq!(uninterrupted.best(), resumed.best());
+        assert!(resumed.best().num_empty() > start.num_empty());
+        assert_eq!(Ok(solution), solve(resumed.best()));
+    }
+
+    #[test]
+    fn solutions_resume() {
+        let mut board = parse_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79").unwrap();
+        for (x, y) in [(0, 0), (1, 0), (4, 0), (0, 1), (3, 1), (4, 1), (5, 1)] {
+            board.field_mut(x, y).set(None);
+        }
+        let mut expected = find_solutions(board, usize::MAX);
+        assert!(expected.len() > 1);
+
+        let mut search = SolutionSearch::new(board, &[]);
+        let mut solutions = search.step(1);
+        let path = temporary_path("solutions");
+        search.save(&path).unwrap();
+        let mut resumed = SolutionSearch::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(search.found(), resumed.found());
+        assert_eq!(search.frontier, resumed.frontier);
+        while !resumed.is_done() {
+            solutions.extend(resumed.step(16));
+        }
+        assert_eq!(expected.len() as u64, resumed.found());
+        solutions.sort_by_key(format_line);
+        expected.sort_by_key(format_line);
+        assert_eq!(expected, solutions);
+    }
+
+    #[test]
+    fn constraints_are_saved() {
+        let search = SolutionSearch::new(Board::new_empty(), &[Constraint::AntiKing, Constraint::NonConsecutive]);
+        let path = temporary_path("constraints");
+        search.save(&path).unwrap();
+        let resumed = SolutionSearch::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(search.constraints, resumed.constraints);
+    }
+
+    #[test]
+    fn invalid_checkpoints() {
+        let path = temporary_path("invalid");
+        fs::write(&path, "search solutions\nbest ...").unwrap();
+        assert!(matches!(SolutionSearch::load(&path), Err(CheckpointError::Invalid { line: 2, .. })));
+        #[cfg(feature = "rand")]
+        {
+            assert!(matches!(MaxEmptySearch::load(&path), Err(CheckpointError::Invalid { line: 1, .. })));
+            fs::write(&path, "search max-empty\nseed 1242\n").unwrap();
+            assert!(matches!(MaxEmptySearch::load(&path), Err(CheckpointError::Invalid { line: 2, .. })));
+        }
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,55 @@
+use sudoku::{format_line, rate, Step, Technique};
+
+use super::input::InputArgs;
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Also print every step of the solving path with an explanation
+    #[arg(long, short)]
+    steps: bool,
+}
+
+pub fn run(args: Args) -> Result<(), CliError> {
+    let boards = args.input.read_puzzles()?;
+    for (index, board) in boards.iter().enumerate() {
+        if boards.len() > 1 {
+            if index > 0 {
+                println!();
+            }
+            println!("{}", format_line(board));
+        }
+        let report = rate(board)?;
+        println!("Difficulty: {}", report.difficulty);
+        println!("Techniques in order of use:");
+        for (technique, count) in techniques_in_order(&report.steps) {
+            println!("  {technique} ({count}x)");
+        }
+        if report.requires_guessing {
+            println!("  Guessing");
+        }
+        if args.steps {
+            println!("Steps:");
+            for (number, step) in report.steps.iter().enumerate() {
+                println!("  {:>2}. {}", number + 1, step.explanation());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Groups consecutive steps using the same technique, e.g. to show that hidden singles were used until a
+/// naked single was needed, after which hidden singles were enough again.
+fn techniques_in_order(steps: &[Step]) -> Vec<(Technique, usize)> {
+    let mut techniques: Vec<(Technique, usize)> = vec![];
+    for step in steps {
+        match techniques.last_mut() {
+            Some((technique, count)) if *technique == step.technique => *count += 1,
+            _ => techniques.push((step.technique, 1)),
+        }
+    }
+    techniques
+}
@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+use sudoku::{corpus, solve_with_backend, solve_with_config, Backend, Branching, SearchConfig, ValueOrder};
+
+use super::input::CollectionArgs;
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: CollectionArgs,
+
+    /// Solve one of the sets embedded into the library instead of reading puzzles
+    #[arg(long, conflicts_with = "file", value_parser = clap::builder::PossibleValuesParser::new(corpus::NAMES))]
+    corpus: Option<String>,
+
+    /// The backends to compare: backtracking or dlx
+    #[arg(long, value_delimiter = ',', default_values_t = Backend::ALL)]
+    backends: Vec<Backend>,
+
+    /// How the backtracking backend picks the field to guess: auto, first-empty or fewest-candidates
+    #[arg(long, default_value_t = Branching::Auto)]
+    branching: Branching,
+
+    /// In which order the backtracking backend guesses values: ascending, descending or random
+    #[arg(long, default_value_t = ValueOrder::Ascending)]
+    value_order: ValueOrder,
+
+    /// How often the backtracking backend applies the simple strategies after each guess
+    #[arg(long, default_value_t = 1)]
+    propagation_passes: usize,
+
+    /// Makes the backtracking backend start over after this many guesses, doubling the limit each time
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+    restart_after: Option<u64>,
+
+    /// Seed for the random value order
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+struct BackendResult {
+    backend: Backend,
+    time: Duration,
+    nodes: u64,
+    failed: usize,
+}
+
+pub fn run(args: Args) -> Result<(), CliError> {
+    let boards = match &args.corpus {
+        Some(name) => corpus::by_name(name).expect("Clap only accepts the names of embedded sets"),
+        None => args.input.read_puzzles()?,
+    };
+    let config = SearchConfig {
+        branching: args.branching,
+        value_order: args.value_order,
+        propagation_passes: args.propagation_passes,
+        restart_after: args.restart_after,
+        seed: args.seed,
+    };
+    // Puzzles are solved one after the other on a single thread so the timings are comparable
+    let results: Vec<BackendResult> = args
+        .backends
+        .iter()
+        .map(|&backend| {
+            let mut result = BackendResult {
+                backend,
+                time: Duration::ZERO,
+                nodes: 0,
+                failed: 0,
+            };
+            for board in &boards {
+                let start = Instant::now();
+                let (solution, stats) = match backend {
+                    Backend::Backtracking => solve_with_config(*board, &[], config),
+                    Backend::Dlx => solve_with_backend(*board, backend),
+                };
+                result.time += start.elapsed();
+                result.nodes += stats.nodes;
+                if solution.is_err() {
+                    result.failed += 1;
+                }
+            }
+            result
+        })
+        .collect();
+
+    println!("Puzzles: {}", boards.len());
+    println!(
+        "{:<14} {:>12} {:>14} {:>14} {:>12}",
+        "backend", "total", "per puzzle", "nodes", "no solution"
+    );
+    let num_boards = boards.len().max(1);
+    for result in results {
+        println!(
+            "{:<14} {:>12} {:>14} {:>14} {:>12}",
+            result.backend.name(),
+            format!("{:.2?}", result.time),
+            format!("{:.2?}", result.time / num_boards as u32),
+            result.nodes,
+            result.failed
+        );
+    }
+    Ok(())
+}
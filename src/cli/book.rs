@@ -0,0 +1,131 @@
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::path::PathBuf;
+use sudoku::{generate_with_difficulty_seeded, render, solve, Difficulty, Figure, RenderFormat, Symmetry};
+
+use super::export::write_output;
+use super::{seed_or_random, CliError};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Number of puzzles in the book
+    #[arg(long, short = 'n', default_value_t = 100)]
+    count: usize,
+
+    /// Share of each difficulty, e.g. `easy:40,medium:40,hard:20`. The shares are relative to each other,
+    /// so `easy:1,hard:1` means half of the puzzles are easy and half are hard.
+    #[arg(long, default_value = "easy:30,medium:40,hard:30", value_parser = parse_difficulties)]
+    difficulties: DifficultyShares,
+
+    /// The format of the book: svg, pdf, latex or html
+    #[arg(long, default_value_t = RenderFormat::Pdf)]
+    format: RenderFormat,
+
+    /// The file to write to. If not given, the book is written to stdout.
+    #[arg(long, short)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DifficultyShares(Vec<(Difficulty, usize)>);
+
+fn parse_difficulties(s: &str) -> Result<DifficultyShares, String> {
+    let shares = s
+        .split(',')
+        .map(|share| {
+            let (difficulty, weight) = share
+                .split_once(':')
+                .ok_or_else(|| format!("Expected 'difficulty:share' but found '{share}'"))?;
+            let weight = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid share '{weight}'"))?;
+            Ok((difficulty.trim().parse()?, weight))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    if shares.iter().all(|(_, weight)| *weight == 0) {
+        return Err("At least one share must be positive".to_string());
+    }
+    Ok(DifficultyShares(shares))
+}
+
+impl DifficultyShares {
+    /// Splits `count` puzzles into the difficulties according to their share.
+    /// Rounding leftovers go to the difficulties with the largest remainders.
+    fn counts(&self, count: usize) -> Vec<(Difficulty, usize)> {
+        let total: usize = self.0.iter().map(|(_, weight)| weight).sum();
+        let mut counts: Vec<(Difficulty, usize, usize)> = self
+            .0
+            .iter()
+            .map(|&(difficulty, weight)| (difficulty, count * weight / total, count * weight % total))
+            .collect();
+        let assigned: usize = counts.iter().map(|(_, count, _)| count).sum();
+        let mut by_remainder: Vec<usize> = (0..counts.len()).collect();
+        by_remainder.sort_by_key(|&index| Reverse(counts[index].2));
+        for &index in by_remainder.iter().take(count - assigned) {
+            counts[index].1 += 1;
+        }
+        counts.into_iter().map(|(difficulty, count, _)| (difficulty, count)).collect()
+    }
+}
+
+/// Generates a book of puzzles sorted from easiest to hardest, followed by their solutions
+pub fn run(args: Args, seed: Option<u64>) -> Result<(), CliError> {
+    let seed = seed_or_random(seed);
+    let difficulties: Vec<Difficulty> = args
+        .difficulties
+        .counts(args.count)
+        .into_iter()
+        .flat_map(|(difficulty, count)| std::iter::repeat_n(difficulty, count))
+        .collect();
+    let mut puzzles: Vec<_> = difficulties
+        .into_par_iter()
+        .enumerate()
+        .map(|(index, difficulty)| {
+            let puzzle = generate_with_difficulty_seeded(difficulty, Symmetry::Rotational, seed.wrapping_add(index as u64));
+            (difficulty, puzzle)
+        })
+        .collect();
+    // Within a difficulty, puzzles with fewer clues tend to be harder
+    puzzles.sort_by_key(|(difficulty, puzzle)| (*difficulty, puzzle.num_empty()));
+
+    let mut figures: Vec<Figure> = puzzles
+        .iter()
+        .enumerate()
+        .map(|(index, (difficulty, puzzle))| Figure {
+            caption: format!("#{} ({difficulty}, {} clues)", index + 1, puzzle.num_filled()),
+            board: *puzzle,
+        })
+        .collect();
+    for (index, (_, puzzle)) in puzzles.iter().enumerate() {
+        figures.push(Figure {
+            caption: format!("Answer #{}", index + 1),
+            board: solve(*puzzle)?,
+        });
+    }
+    write_output(args.out, &render(&figures, args.format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn difficulty_shares() {
+        let shares = parse_difficulties("easy:40,medium:40,hard:20").unwrap();
+        assert_eq!(
+            vec![(Difficulty::Easy, 40), (Difficulty::Medium, 40), (Difficulty::Hard, 20)],
+            shares.counts(100)
+        );
+        assert_eq!(
+            vec![(Difficulty::Easy, 4), (Difficulty::Medium, 4), (Difficulty::Hard, 2)],
+            shares.counts(10)
+        );
+        let shares = parse_difficulties("easy:1,medium:1,hard:1").unwrap();
+        assert_eq!(10, shares.counts(10).iter().map(|(_, count)| count).sum::<usize>());
+        assert!(parse_difficulties("easy").is_err());
+        assert!(parse_difficulties("easy:x").is_err());
+        assert!(parse_difficulties("trivial:1").is_err());
+        assert!(parse_difficulties("easy:0").is_err());
+    }
+}
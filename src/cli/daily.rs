@@ -0,0 +1,24 @@
+use sudoku::{daily_puzzle, Date, Difficulty};
+
+use super::{CliError, OutputFormat};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The day of the puzzle as YYYY-MM-DD. Defaults to today (UTC).
+    #[arg(long)]
+    date: Option<Date>,
+
+    #[arg(long, default_value_t = Difficulty::Medium)]
+    difficulty: Difficulty,
+
+    #[arg(long, value_enum, default_value_t)]
+    format: OutputFormat,
+}
+
+/// Prints the puzzle of the day. Everyone running this for the same date and difficulty gets the same puzzle.
+pub fn run(args: Args) -> Result<(), CliError> {
+    let date = args.date.unwrap_or_else(Date::today);
+    let board = daily_puzzle(date, args.difficulty);
+    println!("{}", args.format.format(&board));
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use rayon::prelude::*;
+use std::collections::HashSet;
+use sudoku::canonical_form;
+
+use super::input::CollectionArgs;
+use super::{CliError, OutputFormat};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: CollectionArgs,
+
+    #[arg(long, value_enum, default_value_t)]
+    format: OutputFormat,
+}
+
+/// Writes the puzzles to stdout in their original order, skipping puzzles that are equivalent to an earlier one
+pub fn run(args: Args) -> Result<(), CliError> {
+    let boards = args.input.read_puzzles()?;
+    let canonical_forms: Vec<_> = boards.par_iter().map(canonical_form).collect();
+    let mut seen = HashSet::new();
+    let mut num_dropped = 0;
+    for (board, canonical) in boards.iter().zip(canonical_forms) {
+        if seen.insert(canonical) {
+            println!("{}", args.format.format(board));
+        } else {
+            num_dropped += 1;
+        }
+    }
+    eprintln!("Dropped {num_dropped} duplicates");
+    Ok(())
+}
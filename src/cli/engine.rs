@@ -0,0 +1,159 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use sudoku::{format_line, hint, parse_puzzles, rate, solve, Board};
+
+use super::CliError;
+
+/// Version of the protocol, announced when the engine starts. It changes when existing requests or responses change.
+const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {}
+
+#[derive(Deserialize)]
+#[serde(tag = "command", rename_all = "lowercase")]
+enum Request {
+    Solve { puzzle: String },
+    Hint { puzzle: String },
+    Analyze { puzzle: String },
+    Quit,
+}
+
+/// Speaks a JSON lines protocol over stdin and stdout, so that editor plugins and programs in other languages can
+/// keep one engine process running and send it requests:
+///
+/// - On start, the engine writes `{"engine": "sudoku", "version": ..., "protocol": 1}`.
+/// - Each line of stdin is a request like `{"id": 1, "command": "solve", "puzzle": "..."}`, with the commands
+///   `solve`, `hint`, `analyze` and `quit`. The `id` is optional and can be any JSON value.
+/// - Each request is answered with one line, in order. The response contains the request's `id` and either the result,
+///   like `{"id": 1, "solution": "..."}`, or `{"id": 1, "error": "..."}`. Invalid requests are answered with an error
+///   and don't stop the engine.
+///
+/// The engine stops on `quit` or when stdin is closed.
+pub fn run(_args: Args) -> Result<(), CliError> {
+    serve(io::stdin().lock(), io::stdout().lock()).map_err(CliError::WriteOutput)
+}
+
+fn serve(input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    let greeting = json!({ "engine": "sudoku", "version": env!("CARGO_PKG_VERSION"), "protocol": PROTOCOL_VERSION });
+    writeln!(output, "{greeting}")?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (id, request) = match serde_json::from_str::<Value>(&line) {
+            Ok(mut value) => {
+                let id = value.get_mut("id").map(Value::take).unwrap_or(Value::Null);
+                (id, serde_json::from_value::<Request>(value).map_err(|error| error.to_string()))
+            }
+            Err(error) => (Value::Null, Err(error.to_string())),
+        };
+        if matches!(request, Ok(Request::Quit)) {
+            break;
+        }
+        let mut response = match request.and_then(handle) {
+            Ok(response) => response,
+            Err(error) => json!({ "error": error }),
+        };
+        response["id"] = id;
+        writeln!(output, "{response}")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+fn handle(request: Request) -> Result<Value, String> {
+    match request {
+        Request::Solve { puzzle } => {
+            let solution = solve(parse_puzzle(&puzzle)?).map_err(|error| error.to_string())?;
+            Ok(json!({ "solution": format_line(&solution) }))
+        }
+        Request::Hint { puzzle } => {
+            let step = hint(&parse_puzzle(&puzzle)?, &[]).ok_or("No logical step found")?;
+            Ok(json!({
+                "technique": step.technique,
+                "explanation": step.explanation(),
+                "house": step.house,
+                "cells": step.cells,
+                "placement": step.placement.map(|placement| placement.to_string()),
+                "eliminations": step.eliminations.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            }))
+        }
+        Request::Analyze { puzzle } => {
+            let report = rate(&parse_puzzle(&puzzle)?).map_err(|error| error.to_string())?;
+            let techniques: serde_json::Map<String, Value> = report
+                .technique_counts()
+                .into_iter()
+                .map(|(technique, count)| (technique.name().to_string(), json!(count)))
+                .collect();
+            Ok(json!({
+                "difficulty": report.difficulty,
+                "requires_guessing": report.requires_guessing,
+                "techniques": techniques,
+                "steps": report.steps.iter().map(|step| step.explanation()).collect::<Vec<_>>(),
+            }))
+        }
+        Request::Quit => unreachable!("The engine stops before handling quit"),
+    }
+}
+
+fn parse_puzzle(puzzle: &str) -> Result<Board, String> {
+    let puzzles = parse_puzzles(puzzle, None).map_err(|error| error.to_string())?;
+    match puzzles[..] {
+        [puzzle] => Ok(puzzle),
+        _ => Err("Expected exactly one puzzle".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    fn responses(input: &str) -> Vec<Value> {
+        let mut output = vec![];
+        serve(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn session() {
+        let requests = [
+            json!({ "id": 1, "command": "solve", "puzzle": PUZZLE }),
+            json!({ "id": "a", "command": "hint", "puzzle": PUZZLE }),
+            json!({ "command": "analyze", "puzzle": PUZZLE }),
+            json!({ "command": "quit" }),
+            json!({ "id": 4, "command": "solve", "puzzle": PUZZLE }),
+        ];
+        let input: String = requests.iter().map(|request| format!("{request}\n")).collect();
+        let responses = responses(&input);
+        assert_eq!(4, responses.len());
+        assert_eq!(PROTOCOL_VERSION, responses[0]["protocol"]);
+        assert_eq!(1, responses[1]["id"]);
+        assert_eq!(81, responses[1]["solution"].as_str().unwrap().len());
+        assert_eq!("a", responses[2]["id"]);
+        assert!(responses[2]["placement"].is_string());
+        assert_eq!(Value::Null, responses[3]["id"]);
+        assert_eq!("easy", responses[3]["difficulty"]);
+    }
+
+    #[test]
+    fn errors_keep_the_engine_running() {
+        let ambigious = json!({ "id": 2, "command": "solve", "puzzle": ".".repeat(81) });
+        let input = format!("not json\n{{\"id\": 1, \"command\": \"dance\"}}\n\n{ambigious}\n");
+        let responses = responses(&input);
+        assert_eq!(4, responses.len());
+        assert!(responses[1]["error"].is_string());
+        assert_eq!(1, responses[2]["id"]);
+        assert!(responses[2]["error"].as_str().unwrap().contains("dance"));
+        assert_eq!(json!({ "id": 2, "error": "Sudoku has multiple valid solutions" }), responses[3]);
+    }
+}
@@ -0,0 +1,55 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use sudoku::{render, solve, Figure, RenderFormat};
+
+use super::input::InputArgs;
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// The format to export to: svg, pdf, latex or html
+    #[arg(long)]
+    format: RenderFormat,
+
+    /// The file to write to. If not given, the document is written to stdout.
+    #[arg(long, short)]
+    out: Option<PathBuf>,
+
+    /// Add the solutions after the puzzles
+    #[arg(long)]
+    solutions: bool,
+}
+
+pub fn run(args: Args) -> Result<(), CliError> {
+    let boards = args.input.read_puzzles()?;
+    let mut figures: Vec<Figure> = boards
+        .iter()
+        .enumerate()
+        .map(|(index, board)| Figure {
+            caption: format!("Puzzle {}", index + 1),
+            board: *board,
+        })
+        .collect();
+    if args.solutions {
+        for (index, board) in boards.iter().enumerate() {
+            figures.push(Figure {
+                caption: format!("Solution {}", index + 1),
+                board: solve(*board)?,
+            });
+        }
+    }
+    write_output(args.out, &render(&figures, args.format))
+}
+
+/// Writes the data to the file, or to stdout if no file is given
+pub fn write_output(path: Option<PathBuf>, data: &[u8]) -> Result<(), CliError> {
+    match path {
+        Some(path) => fs::write(path, data),
+        None => io::stdout().write_all(data),
+    }
+    .map_err(CliError::WriteOutput)
+}
@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use sudoku::checkpoint::{CheckpointError, MaxEmptySearch};
+use sudoku::{generate_max_empty, generate_solved, Board, Difficulty, GeneratorConfig, Manifest, Symmetry};
+
+use super::pool::{open, pool_error};
+use super::{seed_or_random, CliError, OutputFormat};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// How many sudokus to generate
+    #[arg(long, short = 'n', default_value_t = 1)]
+    count: usize,
+
+    /// Only output sudokus of this difficulty: easy, medium, hard, expert or extreme
+    #[arg(long)]
+    difficulty: Option<Difficulty>,
+
+    /// Symmetry of the given fields: none, rotational, horizontal, vertical or diagonal
+    #[arg(long, default_value_t)]
+    symmetry: Symmetry,
+
+    /// Remove as many fields as possible. This searches many removal orders in parallel, is much slower,
+    /// and isn't reproducible with `--seed`.
+    #[arg(long, conflicts_with_all = ["difficulty", "symmetry", "seed"])]
+    max_empty: bool,
+
+    /// With `--max-empty`, save the progress to this file regularly and continue from it if it exists, e.g. after
+    /// the machine was preempted. The search tries random removal orders and never ends by itself, so it runs until
+    /// `--attempts` is reached or it is stopped. The best puzzle so far is always in the checkpoint.
+    #[arg(long, requires = "max_empty", conflicts_with = "count")]
+    checkpoint: Option<PathBuf>,
+
+    /// With `--checkpoint`, stop after this many removal orders in total, including the ones before the checkpoint
+    /// was saved, and print the best puzzle
+    #[arg(long, requires = "checkpoint")]
+    attempts: Option<u64>,
+
+    /// Seconds between saving checkpoints
+    #[arg(long, default_value_t = 60, requires = "checkpoint")]
+    checkpoint_interval: u64,
+
+    /// Take the sudokus from a pool filled by the `pool` command, which is instant. If the pool runs out,
+    /// the remaining sudokus are generated as usual.
+    #[arg(long, requires = "difficulty", conflicts_with_all = ["max_empty", "seed"])]
+    pool: Option<PathBuf>,
+
+    /// Write a JSON manifest with the crate version, the settings and the seed of each puzzle to this file,
+    /// so that `regenerate` can reproduce the puzzles
+    #[arg(long, conflicts_with_all = ["max_empty", "pool"])]
+    manifest: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t)]
+    format: OutputFormat,
+}
+
+pub fn run(args: Args, seed: Option<u64>) -> Result<(), CliError> {
+    if let Some(path) = &args.checkpoint {
+        let board = search_with_checkpoints(path, Duration::from_secs(args.checkpoint_interval), args.attempts)?;
+        println!("{}", args.format.format(&board));
+        return Ok(());
+    }
+    if args.max_empty {
+        for _ in 0..args.count {
+            println!("{}", args.format.format(&generate_max_empty()));
+        }
+        return Ok(());
+    }
+    let mut pooled = vec![];
+    if let (Some(directory), Some(difficulty)) = (args.pool, args.difficulty) {
+        let store = open(directory)?;
+        while pooled.len() < args.count {
+            match store.take(difficulty).map_err(|error| pool_error(&store, error))? {
+                Some(board) => pooled.push(board),
+                None => break,
+            }
+        }
+        for board in &pooled {
+            println!("{}", args.format.format(board));
+        }
+        if pooled.len() == args.count {
+            return Ok(());
+        }
+        eprintln!("The pool ran out of {difficulty} puzzles, generating the remaining ones");
+    }
+    let seed = seed_or_random(seed);
+    let config = GeneratorConfig {
+        difficulty: args.difficulty,
+        symmetry: args.symmetry,
+    };
+    // The puzzles taken from the pool use up the first indices, so the generated ones are the same as without a pool
+    let manifest = Manifest::generate_range(config, seed, pooled.len()..args.count);
+    for board in manifest.boards() {
+        println!("{}", args.format.format(&board));
+    }
+    if let Some(path) = args.manifest {
+        let json = serde_json::to_string_pretty(&manifest).expect("Serializing a manifest can't fail");
+        super::export::write_output(Some(path), json.as_bytes())?;
+    }
+    Ok(())
+}
+
+fn search_with_checkpoints(path: &Path, interval: Duration, attempts: Option<u64>) -> Result<Board, CliError> {
+    let checkpoint_error = |error: CheckpointError| CliError::Checkpoint {
+        path: path.display().to_string(),
+        error,
+    };
+    let mut search = if path.exists() {
+        MaxEmptySearch::load(path).map_err(checkpoint_error)?
+    } else {
+        MaxEmptySearch::new(generate_solved(), &[], seed_or_random(None))
+    };
+    let mut saved = Instant::now();
+    while attempts.is_none_or(|attempts| search.attempts() < attempts) {
+        let remaining = attempts.map_or(u64::MAX, |attempts| attempts - search.attempts());
+        let step = remaining.min(rayon::current_num_threads() as u64) as usize;
+        if search.step(step) {
+            eprintln!("Found board with {} empty fields", search.best().num_empty());
+        }
+        if saved.elapsed() >= interval {
+            search.save(path).map_err(|error| checkpoint_error(error.into()))?;
+            saved = Instant::now();
+        }
+    }
+    search.save(path).map_err(|error| checkpoint_error(error.into()))?;
+    Ok(search.best())
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+use sudoku::grpc::proto::sudoku_server::SudokuServer;
+use sudoku::grpc::SudokuService;
+use tonic::transport::Server;
+
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[arg(long, default_value_t = 50051)]
+    port: u16,
+
+    /// The address to listen on. Use 0.0.0.0 to accept connections from other machines.
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Maximum number of requests computed at the same time per connection
+    #[arg(long, default_value_t = 4)]
+    max_concurrent: usize,
+
+    /// Seconds after which a request fails with DEADLINE_EXCEEDED. This doesn't apply to the messages of BatchSolve.
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+}
+
+/// Serves [SudokuService] until the process is killed
+// TODO Like for `sudoku serve`, computations continue after their request timed out.
+pub fn run(args: Args) -> Result<(), CliError> {
+    let address = format!("{}:{}", args.host, args.port)
+        .parse()
+        .map_err(|error| CliError::Grpc(format!("Invalid address: {error}")))?;
+    let runtime = tokio::runtime::Runtime::new().map_err(|error| CliError::Grpc(error.to_string()))?;
+    eprintln!("Listening on {address}");
+    runtime
+        .block_on(
+            Server::builder()
+                .timeout(Duration::from_secs(args.timeout))
+                .concurrency_limit_per_connection(args.max_concurrent)
+                .add_service(SudokuServer::new(SudokuService))
+                .serve(address),
+        )
+        .map_err(|error| CliError::Grpc(error.to_string()))
+}
@@ -0,0 +1,60 @@
+use clap::ValueEnum;
+use sudoku::{hint, Step};
+
+use super::input::InputArgs;
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// How much of the next move to reveal
+    #[arg(long, value_enum, default_value_t)]
+    level: HintLevel,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+enum HintLevel {
+    /// Only the technique to look for
+    Technique,
+
+    /// The technique and where to apply it
+    #[default]
+    Location,
+
+    /// The full move with an explanation
+    Full,
+}
+
+pub fn run(args: Args) -> Result<(), CliError> {
+    for board in args.input.read_puzzles()? {
+        if board.has_conflicts() {
+            return Err(CliError::Conflicting);
+        }
+        match hint(&board, &[]) {
+            Some(step) => println!("{}", describe(&step, args.level)),
+            None if board.is_filled() => println!("The sudoku is already solved"),
+            None => println!("No logical move found, the sudoku requires guessing from here"),
+        }
+    }
+    Ok(())
+}
+
+fn describe(step: &Step, level: HintLevel) -> String {
+    match level {
+        HintLevel::Technique => format!("Look for a {}", step.technique),
+        HintLevel::Location => match step.house {
+            Some(house) => format!("Look for a {} in {house}", step.technique),
+            None => {
+                let cells: Vec<String> = step
+                    .cells
+                    .iter()
+                    .map(|&(x, y)| format!("r{}c{}", y + 1, x + 1))
+                    .collect();
+                format!("Look for a {} at {}", step.technique, cells.join(", "))
+            }
+        },
+        HintLevel::Full => format!("{}: {}", step.technique, step.explanation()),
+    }
+}
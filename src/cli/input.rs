@@ -0,0 +1,105 @@
+use clap::ValueEnum;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use sudoku::{parse_puzzles, Board, PuzzleFormat};
+
+use super::CliError;
+
+/// Where to read puzzles from. If neither a puzzle nor an input file is given, puzzles are read from stdin.
+#[derive(clap::Args, Debug)]
+pub struct InputArgs {
+    /// The puzzle, e.g. in the line format with 81 characters and `.` or `0` for empty cells
+    #[arg(conflicts_with = "input")]
+    puzzle: Option<String>,
+
+    /// Read puzzles from this file instead
+    #[arg(long, short)]
+    input: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t)]
+    input_format: InputFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum InputFormat {
+    /// Detect the format from the input
+    #[default]
+    Auto,
+
+    /// A single puzzle in one line
+    Line,
+
+    /// A single puzzle spread over 9 lines
+    Grid,
+
+    /// One puzzle per line
+    Sdm,
+
+    /// One puzzle per row in the first column, with an optional header row
+    Csv,
+}
+
+impl InputFormat {
+    fn puzzle_format(&self) -> Option<PuzzleFormat> {
+        match self {
+            InputFormat::Auto => None,
+            InputFormat::Line => Some(PuzzleFormat::Line),
+            InputFormat::Grid => Some(PuzzleFormat::Grid),
+            InputFormat::Sdm => Some(PuzzleFormat::Sdm),
+            InputFormat::Csv => Some(PuzzleFormat::Csv),
+        }
+    }
+}
+
+impl InputArgs {
+    pub fn read_puzzles(&self) -> Result<Vec<Board>, CliError> {
+        let input = match &self.puzzle {
+            Some(puzzle) => puzzle.clone(),
+            None => read_input(self.input.as_deref())?,
+        };
+        Ok(parse_puzzles(&input, self.input_format.puzzle_format())?)
+    }
+}
+
+/// Where to read a collection of puzzles from, for commands that only make sense for whole files
+#[derive(clap::Args, Debug)]
+pub struct CollectionArgs {
+    /// The file containing the puzzles. If not given, puzzles are read from stdin.
+    file: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t)]
+    input_format: InputFormat,
+}
+
+impl CollectionArgs {
+    pub fn read_puzzles(&self) -> Result<Vec<Board>, CliError> {
+        let input = read_input(self.file.as_deref())?;
+        Ok(parse_puzzles(&input, self.input_format.puzzle_format())?)
+    }
+}
+
+/// Reads all puzzles from the file, detecting its format
+pub fn read_puzzles_from(path: &Path) -> Result<Vec<Board>, CliError> {
+    Ok(parse_puzzles(&read_input(Some(path))?, None)?)
+}
+
+/// Reads the whole file, or stdin if no file is given
+fn read_input(path: Option<&Path>) -> Result<String, CliError> {
+    match path {
+        Some(path) => fs::read_to_string(path).map_err(|error| CliError::ReadInput {
+            path: path.display().to_string(),
+            error,
+        }),
+        None => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|error| CliError::ReadInput {
+                    path: "stdin".to_string(),
+                    error,
+                })?;
+            Ok(input)
+        }
+    }
+}
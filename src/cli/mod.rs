@@ -0,0 +1,295 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use sudoku::{format_grid, format_line, Board, BoardParseError, RegenerateError, SolverError};
+use thiserror::Error;
+
+mod analyze;
+mod bench;
+mod book;
+mod daily;
+mod dedupe;
+mod engine;
+mod export;
+mod generate;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod hint;
+mod input;
+mod play;
+mod pool;
+mod rate;
+mod regenerate;
+#[cfg(feature = "serve")]
+mod serve;
+mod solve;
+mod stats;
+mod validate;
+mod verify;
+
+#[derive(Parser, Debug)]
+#[command(version, about = "Solve, generate and rate sudokus")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Seed for the random number generator, to make generating sudokus reproducible.
+    /// If not given, a random seed is used and printed to stderr.
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Solve a sudoku and print its solution
+    Solve(solve::Args),
+
+    /// Generate a new sudoku with a unique solution
+    Generate(generate::Args),
+
+    /// Generate the puzzles of a manifest written by `generate --manifest` again and check that they match
+    Regenerate(regenerate::Args),
+
+    /// Rate how hard a sudoku is for a human
+    Rate(rate::Args),
+
+    /// Print the difficulty and the techniques needed to solve a sudoku in order
+    Analyze(analyze::Args),
+
+    /// Print the next logical move for a partially solved sudoku
+    Hint(hint::Args),
+
+    /// Check that a sudoku is conflict-free, solvable and has a unique solution. The exit code tells which check failed.
+    Validate(validate::Args),
+
+    /// Print statistics about a collection of sudokus, e.g. how many clues they have and how hard they are
+    Stats(stats::Args),
+
+    /// Remove sudokus from a collection that are equivalent to an earlier one, e.g. a rotated or relabeled copy
+    Dedupe(dedupe::Args),
+
+    /// Check a file of solutions against a file of puzzles
+    Verify(verify::Args),
+
+    /// Play a sudoku in the terminal
+    Play(play::Args),
+
+    /// Step through a play session recorded with `play --record`
+    Replay(play::replay::Args),
+
+    /// Print the puzzle of the day, which is the same for everyone
+    Daily(daily::Args),
+
+    /// Export sudokus to SVG, PDF, LaTeX or HTML, e.g. for printing
+    Export(export::Args),
+
+    /// Generate a printable book of puzzles sorted by difficulty, with the answers at the end
+    Book(book::Args),
+
+    /// Solve a corpus of sudokus with each solver backend and compare their speed and search tree sizes
+    Bench(bench::Args),
+
+    /// Pre-generate puzzles into a pool directory, so `generate --pool` can hand them out instantly
+    Pool(pool::Args),
+
+    /// Answer solve, hint and analyze requests as JSON lines on stdin and stdout, e.g. for editor plugins
+    Engine(engine::Args),
+
+    /// Serve a JSON API over HTTP for generating, solving, rating and hints
+    #[cfg(feature = "serve")]
+    Serve(serve::Args),
+
+    /// Serve a gRPC service for generating, solving, rating and hints, see proto/sudoku.proto
+    #[cfg(feature = "grpc")]
+    Grpc(grpc::Args),
+}
+
+/// How boards are written to stdout
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+pub enum OutputFormat {
+    /// 81 characters in one line, `.` for empty cells
+    #[default]
+    Line,
+
+    /// 9 lines with the 3x3 regions separated
+    Grid,
+}
+
+impl OutputFormat {
+    pub fn format(&self, board: &Board) -> String {
+        match self {
+            OutputFormat::Line => format_line(board),
+            OutputFormat::Grid => format_grid(board),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("Couldn't parse puzzles: {0}")]
+    Parse(#[from] BoardParseError),
+
+    #[error("Couldn't read {path}: {error}")]
+    ReadInput { path: String, error: std::io::Error },
+
+    #[error("Couldn't write output: {0}")]
+    WriteOutput(std::io::Error),
+
+    #[error(transparent)]
+    Solver(#[from] SolverError),
+
+    #[error("The sudoku breaks the rules, some values appear twice in a row, column or region")]
+    Conflicting,
+
+    #[error("The sudoku breaks the rules: {0}")]
+    Invalid(String),
+
+    #[error("Invalid theme: {0}")]
+    Theme(String),
+
+    #[cfg(feature = "serve")]
+    #[error("Couldn't start the server: {0}")]
+    Serve(String),
+
+    #[cfg(feature = "grpc")]
+    #[error("gRPC server failed: {0}")]
+    Grpc(String),
+
+    #[error("Puzzle pool {path}: {error}")]
+    Pool { path: String, error: std::io::Error },
+
+    #[error("Checkpoint {path}: {error}")]
+    Checkpoint {
+        path: String,
+        error: sudoku::checkpoint::CheckpointError,
+    },
+
+    #[error("Invalid replay file: {0}")]
+    Replay(String),
+
+    #[error("Invalid manifest: {0}")]
+    Manifest(String),
+
+    #[error(transparent)]
+    Regenerate(#[from] RegenerateError),
+
+    #[error("Terminal error: {0}")]
+    Terminal(std::io::Error),
+
+    #[error("{0} puzzles failed")]
+    BatchFailed(usize),
+
+    #[error("Found {solutions} solutions for {puzzles} puzzles")]
+    WrongNumberOfSolutions { puzzles: usize, solutions: usize },
+
+    #[error("{0} solutions are wrong")]
+    WrongSolutions(usize),
+}
+
+/// Exit code for an invalid command line, e.g. an unknown option. This is `EX_USAGE` from `sysexits.h`.
+/// It differs from the 2 that clap uses by default, because 2 means that a sudoku isn't solvable.
+pub const USAGE_EXIT_CODE: u8 = 64;
+
+impl CliError {
+    /// The exit code of the process when the command fails with this error, so that scripts can tell failures apart.
+    /// These are stable:
+    ///
+    /// - 0: success, e.g. the sudoku was solved or is valid
+    /// - 1: any other error, e.g. a file couldn't be read
+    /// - 2: the sudoku isn't solvable
+    /// - 3: the sudoku has multiple solutions
+    /// - 4: the input couldn't be parsed
+    /// - 5: the sudoku breaks the rules, e.g. a value appears twice in a row
+    /// - 6: some puzzles of a batch failed, some solutions are wrong, or a manifest doesn't reproduce its puzzles.
+    ///   The details are printed to stderr.
+    /// - 64: the command line is invalid, see [USAGE_EXIT_CODE]
+    ///
+    /// Data is printed to stdout and all diagnostics go to stderr, so the output can be piped into other commands.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Solver(SolverError::NotSolvable) => 2,
+            CliError::Solver(SolverError::Ambigious) => 3,
+            CliError::Parse(_) | CliError::Replay(_) | CliError::Manifest(_) => 4,
+            CliError::Solver(SolverError::Conflicting | SolverError::InvalidConstraint(_))
+            | CliError::Conflicting
+            | CliError::Invalid(_) => 5,
+            CliError::BatchFailed(_)
+            | CliError::WrongNumberOfSolutions { .. }
+            | CliError::WrongSolutions(_)
+            | CliError::Regenerate(_) => 6,
+            CliError::ReadInput { .. }
+            | CliError::WriteOutput(_)
+            | CliError::Pool { .. }
+            | CliError::Checkpoint { .. }
+            | CliError::Terminal(_)
+            | CliError::Theme(_) => 1,
+            #[cfg(feature = "serve")]
+            CliError::Serve(_) => 1,
+            #[cfg(feature = "grpc")]
+            CliError::Grpc(_) => 1,
+        }
+    }
+}
+
+pub fn run(cli: Cli) -> Result<(), CliError> {
+    match cli.command {
+        Command::Solve(args) => solve::run(args),
+        Command::Generate(args) => generate::run(args, cli.seed),
+        Command::Regenerate(args) => regenerate::run(args),
+        Command::Rate(args) => rate::run(args),
+        Command::Analyze(args) => analyze::run(args),
+        Command::Hint(args) => hint::run(args),
+        Command::Validate(args) => validate::run(args),
+        Command::Stats(args) => stats::run(args),
+        Command::Dedupe(args) => dedupe::run(args),
+        Command::Verify(args) => verify::run(args),
+        Command::Play(args) => play::run(args, cli.seed),
+        Command::Replay(args) => play::replay::run(args),
+        Command::Daily(args) => daily::run(args),
+        Command::Export(args) => export::run(args),
+        Command::Book(args) => book::run(args, cli.seed),
+        Command::Bench(args) => bench::run(args),
+        Command::Pool(args) => pool::run(args),
+        Command::Engine(args) => engine::run(args),
+        #[cfg(feature = "serve")]
+        Command::Serve(args) => serve::run(args),
+        #[cfg(feature = "grpc")]
+        Command::Grpc(args) => grpc::run(args),
+    }
+}
+
+/// Returns the seed given on the command line, or a random one that is printed so the run can be reproduced
+pub fn seed_or_random(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| {
+        let seed = rand::random();
+        eprintln!("Using seed {seed}");
+        seed
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(2, CliError::from(SolverError::NotSolvable).exit_code());
+        assert_eq!(3, CliError::from(SolverError::Ambigious).exit_code());
+        assert_eq!(4, CliError::from(BoardParseError::Empty).exit_code());
+        assert_eq!(5, CliError::from(SolverError::Conflicting).exit_code());
+        assert_eq!(6, CliError::BatchFailed(1).exit_code());
+        assert_eq!(1, CliError::WriteOutput(std::io::Error::other("closed")).exit_code());
+    }
+
+    #[test]
+    fn usage_errors() {
+        assert!(Cli::try_parse_from(["sudoku", "solve", "--unknown"]).unwrap_err().use_stderr());
+        // Help isn't an error, it's printed to stdout with exit code 0
+        assert!(!Cli::try_parse_from(["sudoku", "--help"]).unwrap_err().use_stderr());
+    }
+
+    #[test]
+    fn bench_corpus() {
+        assert!(Cli::try_parse_from(["sudoku", "bench", "--corpus", "hard"]).is_ok());
+        assert!(Cli::try_parse_from(["sudoku", "bench", "--corpus", "top1465"]).is_err());
+        assert!(Cli::try_parse_from(["sudoku", "bench", "--corpus", "hard", "puzzles.txt"]).is_err());
+    }
+}
@@ -0,0 +1,416 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::num::NonZeroU8;
+use sudoku::{Board, Game, GameError, LogicalSolver, HEIGHT, WIDTH};
+
+use super::replay::{Action, Move, Replay};
+
+/// State of the play mode, independent from how it's drawn
+pub struct App {
+    /// The givens, the values entered by the player and their pencil marks, with the undo history
+    game: Game,
+
+    cursor: (usize, usize),
+
+    /// If set, digits toggle pencil marks instead of entering values
+    pencil_mode: bool,
+
+    /// If set, empty cells show the values that are still possible instead of the pencil marks
+    show_candidates: bool,
+
+    /// The possible values of each cell as a bitmask where bit `value - 1` is set if the value is possible,
+    /// updated whenever the board changes
+    candidates: [[u16; HEIGHT]; WIDTH],
+
+    message: String,
+
+    quit: bool,
+
+    /// Every move so far, so the session can be saved as a replay
+    replay: Replay,
+}
+
+impl App {
+    pub fn new(puzzle: Board) -> Self {
+        let mut game = Game::new(puzzle);
+        game.set_auto_remove_notes(true);
+        game.start();
+        let mut app = Self {
+            game,
+            cursor: (0, 0),
+            pencil_mode: false,
+            show_candidates: false,
+            candidates: [[0; HEIGHT]; WIDTH],
+            message: String::new(),
+            quit: false,
+            replay: Replay::new(&puzzle),
+        };
+        app.board_changed();
+        app
+    }
+
+    fn board_changed(&mut self) {
+        let solver = LogicalSolver::new(*self.game.board(), vec![]);
+        for (x, column) in self.candidates.iter_mut().enumerate() {
+            for (y, mask) in column.iter_mut().enumerate() {
+                *mask = solver
+                    .possible_values_for_field(x, y)
+                    .fold(0, |mask, value| mask | 1 << (value.get() - 1));
+            }
+        }
+    }
+
+    pub fn set_teaching(&mut self, enabled: bool) {
+        self.game.set_teaching(enabled);
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn board(&self) -> &Board {
+        self.game.board()
+    }
+
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    pub fn is_given(&self, x: usize, y: usize) -> bool {
+        self.game.is_given(x, y)
+    }
+
+    /// The pencil marks of an empty cell, or the values that are still possible if the candidates overlay is shown
+    pub fn pencil_marks(&self, x: usize, y: usize) -> impl Iterator<Item = u8> + '_ {
+        let marks = if self.show_candidates {
+            self.candidates[x][y]
+        } else {
+            self.game.notes().values(x, y).fold(0, |mask, value| mask | 1 << (value.get() - 1))
+        };
+        (1..=9).filter(move |value| marks & (1 << (value - 1)) != 0)
+    }
+
+    pub fn show_candidates(&self) -> bool {
+        self.show_candidates
+    }
+
+    pub fn is_conflicting(&self, x: usize, y: usize) -> bool {
+        self.game.is_conflicting(x, y)
+    }
+
+    /// True if the cell has the same value as the cell under the cursor
+    pub fn has_selected_value(&self, x: usize, y: usize) -> bool {
+        let (cursor_x, cursor_y) = self.cursor;
+        let selected = self.board().field(cursor_x, cursor_y).get();
+        selected.is_some() && self.board().field(x, y).get() == selected
+    }
+
+    pub fn pencil_mode(&self) -> bool {
+        self.pencil_mode
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn set_message(&mut self, message: String) {
+        self.message = message;
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.game.is_solved()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(-1, 0),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(1, 0),
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(0, -1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(0, 1),
+            KeyCode::Char('p') => {
+                self.pencil_mode = !self.pencil_mode;
+                self.message = if self.pencil_mode {
+                    "Pencil mode: digits toggle pencil marks".to_string()
+                } else {
+                    "Digits enter values".to_string()
+                };
+            }
+            KeyCode::Char('c') => {
+                self.show_candidates = !self.show_candidates;
+                self.message = if self.show_candidates {
+                    "Showing the possible values instead of pencil marks".to_string()
+                } else {
+                    "Showing pencil marks".to_string()
+                };
+            }
+            KeyCode::Char('?') => self.record(Action::Hint),
+            KeyCode::Char('n') => self.nudge(),
+            KeyCode::Char('f') => self.record(Action::FillSingles),
+            KeyCode::Char('u') => self.record(Action::Undo),
+            KeyCode::Char('r') => self.record(Action::Redo),
+            KeyCode::Char(c @ '1'..='9') => {
+                let (x, y) = self.cursor;
+                let value = c as u8 - b'0';
+                if self.pencil_mode {
+                    self.record(Action::TogglePencilMark { x, y, value });
+                } else {
+                    self.record(Action::Enter { x, y, value: Some(value) });
+                }
+            }
+            KeyCode::Char('0') | KeyCode::Char('.') | KeyCode::Backspace | KeyCode::Delete => {
+                let (x, y) = self.cursor;
+                self.record(Action::Enter { x, y, value: None });
+            }
+            _ => {}
+        }
+    }
+
+    fn record(&mut self, action: Action) {
+        self.replay.moves.push(Move {
+            time_ms: self.game.elapsed().as_millis() as u64,
+            action,
+        });
+        self.apply(action);
+    }
+
+    /// Performs the action without recording it, e.g. to step through a replay
+    pub fn apply(&mut self, action: Action) {
+        match action {
+            Action::Enter { x, y, value } => {
+                self.cursor = (x, y);
+                self.enter(value.and_then(NonZeroU8::new));
+            }
+            Action::TogglePencilMark { x, y, value } => {
+                self.cursor = (x, y);
+                if let Some(value) = NonZeroU8::new(value) {
+                    self.toggle_pencil_mark(value);
+                }
+            }
+            Action::Hint => self.hint(),
+            Action::FillSingles => {
+                self.message = match self.game.autofill_singles() {
+                    0 if !self.game.conflicts().is_empty() => "Fix the conflicting values first".to_string(),
+                    0 => "No singles found".to_string(),
+                    filled => format!("Filled {filled} singles"),
+                };
+                self.board_changed();
+            }
+            Action::Undo => {
+                if self.game.undo() {
+                    self.board_changed();
+                    self.message = String::new();
+                } else {
+                    self.message = "Nothing to undo".to_string();
+                }
+            }
+            Action::Redo => {
+                if self.game.redo() {
+                    self.board_changed();
+                    self.message = String::new();
+                } else {
+                    self.message = "Nothing to redo".to_string();
+                }
+            }
+        }
+    }
+
+    fn move_cursor(&mut self, dx: isize, dy: isize) {
+        let (x, y) = self.cursor;
+        self.cursor = (
+            x.saturating_add_signed(dx).min(WIDTH - 1),
+            y.saturating_add_signed(dy).min(HEIGHT - 1),
+        );
+    }
+
+    fn enter(&mut self, value: Option<NonZeroU8>) {
+        let (x, y) = self.cursor;
+        let num_annotations = self.game.annotations().len();
+        if let Err(GameError::Given { .. }) = self.game.set(x, y, value) {
+            self.message = "This cell is given and can't be changed".to_string();
+            return;
+        }
+        self.board_changed();
+        self.message = if self.is_solved() {
+            let score = self.game.score();
+            format!("Solved, congratulations! Score: {}", score.total)
+        } else if let Some(annotation) = self.game.annotations().get(num_annotations) {
+            annotation.to_string()
+        } else {
+            String::new()
+        };
+    }
+
+    fn toggle_pencil_mark(&mut self, value: NonZeroU8) {
+        let (x, y) = self.cursor;
+        if !self.board().field(x, y).is_empty() {
+            self.message = "Pencil marks can only be added to empty cells".to_string();
+            return;
+        }
+        // Can't fail, the cell is empty and thus not given
+        self.game.toggle_note(x, y, value.get()).unwrap();
+    }
+
+    fn nudge(&mut self) {
+        if !self.game.conflicts().is_empty() {
+            self.message = "Fix the conflicting values first".to_string();
+            return;
+        }
+        self.message = match self.game.nudge() {
+            Some(nudge) => nudge.to_string(),
+            None if self.board().is_filled() => "Nothing left to do".to_string(),
+            None => "No logical step found, you have to guess".to_string(),
+        };
+    }
+
+    fn hint(&mut self) {
+        if !self.game.conflicts().is_empty() {
+            self.message = "Fix the conflicting values first".to_string();
+            return;
+        }
+        self.message = match self.game.hint() {
+            Some(step) => {
+                if let Some(placement) = step.placement {
+                    self.cursor = (placement.x, placement.y);
+                }
+                format!("{}: {}", step.technique, step.explanation())
+            }
+            None if self.board().is_filled() => "Nothing left to do".to_string(),
+            None => "No logical step found, you have to guess".to_string(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn puzzle() -> Board {
+        sudoku::parse_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79").unwrap()
+    }
+
+    #[test]
+    fn enter_values() {
+        let mut app = App::new(puzzle());
+        press(&mut app, KeyCode::Char('4'));
+        assert_eq!(NonZeroU8::new(5), app.board().field(0, 0).get());
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Char('4'));
+        assert_eq!(NonZeroU8::new(4), app.board().field(2, 0).get());
+        press(&mut app, KeyCode::Backspace);
+        assert!(app.board().field(2, 0).is_empty());
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut app = App::new(puzzle());
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Char('4'));
+        press(&mut app, KeyCode::Char('u'));
+        assert!(app.board().field(2, 0).is_empty());
+        press(&mut app, KeyCode::Char('r'));
+        assert_eq!(NonZeroU8::new(4), app.board().field(2, 0).get());
+        press(&mut app, KeyCode::Char('r'));
+        assert_eq!("Nothing to redo", app.message());
+    }
+
+    #[test]
+    fn pencil_marks() {
+        let mut app = App::new(puzzle());
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Char('p'));
+        press(&mut app, KeyCode::Char('2'));
+        press(&mut app, KeyCode::Char('7'));
+        press(&mut app, KeyCode::Char('2'));
+        assert_eq!(vec![7], app.pencil_marks(1, 1).collect::<Vec<_>>());
+        assert!(app.board().field(1, 1).is_empty());
+    }
+
+    #[test]
+    fn conflicts_and_candidates() {
+        let mut app = App::new(puzzle());
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Right);
+        assert_eq!(0, app.pencil_marks(2, 0).count());
+        press(&mut app, KeyCode::Char('c'));
+        assert_eq!(vec![1, 2, 4], app.pencil_marks(2, 0).collect::<Vec<_>>());
+        press(&mut app, KeyCode::Char('5'));
+        assert!(app.is_conflicting(0, 0));
+        assert!(app.is_conflicting(2, 0));
+        assert!(!app.is_conflicting(1, 0));
+        assert!(app.has_selected_value(0, 0));
+        assert!(!app.has_selected_value(1, 0));
+    }
+
+    #[test]
+    fn teaching_explains_moves() {
+        let mut app = App::new(puzzle());
+        app.set_teaching(true);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Char('4'));
+        assert!(app.message().starts_with("r1c3=4: found"));
+    }
+
+    #[test]
+    fn nudge_keeps_cursor() {
+        let mut app = App::new(puzzle());
+        press(&mut app, KeyCode::Char('n'));
+        assert_eq!((0, 0), app.cursor());
+        assert!(app.message().starts_with("Look at"));
+        assert_eq!(0, app.game().hints());
+    }
+
+    #[test]
+    fn hint_moves_cursor() {
+        let mut app = App::new(puzzle());
+        press(&mut app, KeyCode::Char('?'));
+        let (x, y) = app.cursor();
+        assert!(app.board().field(x, y).is_empty());
+        assert!(!app.message().is_empty());
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let mut app = App::new(puzzle());
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Char('4'));
+        press(&mut app, KeyCode::Char('p'));
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Char('2'));
+        press(&mut app, KeyCode::Char('?'));
+        let actions: Vec<Action> = app.replay().moves.iter().map(|step| step.action).collect();
+        assert_eq!(
+            vec![
+                Action::Enter { x: 2, y: 0, value: Some(4) },
+                Action::TogglePencilMark { x: 2, y: 1, value: 2 },
+                Action::Hint,
+            ],
+            actions
+        );
+
+        let mut replayed = App::new(puzzle());
+        for action in actions {
+            replayed.apply(action);
+        }
+        assert_eq!(app.board(), replayed.board());
+        assert_eq!(vec![2], replayed.pencil_marks(2, 1).collect::<Vec<_>>());
+        assert_eq!(app.cursor(), replayed.cursor());
+    }
+}
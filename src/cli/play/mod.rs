@@ -0,0 +1,96 @@
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+use std::io;
+use std::path::PathBuf;
+use sudoku::{generate_symmetric_seeded, generate_with_difficulty_seeded, parse_puzzles, Board, BoardParseError, Difficulty, Symmetry};
+
+use super::input::read_puzzles_from;
+use super::{seed_or_random, CliError};
+
+mod app;
+pub mod replay;
+mod theme;
+mod ui;
+
+use app::App;
+use theme::Theme;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The puzzle to play. If neither a puzzle nor an input file is given, a new one is generated.
+    #[arg(conflicts_with = "input")]
+    puzzle: Option<String>,
+
+    /// Play the first puzzle from this file
+    #[arg(long, short)]
+    input: Option<PathBuf>,
+
+    /// Difficulty of the generated puzzle
+    #[arg(long, conflicts_with_all = ["puzzle", "input"])]
+    difficulty: Option<Difficulty>,
+
+    /// Color theme: default, high-contrast or colorblind. Overrides the theme from the config file `~/.config/sudoku/theme`.
+    #[arg(long)]
+    theme: Option<String>,
+
+    /// Record every move to this file when quitting, to step through it later with `replay`
+    #[arg(long)]
+    record: Option<PathBuf>,
+
+    /// Teaching mode: after each value you enter, show which logical deduction justified it, or which one was
+    /// available if you guessed
+    #[arg(long)]
+    teach: bool,
+
+    /// Write a breakdown of the game as JSON to this file when quitting, e.g. where you spent the most time
+    #[arg(long)]
+    analytics: Option<PathBuf>,
+}
+
+pub fn run(args: Args, seed: Option<u64>) -> Result<(), CliError> {
+    let theme = Theme::load(args.theme.as_deref())?;
+    let puzzle = load_or_generate(&args, seed)?;
+    let mut terminal = ratatui::try_init().map_err(CliError::Terminal)?;
+    let mut app = App::new(puzzle);
+    app.set_teaching(args.teach);
+    let result = play(&mut terminal, app, &theme);
+    ratatui::restore();
+    let app = result.map_err(CliError::Terminal)?;
+    if let Some(path) = args.analytics {
+        let json = serde_json::to_string_pretty(&app.game().analytics()).expect("Serializing analytics can't fail");
+        super::export::write_output(Some(path), json.as_bytes())?;
+    }
+    match args.record {
+        Some(path) => app.replay().save(path),
+        None => Ok(()),
+    }
+}
+
+fn load_or_generate(args: &Args, seed: Option<u64>) -> Result<Board, CliError> {
+    let puzzles = if let Some(puzzle) = &args.puzzle {
+        parse_puzzles(puzzle, None)?
+    } else if let Some(path) = &args.input {
+        read_puzzles_from(path)?
+    } else {
+        let seed = seed_or_random(seed);
+        let puzzle = match args.difficulty {
+            Some(difficulty) => generate_with_difficulty_seeded(difficulty, Symmetry::Rotational, seed),
+            None => generate_symmetric_seeded(Symmetry::Rotational, seed),
+        };
+        vec![puzzle]
+    };
+    puzzles.first().copied().ok_or(CliError::Parse(BoardParseError::Empty))
+}
+
+fn play(terminal: &mut DefaultTerminal, mut app: App, theme: &Theme) -> io::Result<App> {
+    while !app.should_quit() {
+        terminal.draw(|frame| ui::draw(frame, &app, theme, ui::HELP))?;
+        if let Event::Key(key) = event::read()? {
+            // Windows also reports key releases
+            if key.kind == KeyEventKind::Press {
+                app.handle_key(key);
+            }
+        }
+    }
+    Ok(app)
+}
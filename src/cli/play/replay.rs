@@ -0,0 +1,182 @@
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use sudoku::{format_line, parse_line, Board};
+
+use super::app::App;
+use super::theme::Theme;
+use super::ui;
+use crate::cli::CliError;
+
+const HELP: &str = "right/space: next move  left: previous move  home/end: first/last move  q: quit";
+
+/// A recorded play session: the puzzle and every move the player made, written by `play --record`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    /// The puzzle in the line format
+    pub puzzle: String,
+
+    pub moves: Vec<Move>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    /// Milliseconds since the session started
+    pub time_ms: u64,
+
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// Everything a player can do that changes the game. Cursor movements aren't recorded.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    /// Enters a value into a cell, or erases it if `value` is `None`
+    Enter { x: usize, y: usize, value: Option<u8> },
+
+    TogglePencilMark { x: usize, y: usize, value: u8 },
+
+    Hint,
+
+    /// Fills all naked and hidden singles at once
+    FillSingles,
+
+    Undo,
+
+    Redo,
+}
+
+impl Action {
+    pub fn describe(&self) -> String {
+        match self {
+            Action::Enter { x, y, value: Some(value) } => format!("enter {value} at r{}c{}", y + 1, x + 1),
+            Action::Enter { x, y, value: None } => format!("erase r{}c{}", y + 1, x + 1),
+            Action::TogglePencilMark { x, y, value } => format!("toggle pencil mark {value} at r{}c{}", y + 1, x + 1),
+            Action::Hint => "ask for a hint".to_string(),
+            Action::FillSingles => "fill singles".to_string(),
+            Action::Undo => "undo".to_string(),
+            Action::Redo => "redo".to_string(),
+        }
+    }
+}
+
+impl Replay {
+    pub fn new(puzzle: &Board) -> Self {
+        Self {
+            puzzle: format_line(puzzle),
+            moves: vec![],
+        }
+    }
+
+    pub fn load(path: &PathBuf) -> Result<Self, CliError> {
+        let content = fs::read_to_string(path).map_err(|error| CliError::ReadInput {
+            path: path.display().to_string(),
+            error,
+        })?;
+        let replay: Replay = serde_json::from_str(&content).map_err(|error| CliError::Replay(error.to_string()))?;
+        parse_line(&replay.puzzle)?;
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: PathBuf) -> Result<(), CliError> {
+        let json = serde_json::to_string_pretty(self).expect("Serializing a replay can't fail");
+        crate::cli::export::write_output(Some(path), json.as_bytes())
+    }
+
+    /// The state of the game after the first `num_moves` moves
+    fn app_after(&self, num_moves: usize) -> App {
+        let mut app = App::new(parse_line(&self.puzzle).expect("Checked in Replay::load"));
+        for step in &self.moves[..num_moves] {
+            app.apply(step.action);
+        }
+        let status = match num_moves.checked_sub(1).map(|index| self.moves[index]) {
+            Some(step) => format!(
+                "Move {num_moves}/{} at {}:{:02}: {}. {}",
+                self.moves.len(),
+                step.time_ms / 60_000,
+                step.time_ms / 1000 % 60,
+                step.action.describe(),
+                app.message()
+            ),
+            None => format!("Start, {} moves recorded", self.moves.len()),
+        };
+        app.set_message(status);
+        app
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The replay file written by `play --record`
+    file: PathBuf,
+
+    /// Color theme: default, high-contrast or colorblind
+    #[arg(long)]
+    theme: Option<String>,
+}
+
+pub fn run(args: Args) -> Result<(), CliError> {
+    let theme = Theme::load(args.theme.as_deref())?;
+    let replay = Replay::load(&args.file)?;
+    let mut terminal = ratatui::try_init().map_err(CliError::Terminal)?;
+    let result = step_through(&mut terminal, &replay, &theme);
+    ratatui::restore();
+    result.map_err(CliError::Terminal)
+}
+
+fn step_through(terminal: &mut ratatui::DefaultTerminal, replay: &Replay, theme: &Theme) -> io::Result<()> {
+    let mut num_moves = 0;
+    loop {
+        let app = replay.app_after(num_moves);
+        terminal.draw(|frame| ui::draw(frame, &app, theme, HELP))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Right | KeyCode::Char(' ') | KeyCode::Char('l') => num_moves = (num_moves + 1).min(replay.moves.len()),
+            KeyCode::Left | KeyCode::Char('h') => num_moves = num_moves.saturating_sub(1),
+            KeyCode::Home => num_moves = 0,
+            KeyCode::End => num_moves = replay.moves.len(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_roundtrip() {
+        let mut replay = Replay::new(&Board::new_empty());
+        replay.moves = vec![
+            Move {
+                time_ms: 1500,
+                action: Action::Enter { x: 1, y: 2, value: Some(3) },
+            },
+            Move {
+                time_ms: 2000,
+                action: Action::Enter { x: 1, y: 2, value: None },
+            },
+            Move {
+                time_ms: 2500,
+                action: Action::TogglePencilMark { x: 0, y: 0, value: 9 },
+            },
+            Move {
+                time_ms: 3000,
+                action: Action::Hint,
+            },
+        ];
+        let json = serde_json::to_string(&replay).unwrap();
+        assert!(json.contains(r#"{"time_ms":1500,"action":"enter","x":1,"y":2,"value":3}"#));
+        assert_eq!(replay, serde_json::from_str(&json).unwrap());
+    }
+}
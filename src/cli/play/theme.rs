@@ -0,0 +1,209 @@
+use ratatui::style::{Color, Modifier, Style};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::cli::CliError;
+
+/// Styles used to draw the cells in the play mode.
+/// Conflicts are always underlined in addition to their color so they don't rely on color alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub given: Style,
+    pub entry: Style,
+    pub empty: Style,
+    pub conflict: Style,
+    pub same_value: Style,
+    pub cursor: Style,
+}
+
+impl Theme {
+    pub const NAMES: [&'static str; 3] = ["default", "high-contrast", "colorblind"];
+
+    pub fn by_name(name: &str) -> Option<Theme> {
+        match name {
+            "default" => Some(Self::default_theme()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "colorblind" => Some(Self::colorblind()),
+            _ => None,
+        }
+    }
+
+    fn default_theme() -> Theme {
+        Theme {
+            given: Style::new().add_modifier(Modifier::BOLD),
+            entry: Style::new().fg(Color::Cyan),
+            empty: Style::new().add_modifier(Modifier::DIM),
+            conflict: Style::new().fg(Color::Red).add_modifier(Modifier::UNDERLINED),
+            same_value: Style::new().bg(Color::DarkGray),
+            cursor: Style::new().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Only black and white plus bold text, for low quality displays and low vision
+    fn high_contrast() -> Theme {
+        Theme {
+            given: Style::new().fg(Color::White).add_modifier(Modifier::BOLD),
+            entry: Style::new().fg(Color::White),
+            empty: Style::new().fg(Color::White),
+            conflict: Style::new()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED | Modifier::SLOW_BLINK),
+            same_value: Style::new().add_modifier(Modifier::BOLD | Modifier::ITALIC),
+            cursor: Style::new().fg(Color::Black).bg(Color::White),
+        }
+    }
+
+    /// Uses the Okabe-Ito palette, which stays distinguishable for the common kinds of color blindness.
+    /// Conflicts are vermillion instead of red, entries sky blue instead of cyan.
+    fn colorblind() -> Theme {
+        Theme {
+            given: Style::new().add_modifier(Modifier::BOLD),
+            entry: Style::new().fg(Color::Rgb(0x56, 0xB4, 0xE9)),
+            empty: Style::new().add_modifier(Modifier::DIM),
+            conflict: Style::new()
+                .fg(Color::Rgb(0xD5, 0x5E, 0x00))
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            same_value: Style::new().bg(Color::Rgb(0x00, 0x49, 0x7A)),
+            cursor: Style::new().add_modifier(Modifier::REVERSED),
+        }
+    }
+
+    /// Loads the theme from the config file, if it exists. The theme given on the command line replaces the
+    /// base theme from the config file, but the styles overridden in the config file still apply.
+    ///
+    /// The config file is `$XDG_CONFIG_HOME/sudoku/theme` or `~/.config/sudoku/theme` and contains one setting per line:
+    /// ```text
+    /// # Start from one of the built-in themes
+    /// theme = colorblind
+    /// # Override single styles with colors, "on <color>" for the background, and modifiers
+    /// conflict = magenta bold underlined
+    /// same-value = on #404040
+    /// ```
+    pub fn load(name: Option<&str>) -> Result<Theme, CliError> {
+        let config = match config_path().map(fs::read_to_string) {
+            Some(Ok(config)) => config,
+            Some(Err(error)) if error.kind() != io::ErrorKind::NotFound => {
+                return Err(CliError::Theme(format!("Couldn't read config file: {error}")));
+            }
+            _ => String::new(),
+        };
+        Self::from_config(&config, name).map_err(CliError::Theme)
+    }
+
+    fn from_config(config: &str, name: Option<&str>) -> Result<Theme, String> {
+        let settings: Vec<(usize, &str, &str)> = config
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(index, line)| {
+                let (key, value) = line
+                    .split_once('=')
+                    .ok_or_else(|| format!("Line {}: Expected 'key = value'", index + 1))?;
+                Ok((index, key.trim(), value.trim()))
+            })
+            .collect::<Result<_, String>>()?;
+        let name = name.or_else(|| {
+            settings
+                .iter()
+                .find(|(_, key, _)| *key == "theme")
+                .map(|(_, _, value)| *value)
+        });
+        let mut theme = match name {
+            Some(name) => Self::by_name(name).ok_or_else(|| {
+                format!("Unknown theme '{name}', expected one of {}", Self::NAMES.join(", "))
+            })?,
+            None => Self::default_theme(),
+        };
+        for (index, key, value) in settings {
+            let style = match key {
+                "theme" => continue,
+                "given" => &mut theme.given,
+                "entry" => &mut theme.entry,
+                "empty" => &mut theme.empty,
+                "conflict" => &mut theme.conflict,
+                "same-value" => &mut theme.same_value,
+                "cursor" => &mut theme.cursor,
+                _ => return Err(format!("Line {}: Unknown setting '{key}'", index + 1)),
+            };
+            *style = parse_style(value).map_err(|err| format!("Line {}: {err}", index + 1))?;
+        }
+        Ok(theme)
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("sudoku").join("theme"))
+}
+
+/// Parses a style like `red on black bold`
+fn parse_style(spec: &str) -> Result<Style, String> {
+    let mut style = Style::new();
+    let mut words = spec.split_whitespace();
+    while let Some(word) = words.next() {
+        style = match word {
+            "bold" => style.add_modifier(Modifier::BOLD),
+            "dim" => style.add_modifier(Modifier::DIM),
+            "italic" => style.add_modifier(Modifier::ITALIC),
+            "underlined" => style.add_modifier(Modifier::UNDERLINED),
+            "reversed" => style.add_modifier(Modifier::REVERSED),
+            "on" => {
+                let color = words.next().ok_or("Expected a color after 'on'")?;
+                style.bg(parse_color(color)?)
+            }
+            color => style.fg(parse_color(color)?),
+        };
+    }
+    Ok(style)
+}
+
+fn parse_color(color: &str) -> Result<Color, String> {
+    color.parse().map_err(|_| format!("Unknown color '{color}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_themes() {
+        for name in Theme::NAMES {
+            assert!(Theme::by_name(name).is_some());
+        }
+        assert_eq!(Ok(Theme::default_theme()), Theme::from_config("", None));
+        assert_eq!(Ok(Theme::colorblind()), Theme::from_config("", Some("colorblind")));
+    }
+
+    #[test]
+    fn config() {
+        let config = "
+            # comment
+            theme = high-contrast
+            conflict = magenta on #101010 bold
+        ";
+        let theme = Theme::from_config(config, None).unwrap();
+        assert_eq!(Theme::high_contrast().given, theme.given);
+        assert_eq!(
+            Style::new()
+                .fg(Color::Magenta)
+                .bg(Color::Rgb(0x10, 0x10, 0x10))
+                .add_modifier(Modifier::BOLD),
+            theme.conflict
+        );
+        // The command line wins over the config file
+        assert_eq!(Theme::colorblind().cursor, Theme::from_config(config, Some("colorblind")).unwrap().cursor);
+    }
+
+    #[test]
+    fn config_errors() {
+        assert!(Theme::from_config("", Some("pink")).is_err());
+        assert_eq!(Err("Line 1: Unknown setting 'foo'".to_string()), Theme::from_config("foo = red", None));
+        assert_eq!(Err("Line 2: Unknown color 'blurple'".to_string()), Theme::from_config("\ngiven = blurple", None));
+        assert_eq!(Err("Line 1: Expected 'key = value'".to_string()), Theme::from_config("given", None));
+    }
+}
@@ -0,0 +1,105 @@
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use ratatui::Frame;
+use sudoku::{HEIGHT, WIDTH};
+
+use super::app::App;
+use super::theme::Theme;
+
+/// Each cell is drawn as 3 lines of 7 characters so that pencil marks fit in a 3x3 layout
+const CELL_WIDTH: usize = 7;
+const CELL_HEIGHT: usize = 3;
+const GRID_WIDTH: u16 = (WIDTH * CELL_WIDTH + 2 + 2) as u16;
+const GRID_HEIGHT: u16 = (HEIGHT * CELL_HEIGHT + 2 + 2) as u16;
+
+pub const HELP: &str = "arrows/hjkl: move  1-9: enter  0/backspace: clear  p: pencil mode  c: candidates  ?: hint  n: nudge  f: fill singles  u/r: undo/redo  q: quit";
+
+pub fn draw(frame: &mut Frame, app: &App, theme: &Theme, help: &str) {
+    let [grid_area, status_area, help_area] = Layout::vertical([
+        Constraint::Length(GRID_HEIGHT),
+        Constraint::Length(2),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [grid_area] = Layout::horizontal([Constraint::Length(GRID_WIDTH)]).areas(grid_area);
+
+    let title = match (app.pencil_mode(), app.show_candidates()) {
+        (false, false) => " Sudoku ",
+        (true, false) => " Sudoku (pencil) ",
+        (false, true) => " Sudoku (candidates) ",
+        (true, true) => " Sudoku (pencil, candidates) ",
+    };
+    frame.render_widget(Paragraph::new(grid_lines(app, theme)).block(Block::bordered().title(title)), grid_area);
+    frame.render_widget(Paragraph::new(app.message()).wrap(Wrap { trim: true }), status_area);
+    frame.render_widget(Paragraph::new(help).dim(), help_area);
+}
+
+fn grid_lines(app: &App, theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    for y in 0..HEIGHT {
+        if y > 0 && y % 3 == 0 {
+            lines.push(Line::from(region_separator()));
+        }
+        for line_in_cell in 0..CELL_HEIGHT {
+            let mut spans = vec![];
+            for x in 0..WIDTH {
+                if x > 0 && x % 3 == 0 {
+                    spans.push(Span::raw("│"));
+                }
+                spans.push(cell_span(app, theme, x, y, line_in_cell));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
+
+fn region_separator() -> String {
+    let region = "─".repeat(3 * CELL_WIDTH);
+    format!("{region}┼{region}┼{region}")
+}
+
+fn cell_span(app: &App, theme: &Theme, x: usize, y: usize, line_in_cell: usize) -> Span<'static> {
+    let text = match app.board().field(x, y).get() {
+        Some(value) if line_in_cell == 1 => format!("{:^CELL_WIDTH$}", value),
+        Some(_) => " ".repeat(CELL_WIDTH),
+        None => {
+            // Pencil marks 1-3 in the first line, 4-6 in the second, 7-9 in the third
+            let marks: Vec<u8> = app.pencil_marks(x, y).collect();
+            let line: String = (1..=3)
+                .map(|column| {
+                    let value = 3 * line_in_cell as u8 + column;
+                    if marks.contains(&value) {
+                        format!("{value} ")
+                    } else {
+                        "  ".to_string()
+                    }
+                })
+                .collect();
+            format!(" {line}")
+        }
+    };
+    Span::styled(text, cell_style(app, theme, x, y))
+}
+
+fn cell_style(app: &App, theme: &Theme, x: usize, y: usize) -> Style {
+    let mut style = if app.is_given(x, y) {
+        theme.given
+    } else if app.board().field(x, y).is_empty() {
+        theme.empty
+    } else {
+        theme.entry
+    };
+    if app.is_conflicting(x, y) {
+        style = style.patch(theme.conflict);
+    }
+    if app.has_selected_value(x, y) {
+        style = style.patch(theme.same_value);
+    }
+    if app.cursor() == (x, y) {
+        style = style.patch(theme.cursor);
+    }
+    style
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use sudoku::{Difficulty, PuzzlePool, PuzzleStore};
+
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The directory the puzzles are stored in
+    directory: PathBuf,
+
+    /// How many puzzles to keep ready per difficulty
+    #[arg(long, default_value_t = 10)]
+    size: usize,
+
+    /// The difficulties to keep puzzles for. Extreme puzzles aren't included by default since they take long to generate.
+    #[arg(long, value_delimiter = ',', default_values_t = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard, Difficulty::Expert])]
+    difficulties: Vec<Difficulty>,
+
+    /// Keep running in the background and refill the pool whenever puzzles are taken from it.
+    /// Without this, the pool is filled once and the command exits.
+    #[arg(long)]
+    daemon: bool,
+
+    /// How often the daemon checks for taken puzzles, in seconds
+    #[arg(long, default_value_t = 5, requires = "daemon")]
+    interval: u64,
+}
+
+pub fn run(args: Args) -> Result<(), CliError> {
+    let store = open(args.directory)?;
+    let targets: Vec<(Difficulty, usize)> = args
+        .difficulties
+        .iter()
+        .map(|&difficulty| (difficulty, args.size))
+        .collect();
+    if args.daemon {
+        eprintln!("Keeping {} puzzles per difficulty ready in {}", args.size, store.directory().display());
+        let path = store.directory().display().to_string();
+        // Runs until the process is killed
+        let pool = PuzzlePool::start(store, targets, Duration::from_secs(args.interval));
+        return pool.join().map_err(|error| CliError::Pool { path, error });
+    }
+    for (difficulty, target) in targets {
+        let generated = store
+            .refill(difficulty, target)
+            .map_err(|error| pool_error(&store, error))?;
+        eprintln!("{difficulty}: generated {generated} puzzles");
+    }
+    Ok(())
+}
+
+pub fn open(directory: PathBuf) -> Result<PuzzleStore, CliError> {
+    PuzzleStore::open(&directory).map_err(|error| CliError::Pool {
+        path: directory.display().to_string(),
+        error,
+    })
+}
+
+pub fn pool_error(store: &PuzzleStore, error: std::io::Error) -> CliError {
+    CliError::Pool {
+        path: store.directory().display().to_string(),
+        error,
+    }
+}
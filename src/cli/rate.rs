@@ -0,0 +1,163 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use sudoku::{format_line, rate_batch, Board, Complexity, Difficulty, SolverError, Technique, TechniqueReport};
+
+use super::input::InputArgs;
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: InputArgs,
+
+    #[arg(long, value_enum, default_value_t)]
+    output: RateOutput,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum RateOutput {
+    /// A human readable summary for each puzzle
+    #[default]
+    Text,
+
+    /// One row per puzzle with the difficulty and how often each technique was used
+    Csv,
+
+    /// An array with one object per puzzle
+    Json,
+}
+
+#[derive(Serialize)]
+struct Rating {
+    puzzle: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    difficulty: Option<Difficulty>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requires_guessing: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    complexity: Option<Complexity>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    techniques: BTreeMap<&'static str, usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Rating {
+    fn new(puzzle: String, report: &Result<TechniqueReport, SolverError>) -> Self {
+        match report {
+            Ok(report) => Self {
+                puzzle,
+                difficulty: Some(report.difficulty),
+                requires_guessing: Some(report.requires_guessing),
+                complexity: Some(report.complexity()),
+                techniques: report
+                    .technique_counts()
+                    .into_iter()
+                    .map(|(technique, count)| (technique.name(), count))
+                    .collect(),
+                error: None,
+            },
+            Err(err) => Self {
+                puzzle,
+                difficulty: None,
+                requires_guessing: None,
+                complexity: None,
+                techniques: BTreeMap::new(),
+                error: Some(err.to_string()),
+            },
+        }
+    }
+}
+
+/// Rates all puzzles in parallel and prints the results in the order of the input
+pub fn run(args: Args) -> Result<(), CliError> {
+    let boards = args.input.read_puzzles()?;
+    let reports = rate_batch(boards.iter().copied());
+    if let [Err(err)] = &reports[..] {
+        return Err((*err).into());
+    }
+    match args.output {
+        RateOutput::Text => print_text(&boards, &reports),
+        RateOutput::Csv => print_csv(&boards, &reports),
+        RateOutput::Json => {
+            let ratings: Vec<Rating> = boards
+                .iter()
+                .zip(&reports)
+                .map(|(board, report)| Rating::new(format_line(board), report))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&ratings).unwrap());
+        }
+    }
+    let num_failed = reports.iter().filter(|report| report.is_err()).count();
+    if num_failed > 0 {
+        return Err(CliError::BatchFailed(num_failed));
+    }
+    Ok(())
+}
+
+fn print_text(boards: &[Board], reports: &[Result<TechniqueReport, SolverError>]) {
+    for (index, (board, report)) in boards.iter().zip(reports).enumerate() {
+        let report = match report {
+            Ok(report) => report,
+            Err(err) => {
+                eprintln!("{}: {err}", format_line(board));
+                continue;
+            }
+        };
+        if boards.len() > 1 {
+            if index > 0 {
+                println!();
+            }
+            println!("{}", format_line(board));
+        }
+        println!("Difficulty: {}", report.difficulty);
+        println!("Complexity: {}", report.complexity().score);
+        for (technique, count) in report.technique_counts() {
+            println!("{technique}: {count}");
+        }
+        if report.requires_guessing {
+            println!("Requires guessing after {} logical steps", report.steps.len());
+        }
+    }
+}
+
+fn print_csv(boards: &[Board], reports: &[Result<TechniqueReport, SolverError>]) {
+    let technique_names: Vec<&str> = Technique::ALL.iter().map(|technique| technique.name()).collect();
+    println!("puzzle,difficulty,requires_guessing,complexity,{},error", technique_names.join(","));
+    for (board, report) in boards.iter().zip(reports) {
+        match report {
+            Ok(report) => {
+                let counts = report.technique_counts();
+                let columns: Vec<String> = Technique::ALL
+                    .iter()
+                    .map(|technique| {
+                        let count = counts
+                            .iter()
+                            .find(|(counted, _)| counted == technique)
+                            .map(|(_, count)| *count)
+                            .unwrap_or(0);
+                        count.to_string()
+                    })
+                    .collect();
+                println!(
+                    "{},{},{},{},{},",
+                    format_line(board),
+                    report.difficulty,
+                    report.requires_guessing,
+                    report.complexity().score,
+                    columns.join(",")
+                );
+            }
+            Err(err) => {
+                let empty_columns = ",".repeat(Technique::ALL.len());
+                println!("{},,,,{empty_columns}{err}", format_line(board));
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::fs;
+use std::path::PathBuf;
+use sudoku::{regenerate, Manifest};
+
+use super::{CliError, OutputFormat};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The manifest written by `generate --manifest`
+    manifest: PathBuf,
+
+    #[arg(long, value_enum, default_value_t)]
+    format: OutputFormat,
+}
+
+/// Prints the puzzles of the manifest after checking that their seeds still generate them
+pub fn run(args: Args) -> Result<(), CliError> {
+    let content = fs::read_to_string(&args.manifest).map_err(|error| CliError::ReadInput {
+        path: args.manifest.display().to_string(),
+        error,
+    })?;
+    let manifest: Manifest = serde_json::from_str(&content).map_err(|error| CliError::Manifest(error.to_string()))?;
+    for board in regenerate(&manifest)? {
+        println!("{}", args.format.format(&board));
+    }
+    Ok(())
+}
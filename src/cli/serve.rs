@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+use sudoku::{
+    format_line, generate_symmetric_seeded, generate_with_difficulty_cancellable, hint, parse_puzzles, rate_with_cancel,
+    solve_with_cancel, Board, Difficulty, Symmetry,
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use super::CliError;
+
+/// Request bodies are only a puzzle or a few options, so anything larger is rejected with 413 before it's read
+const MAX_BODY_SIZE: u64 = 64 * 1024;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+
+    /// The address to listen on. Use 0.0.0.0 to accept connections from other machines.
+    #[arg(long, default_value = "127.0.0.1")]
+    host: String,
+
+    /// Maximum number of requests computed at the same time. Further requests are rejected with 503.
+    #[arg(long, default_value_t = 4)]
+    max_concurrent: usize,
+
+    /// Seconds after which a request is answered with 503
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+}
+
+#[derive(Deserialize)]
+struct PuzzleRequest {
+    puzzle: String,
+}
+
+#[derive(Deserialize, Default)]
+struct GenerateRequest {
+    difficulty: Option<Difficulty>,
+    #[serde(default)]
+    symmetry: Symmetry,
+    seed: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+struct ApiError {
+    #[serde(skip)]
+    status: u16,
+    error: String,
+}
+
+impl ApiError {
+    fn new(status: u16, error: impl ToString) -> Self {
+        Self {
+            status,
+            error: error.to_string(),
+        }
+    }
+}
+
+/// Serves the JSON API until the process is killed:
+/// - `POST /solve` with `{"puzzle": "..."}` returns `{"solution": "..."}`
+/// - `POST /rate` with `{"puzzle": "..."}` returns the difficulty and how often each technique was used
+/// - `POST /hint` with `{"puzzle": "..."}` returns the next logical step
+/// - `POST /generate` with optional `difficulty`, `symmetry` and `seed` returns `{"puzzle": "...", "seed": ...}`
+///
+/// Puzzles use the line format. Each request is computed on its own thread. When a request times out, its
+/// computation is cancelled, so it frees its slot of `--max-concurrent` shortly after.
+pub fn run(args: Args) -> Result<(), CliError> {
+    let address = format!("{}:{}", args.host, args.port);
+    let server = Server::http(&address).map_err(|error| CliError::Serve(error.to_string()))?;
+    eprintln!("Listening on http://{address}");
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let timeout = Duration::from_secs(args.timeout);
+    for request in server.incoming_requests() {
+        if in_flight.fetch_add(1, Ordering::SeqCst) >= args.max_concurrent {
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            respond(request, Err(ApiError::new(503, "Too many concurrent requests")));
+            continue;
+        }
+        let in_flight = Arc::clone(&in_flight);
+        thread::spawn(move || handle(request, in_flight, timeout));
+    }
+    Ok(())
+}
+
+fn handle(mut request: Request, in_flight: Arc<AtomicUsize>, timeout: Duration) {
+    let body = match read_body(request.as_reader()) {
+        Ok(body) => body,
+        Err(error) => {
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            respond(request, Err(error));
+            return;
+        }
+    };
+    let method = request.method().clone();
+    let path = request.url().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+    {
+        let cancel = Arc::clone(&cancel);
+        thread::spawn(move || {
+            let result = route(&method, &path, &body, &cancel);
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            // The receiver is gone if the request timed out
+            let _ = sender.send(result);
+        });
+    }
+    let result = receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+        cancel.store(true, Ordering::Relaxed);
+        Err(timed_out())
+    });
+    respond(request, result);
+}
+
+/// Reads at most [MAX_BODY_SIZE] bytes, so a client can't make the server buffer an arbitrarily large body
+fn read_body(reader: impl Read) -> Result<String, ApiError> {
+    let mut body = String::new();
+    reader
+        .take(MAX_BODY_SIZE + 1)
+        .read_to_string(&mut body)
+        .map_err(|error| ApiError::new(400, error))?;
+    if body.len() as u64 > MAX_BODY_SIZE {
+        return Err(ApiError::new(413, format!("Request body is larger than {MAX_BODY_SIZE} bytes")));
+    }
+    Ok(body)
+}
+
+fn timed_out() -> ApiError {
+    ApiError::new(503, "Request timed out")
+}
+
+/// Computes the response. Once `cancel` is set, solving and generating stop early and the request times out.
+fn route(method: &Method, path: &str, body: &str, cancel: &AtomicBool) -> Result<Value, ApiError> {
+    if *method != Method::Post {
+        return Err(ApiError::new(405, "Only POST requests are supported"));
+    }
+    match path {
+        "/solve" => {
+            let solution = solve_with_cancel(parse_puzzle(body)?, &[], cancel)
+                .ok_or_else(timed_out)?
+                .map_err(|error| ApiError::new(422, error))?;
+            Ok(json!({ "solution": format_line(&solution) }))
+        }
+        "/rate" => {
+            let report = rate_with_cancel(&parse_puzzle(body)?, cancel)
+                .ok_or_else(timed_out)?
+                .map_err(|error| ApiError::new(422, error))?;
+            let techniques: serde_json::Map<String, Value> = report
+                .technique_counts()
+                .into_iter()
+                .map(|(technique, count)| (technique.name().to_string(), json!(count)))
+                .collect();
+            Ok(json!({
+                "difficulty": report.difficulty,
+                "requires_guessing": report.requires_guessing,
+                "techniques": techniques,
+            }))
+        }
+        "/hint" => match hint(&parse_puzzle(body)?, &[]) {
+            Some(step) => Ok(json!({
+                "technique": step.technique.name(),
+                "explanation": step.explanation(),
+                "placement": step.placement.map(|placement| placement.to_string()),
+            })),
+            None => Err(ApiError::new(422, "No logical step found")),
+        },
+        "/generate" => {
+            let request: GenerateRequest = if body.trim().is_empty() {
+                GenerateRequest::default()
+            } else {
+                serde_json::from_str(body).map_err(|error| ApiError::new(400, error))?
+            };
+            let seed = request.seed.unwrap_or_else(rand::random);
+            let puzzle = match request.difficulty {
+                Some(difficulty) => generate_with_difficulty_cancellable(difficulty, request.symmetry, seed, cancel)
+                    .ok_or_else(timed_out)?,
+                None => generate_symmetric_seeded(request.symmetry, seed),
+            };
+            Ok(json!({ "puzzle": format_line(&puzzle), "seed": seed }))
+        }
+        _ => Err(ApiError::new(404, format!("Unknown endpoint {path}"))),
+    }
+}
+
+fn parse_puzzle(body: &str) -> Result<Board, ApiError> {
+    let request: PuzzleRequest = serde_json::from_str(body).map_err(|error| ApiError::new(400, error))?;
+    let puzzles = parse_puzzles(&request.puzzle, None).map_err(|error| ApiError::new(400, error))?;
+    match puzzles[..] {
+        [puzzle] => Ok(puzzle),
+        _ => Err(ApiError::new(400, "Expected exactly one puzzle")),
+    }
+}
+
+fn respond(request: Request, result: Result<Value, ApiError>) {
+    let (status, body) = match result {
+        Ok(value) => (200, value.to_string()),
+        Err(error) => (error.status, serde_json::to_string(&error).unwrap()),
+    };
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(body)
+        .with_status_code(status)
+        .with_header(content_type);
+    // The client may have disconnected, there's nobody left to tell about errors
+    let _ = request.respond(response);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static NOT_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+    const PUZZLE: &str = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn routes() {
+        let body = json!({ "puzzle": PUZZLE }).to_string();
+        let solution = route(&Method::Post, "/solve", &body, &NOT_CANCELLED).unwrap();
+        assert_eq!(81, solution["solution"].as_str().unwrap().len());
+        assert_eq!("easy", route(&Method::Post, "/rate", &body, &NOT_CANCELLED).unwrap()["difficulty"]);
+        assert!(route(&Method::Post, "/hint", &body, &NOT_CANCELLED).unwrap()["placement"].is_string());
+        let generated = route(&Method::Post, "/generate", r#"{"seed": 5}"#, &NOT_CANCELLED).unwrap();
+        assert_eq!(generated, route(&Method::Post, "/generate", r#"{"seed": 5}"#, &NOT_CANCELLED).unwrap());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(405, route(&Method::Get, "/solve", "", &NOT_CANCELLED).unwrap_err().status);
+        assert_eq!(404, route(&Method::Post, "/foo", "", &NOT_CANCELLED).unwrap_err().status);
+        assert_eq!(400, route(&Method::Post, "/solve", "{}", &NOT_CANCELLED).unwrap_err().status);
+        assert_eq!(400, route(&Method::Post, "/solve", r#"{"puzzle": "123"}"#, &NOT_CANCELLED).unwrap_err().status);
+        let ambigious = json!({ "puzzle": ".".repeat(81) }).to_string();
+        assert_eq!(422, route(&Method::Post, "/solve", &ambigious, &NOT_CANCELLED).unwrap_err().status);
+    }
+
+    #[test]
+    fn cancelled() {
+        let cancelled = AtomicBool::new(true);
+        let expert = r#"{"difficulty": "expert", "seed": 5}"#;
+        assert_eq!(503, route(&Method::Post, "/generate", expert, &cancelled).unwrap_err().status);
+    }
+
+    #[test]
+    fn body_size() {
+        let body = "x".repeat(MAX_BODY_SIZE as usize);
+        assert_eq!(Some(body.clone()), read_body(body.as_bytes()).ok());
+        let too_large = body + "x";
+        assert_eq!(413, read_body(too_large.as_bytes()).unwrap_err().status);
+    }
+}
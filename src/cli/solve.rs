@@ -0,0 +1,126 @@
+use rayon::prelude::*;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::time::Instant;
+use sudoku::{parse_line, solve};
+
+use super::input::InputArgs;
+use super::{CliError, OutputFormat};
+
+/// Number of lines read from stdin before they're solved in parallel
+const BATCH_CHUNK_SIZE: usize = 4096;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: InputArgs,
+
+    #[arg(long, value_enum, default_value_t)]
+    format: OutputFormat,
+
+    /// Stream puzzles line by line from stdin, solve them in parallel and print the throughput at the end.
+    /// Lines can be in the line format or CSV rows with the puzzle in the first column, and the first line can be a
+    /// CSV header. Each other line gets one output row, `error: ...` if it can't be solved, so rows line up.
+    #[arg(long, conflicts_with_all = ["puzzle", "input"])]
+    batch: bool,
+}
+
+pub fn run(args: Args) -> Result<(), CliError> {
+    if args.batch {
+        return run_batch(args.format);
+    }
+    for board in args.input.read_puzzles()? {
+        let solution = solve(board)?;
+        println!("{}", args.format.format(&solution));
+    }
+    Ok(())
+}
+
+fn run_batch(format: OutputFormat) -> Result<(), CliError> {
+    let stdin_error = |error| CliError::ReadInput {
+        path: "stdin".to_string(),
+        error,
+    };
+    let start = Instant::now();
+    let mut num_solved = 0;
+    let mut num_failed = 0;
+    let mut output = BufWriter::new(io::stdout().lock());
+    let mut lines = io::stdin().lock().lines().enumerate();
+    loop {
+        let chunk: Vec<(usize, String)> = lines
+            .by_ref()
+            .take(BATCH_CHUNK_SIZE)
+            .map(|(index, line)| line.map(|line| (index, line)))
+            .collect::<Result<_, _>>()
+            .map_err(stdin_error)?;
+        if chunk.is_empty() {
+            break;
+        }
+        let results: Vec<(usize, Result<Option<String>, CliError>)> = chunk
+            .par_iter()
+            .map(|(index, line)| (*index, solve_line(*index, line, format)))
+            .collect();
+        for (index, result) in results {
+            let row = match result {
+                Ok(Some(solution)) => {
+                    // Empty lines stay empty
+                    if !solution.is_empty() {
+                        num_solved += 1;
+                    }
+                    solution
+                }
+                // The CSV header
+                Ok(None) => continue,
+                Err(err) => {
+                    eprintln!("Line {}: {err}", index + 1);
+                    num_failed += 1;
+                    format!("error: {err}")
+                }
+            };
+            writeln!(output, "{row}").map_err(CliError::WriteOutput)?;
+        }
+    }
+    output.flush().map_err(CliError::WriteOutput)?;
+    let elapsed = start.elapsed();
+    eprintln!(
+        "Solved {num_solved} puzzles in {:.3}s ({:.0} puzzles/sec)",
+        elapsed.as_secs_f64(),
+        num_solved as f64 / elapsed.as_secs_f64(),
+    );
+    if num_failed > 0 {
+        return Err(CliError::BatchFailed(num_failed));
+    }
+    Ok(())
+}
+
+/// Returns the solution of the puzzle in the line, an empty row for an empty line, or `None` for a CSV header
+fn solve_line(index: usize, line: &str, format: OutputFormat) -> Result<Option<String>, CliError> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(Some(String::new()));
+    }
+    let puzzle = line.split(',').next().unwrap().trim().trim_matches('"');
+    match parse_line(puzzle) {
+        Ok(board) => Ok(Some(format.format(&solve(board)?))),
+        Err(_) if index == 0 && line.contains(',') => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn batch_lines() {
+        let solution = solve_line(1, PUZZLE, OutputFormat::Line).unwrap().unwrap();
+        assert_eq!(Some(solution.clone()), solve_line(1, &format!("{PUZZLE},x"), OutputFormat::Line).unwrap());
+        assert_eq!(Some(String::new()), solve_line(1, " ", OutputFormat::Line).unwrap());
+        assert_eq!(None, solve_line(0, "puzzle,solution", OutputFormat::Line).unwrap());
+        // Only the first line can be a header
+        assert!(solve_line(1, "puzzle,solution", OutputFormat::Line).is_err());
+        assert!(solve_line(0, "puzzle", OutputFormat::Line).is_err());
+        assert!(solve_line(1, &".".repeat(81), OutputFormat::Line).is_err());
+    }
+}
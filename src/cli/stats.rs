@@ -0,0 +1,63 @@
+use clap::ValueEnum;
+use sudoku::stats::analyze;
+use sudoku::{Difficulty, Symmetry};
+
+use super::input::CollectionArgs;
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: CollectionArgs,
+
+    #[arg(long, value_enum, default_value_t)]
+    output: StatsOutput,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default)]
+enum StatsOutput {
+    /// A human readable summary
+    #[default]
+    Text,
+
+    /// One object with all distributions
+    Json,
+}
+
+pub fn run(args: Args) -> Result<(), CliError> {
+    let stats = analyze(args.input.read_puzzles()?);
+    if let StatsOutput::Json = args.output {
+        println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+        return Ok(());
+    }
+
+    println!("Puzzles: {}", stats.num_puzzles);
+    println!("Duplicates: {}", stats.num_duplicates);
+    println!("Without unique solution: {}", stats.num_invalid);
+    println!("Requiring guessing: {}", stats.num_requiring_guessing);
+
+    println!();
+    println!("Clues:");
+    for (clues, count) in &stats.clues {
+        println!("  {clues:>2}: {count}");
+    }
+
+    println!();
+    println!("Difficulty:");
+    for difficulty in Difficulty::ALL {
+        println!("  {difficulty}: {}", stats.difficulties.get(&difficulty).unwrap_or(&0));
+    }
+
+    println!();
+    println!("Techniques (puzzles using it, total steps):");
+    for (technique, technique_stats) in &stats.techniques {
+        println!("  {technique}: {}, {}", technique_stats.puzzles, technique_stats.steps);
+    }
+
+    println!();
+    println!("Symmetry:");
+    for symmetry in Symmetry::ALL {
+        println!("  {symmetry}: {}", stats.symmetries.get(&symmetry).unwrap_or(&0));
+    }
+    Ok(())
+}
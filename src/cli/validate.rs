@@ -0,0 +1,57 @@
+use sudoku::{format_line, solve, validate_with, Board, Rule, SolverError};
+
+use super::input::InputArgs;
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(flatten)]
+    input: InputArgs,
+}
+
+/// Checks that each puzzle is conflict-free, solvable and has a unique solution.
+/// Malformed input fails before anything is checked. For the exit codes, see [CliError::exit_code].
+pub fn run(args: Args) -> Result<(), CliError> {
+    let boards = args.input.read_puzzles()?;
+    let mut first_error = None;
+    for board in &boards {
+        if boards.len() > 1 {
+            print!("{}: ", format_line(board));
+        }
+        let result = validate(board);
+        match &result {
+            Ok(()) => println!("valid"),
+            Err(CliError::Invalid(_) | CliError::Conflicting) => println!("conflicting"),
+            Err(CliError::Solver(SolverError::NotSolvable)) => println!("not solvable"),
+            Err(CliError::Solver(SolverError::Ambigious)) => println!("ambigious"),
+            Err(err) => println!("{err}"),
+        }
+        if first_error.is_none() {
+            first_error = result.err();
+        }
+    }
+    match first_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+fn validate(board: &Board) -> Result<(), CliError> {
+    let report = validate_with(board, &[]);
+    if let Some(violation) = report.violations.first() {
+        let Rule::House(house) = violation.rule else {
+            unreachable!("We didn't pass any constraints");
+        };
+        let cells: Vec<String> = violation
+            .cells
+            .iter()
+            .map(|&(x, y)| format!("r{}c{}", y + 1, x + 1))
+            .collect();
+        return Err(CliError::Invalid(format!("conflict in {house} at {}", cells.join(", "))));
+    }
+    match solve(*board) {
+        Ok(_) => Ok(()),
+        Err(SolverError::Conflicting) => Err(CliError::Conflicting),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+use sudoku::Board;
+
+use super::input::read_puzzles_from;
+use super::CliError;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// The file with the puzzles
+    #[arg(long)]
+    puzzles: PathBuf,
+
+    /// The file with the solutions, in the same order as the puzzles
+    #[arg(long)]
+    solutions: PathBuf,
+}
+
+/// Checks that each solution is a valid filled sudoku and keeps the givens of its puzzle.
+/// Wrong solutions are reported on stderr by their position in the files.
+pub fn run(args: Args) -> Result<(), CliError> {
+    let puzzles = read_puzzles_from(&args.puzzles)?;
+    let solutions = read_puzzles_from(&args.solutions)?;
+    if puzzles.len() != solutions.len() {
+        return Err(CliError::WrongNumberOfSolutions {
+            puzzles: puzzles.len(),
+            solutions: solutions.len(),
+        });
+    }
+    let mut num_wrong = 0;
+    for (index, (puzzle, solution)) in puzzles.iter().zip(&solutions).enumerate() {
+        if let Some(problem) = check_solution(puzzle, solution) {
+            eprintln!("Puzzle {}: {problem}", index + 1);
+            num_wrong += 1;
+        }
+    }
+    println!("{} of {} solutions are correct", puzzles.len() - num_wrong, puzzles.len());
+    if num_wrong > 0 {
+        return Err(CliError::WrongSolutions(num_wrong));
+    }
+    Ok(())
+}
+
+fn check_solution(puzzle: &Board, solution: &Board) -> Option<&'static str> {
+    if !solution.is_filled() {
+        Some("The solution isn't completely filled")
+    } else if solution.has_conflicts() {
+        Some("The solution breaks the rules")
+    } else if !puzzle.is_subset_of(solution) {
+        Some("The solution doesn't match the givens of the puzzle")
+    } else {
+        None
+    }
+}
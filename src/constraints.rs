@@ -0,0 +1,857 @@
+#[cfg(feature = "alloc")]
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use itertools::Either;
+use core::fmt::{self, Display};
+#[cfg(feature = "alloc")]
+use core::str::FromStr;
+#[cfg(feature = "alloc")]
+use thiserror::Error;
+
+use crate::bitboard::fields_of_bitboard;
+use crate::board::{Board, HEIGHT, WIDTH};
+
+/// A [Constraint] is an additional rule that a sudoku variant adds on top of the classic row, column and 3x3 region rules.
+///
+/// Variants that change the houses themselves, like jigsaw sudokus whose irregular regions replace the 3x3 regions,
+/// aren't supported yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Constraint {
+    /// Two cells that are a knight's move (in chess) apart must not contain the same value.
+    AntiKnight,
+
+    /// Two cells that are a king's move (in chess) apart must not contain the same value.
+    /// Most of these cells already share a 3x3 region, the new restriction is only for diagonal neighbors across region borders.
+    AntiKing,
+
+    /// The cell at `greater` must contain a larger value than the cell at `smaller`.
+    /// The cells must be horizontally or vertically adjacent. Greater-than sudokus usually place these signs within 3x3
+    /// regions.
+    GreaterThan {
+        greater: (usize, usize),
+        smaller: (usize, usize),
+    },
+
+    /// Both main diagonals must contain each value exactly once (also known as X-sudoku).
+    Diagonal,
+
+    /// The four 3x3 windows offset by one cell from the top left corner must contain each value exactly once
+    /// (also known as hyper sudoku or windoku).
+    Windows,
+
+    /// Horizontally or vertically adjacent cells must not contain consecutive values.
+    NonConsecutive,
+
+    /// A dot between two horizontally or vertically adjacent cells (kropki sudoku), see [KropkiDot].
+    /// Cells without a dot between them aren't restricted.
+    Kropki {
+        dot: KropkiDot,
+        cells: [(usize, usize); 2],
+    },
+
+    /// The values in the cells of the cage must be different and add up to `sum` (killer sudoku).
+    /// Cells outside of all cages aren't restricted.
+    Killer { cage: Cage, sum: u8 },
+}
+
+/// The cells of a [Constraint::Killer] cage. They're stored as a bitboard, so constraints stay `Copy`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "Vec<(usize, usize)>", try_from = "Vec<(usize, usize)>"))]
+pub struct Cage(u128);
+
+impl Cage {
+    /// Returns `None` if one of the cells isn't on the board
+    pub fn new(cells: impl IntoIterator<Item = (usize, usize)>) -> Option<Self> {
+        cells.into_iter().try_fold(Cage(0), |cage, (x, y)| {
+            (x < WIDTH && y < HEIGHT).then_some(Cage(cage.0 | 1 << (x * HEIGHT + y)))
+        })
+    }
+
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < WIDTH && y < HEIGHT && self.0 & 1 << (x * HEIGHT + y) != 0
+    }
+
+    /// The cells of the cage, row by row
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..HEIGHT)
+            .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+            .filter(|&cell| self.contains(cell))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Cage> for Vec<(usize, usize)> {
+    fn from(cage: Cage) -> Self {
+        cage.cells().collect()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Vec<(usize, usize)>> for Cage {
+    type Error = &'static str;
+
+    fn try_from(cells: Vec<(usize, usize)>) -> Result<Self, Self::Error> {
+        Cage::new(cells).ok_or("Cage cell outside of the board")
+    }
+}
+
+/// The color of a [Constraint::Kropki] dot
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KropkiDot {
+    /// The values of the two cells are consecutive
+    White,
+
+    /// One of the values is double the other
+    Black,
+}
+
+impl KropkiDot {
+    /// Returns true if the two values can be on either side of the dot
+    pub fn allows(&self, a: u8, b: u8) -> bool {
+        match self {
+            KropkiDot::White => a.abs_diff(b) == 1,
+            KropkiDot::Black => a == 2 * b || b == 2 * a,
+        }
+    }
+}
+
+const KNIGHT_OFFSETS: [(isize, isize); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+const KING_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+const ORTHOGONAL_OFFSETS: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// A house is a set of 9 cells that must contain each value exactly once
+pub type House = [(usize, usize); 9];
+
+const DIAGONAL_HOUSES: [House; 2] = {
+    let mut houses = [[(0, 0); 9]; 2];
+    let mut i = 0;
+    while i < 9 {
+        houses[0][i] = (i, i);
+        houses[1][i] = (i, 8 - i);
+        i += 1;
+    }
+    houses
+};
+
+const WINDOW_HOUSES: [House; 4] = {
+    let mut houses = [[(0, 0); 9]; 4];
+    let mut window = 0;
+    while window < 4 {
+        let start_x = 1 + 4 * (window % 2);
+        let start_y = 1 + 4 * (window / 2);
+        let mut i = 0;
+        while i < 9 {
+            houses[window][i] = (start_x + i % 3, start_y + i / 3);
+            i += 1;
+        }
+        window += 1;
+    }
+    houses
+};
+
+impl Constraint {
+    /// Returns the cells that, because of this constraint, must not contain the same value as the cell at (x, y).
+    /// This doesn't include the cells in the same row, column or region unless the constraint itself adds them.
+    pub fn peers(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let offsets: &'static [(isize, isize)] = match self {
+            Constraint::AntiKnight => &KNIGHT_OFFSETS,
+            Constraint::AntiKing => &KING_OFFSETS,
+            // The two cells of a greater-than sign share a region, so there are no additional peers.
+            // Non-consecutive neighbors may contain the same value if they're in different regions.
+            // The two cells of a kropki dot never contain the same value anyway.
+            Constraint::GreaterThan { .. } | Constraint::NonConsecutive | Constraint::Kropki { .. } => &[],
+            Constraint::Diagonal | Constraint::Windows => {
+                return Either::Right(Either::Left(
+                    self.houses()
+                        .iter()
+                        .filter(move |house| house.contains(&(x, y)))
+                        .flatten()
+                        .copied()
+                        .filter(move |&cell| cell != (x, y)),
+                ));
+            }
+            // The cells of a cage are like a house with fewer than 9 cells
+            Constraint::Killer { cage, .. } => {
+                let cage = if cage.contains((x, y)) { *cage } else { Cage::default() };
+                return Either::Right(Either::Right(
+                    fields_of_bitboard(cage.0).filter(move |&cell| cell != (x, y)),
+                ));
+            }
+        };
+        Either::Left(offset_cells(x, y, offsets))
+    }
+
+    /// Returns the additional houses this constraint adds to the board
+    pub fn houses(&self) -> &'static [House] {
+        match self {
+            Constraint::Diagonal => &DIAGONAL_HOUSES,
+            Constraint::Windows => &WINDOW_HOUSES,
+            Constraint::AntiKnight
+            | Constraint::AntiKing
+            | Constraint::GreaterThan { .. }
+            | Constraint::NonConsecutive
+            | Constraint::Kropki { .. }
+            | Constraint::Killer { .. } => &[],
+        }
+    }
+
+    /// Returns the cells horizontally or vertically adjacent to the cell at (x, y)
+    pub fn orthogonal_neighbors(x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        offset_cells(x, y, &ORTHOGONAL_OFFSETS)
+    }
+
+    /// Returns true if the (potentially partially filled) board violates this constraint
+    pub fn has_conflicts(&self, board: &Board) -> bool {
+        match self {
+            Constraint::AntiKnight | Constraint::AntiKing => self.has_conflicts_between_peers(board),
+            Constraint::Diagonal | Constraint::Windows => {
+                self.houses().iter().any(|house| board.has_conflicts_in_fields(house))
+            }
+            Constraint::GreaterThan { greater, smaller } => {
+                let greater = board.field(greater.0, greater.1).get();
+                let smaller = board.field(smaller.0, smaller.1).get();
+                match (greater, smaller) {
+                    (Some(greater), Some(smaller)) => greater <= smaller,
+                    _ => false,
+                }
+            }
+            Constraint::Kropki { dot, cells: [a, b] } => {
+                match (board.field(a.0, a.1).get(), board.field(b.0, b.1).get()) {
+                    (Some(a), Some(b)) => !dot.allows(a.get(), b.get()),
+                    _ => false,
+                }
+            }
+            Constraint::Killer { cage, sum } => {
+                let values = cage.cells().filter_map(|(x, y)| board.field(x, y).get());
+                let (mut placed, mut total, mut filled) = (0u16, 0, 0);
+                for value in values {
+                    if placed & 1 << value.get() != 0 {
+                        return true;
+                    }
+                    placed |= 1 << value.get();
+                    total += u32::from(value.get());
+                    filled += 1;
+                }
+                total > u32::from(*sum) || (filled == cage.len() && total != u32::from(*sum))
+            }
+            Constraint::NonConsecutive => {
+                for x in 0..WIDTH {
+                    for y in 0..HEIGHT {
+                        if let Some(value) = board.field(x, y).get() {
+                            if Self::orthogonal_neighbors(x, y).any(|(neighbor_x, neighbor_y)| {
+                                board
+                                    .field(neighbor_x, neighbor_y)
+                                    .get()
+                                    .is_some_and(|neighbor| neighbor.get().abs_diff(value.get()) == 1)
+                            }) {
+                                return true;
+                            }
+                        }
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    /// Returns true if the constraint is well-formed, e.g. a greater-than sign or a kropki dot is between two
+    /// orthogonally adjacent cells on the board, or a killer cage has at most 9 cells and a sum that different values
+    /// in them can add up to
+    pub fn is_valid(&self) -> bool {
+        let on_board = |(x, y): (usize, usize)| x < WIDTH && y < HEIGHT;
+        let adjacent = |a: (usize, usize), b: (usize, usize)| a.0.abs_diff(b.0) + a.1.abs_diff(b.1) == 1;
+        match *self {
+            Constraint::GreaterThan { greater, smaller } => {
+                on_board(greater) && on_board(smaller) && adjacent(greater, smaller)
+            }
+            Constraint::Kropki { cells: [a, b], .. } => on_board(a) && on_board(b) && adjacent(a, b),
+            Constraint::Killer { cage, sum } => {
+                let cells = cage.len();
+                // The smallest sum uses the values 1..=cells, the largest the values 10-cells..=9
+                let sums = cells * (cells + 1) / 2..=cells * (19 - cells) / 2;
+                (1..=9).contains(&cells) && sums.contains(&usize::from(sum))
+            }
+            Constraint::AntiKnight
+            | Constraint::AntiKing
+            | Constraint::Diagonal
+            | Constraint::Windows
+            | Constraint::NonConsecutive => true,
+        }
+    }
+
+    fn has_conflicts_between_peers(&self, board: &Board) -> bool {
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                if let Some(value) = board.field(x, y).get() {
+                    if self
+                        .peers(x, y)
+                        .any(|(peer_x, peer_y)| board.field(peer_x, peer_y).get() == Some(value))
+                    {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+fn offset_cells(
+    x: usize,
+    y: usize,
+    offsets: &'static [(isize, isize)],
+) -> impl Iterator<Item = (usize, usize)> {
+    offsets.iter().filter_map(move |&(dx, dy)| {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+        (x < WIDTH && y < HEIGHT).then_some((x, y))
+    })
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ConstraintParseError {
+    #[error("Unknown constraint '{0}'")]
+    UnknownConstraint(String),
+
+    #[error("Invalid cell '{0}', expected a cell like 'r1c9'")]
+    InvalidCell(String),
+
+    #[error("Invalid greater-than sign '{0}', expected a sign like 'r1c1>r1c2'")]
+    InvalidGreaterThan(String),
+
+    #[error("Invalid kropki dot '{0}', expected a dot like 'r1c1-r1c2'")]
+    InvalidKropki(String),
+
+    #[error("Invalid killer cage '{0}', expected a sum and cells like '15 r1c1 r1c2 r2c1'")]
+    InvalidCage(String),
+
+    #[error("Line {line}: {error}")]
+    InLine {
+        line: usize,
+        error: Box<ConstraintParseError>,
+    },
+}
+
+/// The text format writes one constraint per line:
+/// ```text
+/// anti-knight
+/// anti-king
+/// diagonal
+/// windows
+/// non-consecutive
+/// greater-than r1c1>r1c2
+/// white-dot r1c1-r1c2
+/// black-dot r2c1-r3c1
+/// cage 15 r1c1 r1c2 r2c1
+/// ```
+/// Cells are written with 1-based row and column numbers, i.e. `r1c2` is the cell at x=1, y=0. A killer cage is
+/// written as its sum followed by its cells, row by row.
+impl Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::AntiKnight => write!(f, "anti-knight"),
+            Constraint::AntiKing => write!(f, "anti-king"),
+            Constraint::Diagonal => write!(f, "diagonal"),
+            Constraint::Windows => write!(f, "windows"),
+            Constraint::NonConsecutive => write!(f, "non-consecutive"),
+            Constraint::GreaterThan { greater, smaller } => write!(
+                f,
+                "greater-than r{}c{}>r{}c{}",
+                greater.1 + 1,
+                greater.0 + 1,
+                smaller.1 + 1,
+                smaller.0 + 1
+            ),
+            Constraint::Kropki { dot, cells: [a, b] } => {
+                let dot = match dot {
+                    KropkiDot::White => "white-dot",
+                    KropkiDot::Black => "black-dot",
+                };
+                write!(f, "{dot} r{}c{}-r{}c{}", a.1 + 1, a.0 + 1, b.1 + 1, b.0 + 1)
+            }
+            Constraint::Killer { cage, sum } => {
+                write!(f, "cage {sum}")?;
+                for (x, y) in cage.cells() {
+                    write!(f, " r{}c{}", y + 1, x + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromStr for Constraint {
+    type Err = ConstraintParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "anti-knight" => Ok(Constraint::AntiKnight),
+            "anti-king" => Ok(Constraint::AntiKing),
+            "diagonal" => Ok(Constraint::Diagonal),
+            "windows" => Ok(Constraint::Windows),
+            "non-consecutive" => Ok(Constraint::NonConsecutive),
+            _ => {
+                if let Some(sign) = s.strip_prefix("greater-than ") {
+                    let sign = sign.trim();
+                    let (greater, smaller) = sign
+                        .split_once('>')
+                        .ok_or_else(|| ConstraintParseError::InvalidGreaterThan(sign.to_string()))?;
+                    Ok(Constraint::GreaterThan {
+                        greater: parse_cell(greater.trim())?,
+                        smaller: parse_cell(smaller.trim())?,
+                    })
+                } else if let Some(cage) = s.strip_prefix("cage ") {
+                    let invalid = || ConstraintParseError::InvalidCage(cage.trim().to_string());
+                    let mut parts = cage.split_whitespace();
+                    let sum = parts.next().and_then(|sum| sum.parse().ok()).ok_or_else(invalid)?;
+                    let cells = parts.map(parse_cell).collect::<Result<Vec<_>, _>>()?;
+                    if cells.is_empty() {
+                        return Err(invalid());
+                    }
+                    let cage = Cage::new(cells).expect("Parsed cells are on the board");
+                    Ok(Constraint::Killer { cage, sum })
+                } else if let Some((dot, cells)) = s.split_once(' ') {
+                    let dot = match dot {
+                        "white-dot" => KropkiDot::White,
+                        "black-dot" => KropkiDot::Black,
+                        _ => return Err(ConstraintParseError::UnknownConstraint(s.to_string())),
+                    };
+                    let cells = cells.trim();
+                    let (a, b) = cells
+                        .split_once('-')
+                        .ok_or_else(|| ConstraintParseError::InvalidKropki(cells.to_string()))?;
+                    Ok(Constraint::Kropki {
+                        dot,
+                        cells: [parse_cell(a.trim())?, parse_cell(b.trim())?],
+                    })
+                } else {
+                    Err(ConstraintParseError::UnknownConstraint(s.to_string()))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn format_cell((x, y): (usize, usize)) -> String {
+    format!("r{}c{}", y + 1, x + 1)
+}
+
+#[cfg(feature = "alloc")]
+fn parse_cell(cell: &str) -> Result<(usize, usize), ConstraintParseError> {
+    let invalid = || ConstraintParseError::InvalidCell(cell.to_string());
+    let (row, col) = cell
+        .strip_prefix('r')
+        .and_then(|cell| cell.split_once('c'))
+        .ok_or_else(invalid)?;
+    let row: usize = row.parse().map_err(|_| invalid())?;
+    let col: usize = col.parse().map_err(|_| invalid())?;
+    if !(1..=HEIGHT).contains(&row) || !(1..=WIDTH).contains(&col) {
+        return Err(invalid());
+    }
+    Ok((col - 1, row - 1))
+}
+
+/// Parses a list of constraints in the text format described at [Constraint]'s [Display] implementation.
+/// Empty lines and lines starting with `#` are ignored.
+#[cfg(feature = "alloc")]
+pub fn parse_constraints(s: &str) -> Result<Vec<Constraint>, ConstraintParseError> {
+    s.lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(index, line)| {
+            line.parse().map_err(|error| ConstraintParseError::InLine {
+                line: index + 1,
+                error: Box::new(error),
+            })
+        })
+        .collect()
+}
+
+/// Writes a list of constraints in the text format that can be read by [parse_constraints]
+#[cfg(feature = "alloc")]
+pub fn format_constraints(constraints: &[Constraint]) -> String {
+    constraints
+        .iter()
+        .map(|constraint| format!("{constraint}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroU8;
+
+    #[test]
+    fn knight_peers() {
+        let mut corner: Vec<_> = Constraint::AntiKnight.peers(0, 0).collect();
+        corner.sort();
+        assert_eq!(vec![(1, 2), (2, 1)], corner);
+        assert_eq!(8, Constraint::AntiKnight.peers(4, 4).count());
+    }
+
+    #[test]
+    fn king_peers() {
+        let mut corner: Vec<_> = Constraint::AntiKing.peers(8, 8).collect();
+        corner.sort();
+        assert_eq!(vec![(7, 7), (7, 8), (8, 7)], corner);
+        assert_eq!(8, Constraint::AntiKing.peers(4, 4).count());
+    }
+
+    #[test]
+    fn knight_conflict() {
+        let board = Board::from_str(
+            "
+            ___ ___ ___
+            ___ ___ ___
+            __1 ___ ___
+
+            ___ ___ ___
+            ___ 1__ ___
+            ___ ___ ___
+
+            ___ ___ ___
+            ___ ___ ___
+            ___ ___ ___
+        ",
+        );
+        assert!(!board.has_conflicts());
+        assert!(Constraint::AntiKnight.has_conflicts(&board));
+        assert!(!Constraint::AntiKing.has_conflicts(&board));
+    }
+
+    #[test]
+    fn king_conflict() {
+        let board = Board::from_str(
+            "
+            ___ ___ ___
+            ___ ___ ___
+            __1 ___ ___
+
+            ___ 1__ ___
+            ___ ___ ___
+            ___ ___ ___
+
+            ___ ___ ___
+            ___ ___ ___
+            ___ ___ ___
+        ",
+        );
+        assert!(!board.has_conflicts());
+        assert!(Constraint::AntiKing.has_conflicts(&board));
+        assert!(!Constraint::AntiKnight.has_conflicts(&board));
+    }
+
+    #[test]
+    fn greater_than_validity() {
+        let sign = |greater, smaller| Constraint::GreaterThan { greater, smaller };
+        assert!(sign((0, 0), (1, 0)).is_valid());
+        assert!(sign((4, 5), (4, 4)).is_valid());
+        assert!(!sign((0, 0), (0, 0)).is_valid());
+        assert!(!sign((0, 0), (1, 1)).is_valid());
+        assert!(!sign((0, 0), (2, 0)).is_valid());
+        assert!(!sign((8, 8), (9, 8)).is_valid());
+    }
+
+    #[test]
+    fn greater_than_conflict() {
+        let mut board = Board::new_empty();
+        let constraint = Constraint::GreaterThan {
+            greater: (0, 0),
+            smaller: (1, 0),
+        };
+        assert!(!constraint.has_conflicts(&board));
+        board.field_mut(0, 0).set(NonZeroU8::new(3));
+        assert!(!constraint.has_conflicts(&board));
+        board.field_mut(1, 0).set(NonZeroU8::new(2));
+        assert!(!constraint.has_conflicts(&board));
+        board.field_mut(1, 0).set(NonZeroU8::new(4));
+        assert!(constraint.has_conflicts(&board));
+    }
+
+    #[test]
+    fn kropki_conflict() {
+        let mut board = Board::new_empty();
+        let white = Constraint::Kropki {
+            dot: KropkiDot::White,
+            cells: [(0, 0), (0, 1)],
+        };
+        let black = Constraint::Kropki {
+            dot: KropkiDot::Black,
+            cells: [(0, 0), (0, 1)],
+        };
+        board.field_mut(0, 0).set(NonZeroU8::new(4));
+        assert!(!white.has_conflicts(&board) && !black.has_conflicts(&board));
+        board.field_mut(0, 1).set(NonZeroU8::new(3));
+        assert!(!white.has_conflicts(&board) && black.has_conflicts(&board));
+        board.field_mut(0, 1).set(NonZeroU8::new(8));
+        assert!(white.has_conflicts(&board) && !black.has_conflicts(&board));
+        assert!(white.is_valid());
+        assert!(!Constraint::Kropki {
+            dot: KropkiDot::White,
+            cells: [(0, 0), (1, 1)],
+        }
+        .is_valid());
+    }
+
+    #[test]
+    fn killer_conflict() {
+        let mut board = Board::new_empty();
+        let cage = Cage::new([(0, 0), (1, 0), (0, 1)]).unwrap();
+        let killer = Constraint::Killer { cage, sum: 10 };
+        board.field_mut(0, 0).set(NonZeroU8::new(4));
+        board.field_mut(1, 0).set(NonZeroU8::new(5));
+        assert!(!killer.has_conflicts(&board));
+        board.field_mut(0, 1).set(NonZeroU8::new(2));
+        assert!(killer.has_conflicts(&board));
+        board.field_mut(0, 1).set(NonZeroU8::new(1));
+        assert!(!killer.has_conflicts(&board));
+        board.field_mut(1, 0).set(NonZeroU8::new(7));
+        assert!(killer.has_conflicts(&board));
+
+        // The values in a cage must be different, even if they're in different houses
+        let mut board = Board::new_empty();
+        let killer = Constraint::Killer { cage: Cage::new([(2, 2), (3, 2), (3, 3)]).unwrap(), sum: 15 };
+        board.field_mut(2, 2).set(NonZeroU8::new(4));
+        board.field_mut(3, 3).set(NonZeroU8::new(4));
+        assert!(!board.has_conflicts());
+        assert!(killer.has_conflicts(&board));
+        let mut peers: Vec<_> = killer.peers(3, 2).collect();
+        peers.sort();
+        assert_eq!(vec![(2, 2), (3, 3)], peers);
+        assert_eq!(0, killer.peers(0, 0).count());
+    }
+
+    #[test]
+    fn killer_validity() {
+        const ROW: [(usize, usize); 9] = [(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0)];
+        let killer = |cells: &[(usize, usize)], sum| Constraint::Killer { cage: Cage::new(cells.to_vec()).unwrap(), sum };
+        assert!(killer(&[(0, 0)], 9).is_valid());
+        assert!(killer(&[(0, 0), (0, 1)], 3).is_valid());
+        assert!(killer(&[(0, 0), (0, 1)], 17).is_valid());
+        assert!(!killer(&[(0, 0), (0, 1)], 2).is_valid());
+        assert!(!killer(&[(0, 0), (0, 1)], 18).is_valid());
+        assert!(!killer(&[], 0).is_valid());
+        assert!(killer(&ROW, 45).is_valid());
+        let mut ten = ROW.to_vec();
+        ten.push((0, 1));
+        assert!(!killer(&ten, 45).is_valid());
+        assert_eq!(None, Cage::new([(0, 0), (0, 9)]));
+    }
+
+    #[test]
+    fn non_consecutive_conflict() {
+        let mut board = Board::new_empty();
+        board.field_mut(2, 2).set(NonZeroU8::new(5));
+        board.field_mut(3, 2).set(NonZeroU8::new(7));
+        assert!(!Constraint::NonConsecutive.has_conflicts(&board));
+        board.field_mut(2, 3).set(NonZeroU8::new(4));
+        assert!(Constraint::NonConsecutive.has_conflicts(&board));
+    }
+
+    #[test]
+    fn house_conflicts() {
+        let mut board = Board::new_empty();
+        board.field_mut(0, 0).set(NonZeroU8::new(5));
+        board.field_mut(8, 8).set(NonZeroU8::new(6));
+        board.field_mut(3, 3).set(NonZeroU8::new(6));
+        assert!(!board.has_conflicts());
+        assert!(Constraint::Diagonal.has_conflicts(&board));
+        assert!(!Constraint::Windows.has_conflicts(&board));
+        board.field_mut(8, 8).set(NonZeroU8::new(7));
+        board.field_mut(2, 1).set(NonZeroU8::new(6));
+        assert!(!board.has_conflicts());
+        assert!(!Constraint::Diagonal.has_conflicts(&board));
+        assert!(Constraint::Windows.has_conflicts(&board));
+    }
+
+    #[test]
+    fn diagonal_peers() {
+        assert_eq!(8, Constraint::Diagonal.peers(0, 0).count());
+        assert_eq!(16, Constraint::Diagonal.peers(4, 4).count());
+        assert_eq!(0, Constraint::Diagonal.peers(1, 0).count());
+    }
+
+    #[test]
+    fn window_peers() {
+        let mut peers: Vec<_> = Constraint::Windows.peers(1, 1).collect();
+        peers.sort();
+        assert_eq!(
+            vec![(1, 2), (1, 3), (2, 1), (2, 2), (2, 3), (3, 1), (3, 2), (3, 3)],
+            peers
+        );
+        assert_eq!(0, Constraint::Windows.peers(4, 4).count());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn format_and_parse() {
+        let constraints = vec![
+            Constraint::AntiKnight,
+            Constraint::AntiKing,
+            Constraint::Diagonal,
+            Constraint::Windows,
+            Constraint::NonConsecutive,
+            Constraint::GreaterThan {
+                greater: (1, 0),
+                smaller: (2, 0),
+            },
+            Constraint::Kropki {
+                dot: KropkiDot::White,
+                cells: [(0, 0), (1, 0)],
+            },
+            Constraint::Kropki {
+                dot: KropkiDot::Black,
+                cells: [(0, 1), (0, 2)],
+            },
+            Constraint::Killer {
+                cage: Cage::new([(1, 1), (0, 0), (1, 0)]).unwrap(),
+                sum: 15,
+            },
+        ];
+        let formatted = format_constraints(&constraints);
+        assert_eq!(
+            "anti-knight\nanti-king\ndiagonal\nwindows\nnon-consecutive\ngreater-than r1c2>r1c3\n\
+             white-dot r1c1-r1c2\nblack-dot r2c1-r3c1\ncage 15 r1c1 r1c2 r2c2\n",
+            formatted
+        );
+        assert_eq!(Ok(constraints), parse_constraints(&formatted));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json() {
+        let constraints = vec![
+            Constraint::Windows,
+            Constraint::GreaterThan {
+                greater: (1, 0),
+                smaller: (2, 0),
+            },
+            Constraint::Kropki {
+                dot: KropkiDot::Black,
+                cells: [(0, 1), (0, 2)],
+            },
+            Constraint::Killer {
+                cage: Cage::new([(0, 0), (1, 0)]).unwrap(),
+                sum: 3,
+            },
+        ];
+        let json = serde_json::to_string(&constraints).unwrap();
+        assert_eq!(
+            concat!(
+                r#"["Windows",{"GreaterThan":{"greater":[1,0],"smaller":[2,0]}},"#,
+                r#"{"Kropki":{"dot":"Black","cells":[[0,1],[0,2]]}},"#,
+                r#"{"Killer":{"cage":[[0,0],[1,0]],"sum":3}}]"#
+            ),
+            json
+        );
+        assert_eq!(constraints, serde_json::from_str::<Vec<Constraint>>(&json).unwrap());
+        assert!(serde_json::from_str::<Cage>("[[0,9]]").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn parse_with_comments() {
+        let constraints = parse_constraints(
+            "
+            # Chess sudoku
+            anti-knight
+
+            greater-than r9c9 > r8c9
+        ",
+        );
+        assert_eq!(
+            Ok(vec![
+                Constraint::AntiKnight,
+                Constraint::GreaterThan {
+                    greater: (8, 8),
+                    smaller: (8, 7),
+                },
+            ]),
+            constraints
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn parse_errors() {
+        assert_eq!(
+            Err(ConstraintParseError::UnknownConstraint("anti-queen".to_string())),
+            "anti-queen".parse::<Constraint>()
+        );
+        assert_eq!(
+            Err(ConstraintParseError::InvalidCell("r0c1".to_string())),
+            "greater-than r0c1>r1c1".parse::<Constraint>()
+        );
+        assert_eq!(
+            Err(ConstraintParseError::InvalidGreaterThan("r1c1<r1c2".to_string())),
+            "greater-than r1c1<r1c2".parse::<Constraint>()
+        );
+        assert_eq!(
+            Err(ConstraintParseError::InvalidKropki("r1c1,r1c2".to_string())),
+            "white-dot r1c1,r1c2".parse::<Constraint>()
+        );
+        assert_eq!(
+            Err(ConstraintParseError::InvalidCage("r1c1 r1c2".to_string())),
+            "cage r1c1 r1c2".parse::<Constraint>()
+        );
+        assert_eq!(
+            Err(ConstraintParseError::InvalidCage("7".to_string())),
+            "cage 7".parse::<Constraint>()
+        );
+        assert_eq!(
+            Err(ConstraintParseError::InvalidCell("r1c10".to_string())),
+            "cage 7 r1c1 r1c10".parse::<Constraint>()
+        );
+        assert_eq!(
+            Err(ConstraintParseError::UnknownConstraint("gray-dot r1c1-r1c2".to_string())),
+            "gray-dot r1c1-r1c2".parse::<Constraint>()
+        );
+        assert_eq!(
+            Err(ConstraintParseError::InLine {
+                line: 2,
+                error: Box::new(ConstraintParseError::UnknownConstraint("foo".to_string())),
+            }),
+            parse_constraints("anti-king\nfoo")
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! Sets of puzzles that are embedded into the library, so benchmarks and comparisons between solvers can use the
+//! same inputs. The sets never change once they're released; new sets get new names instead.
+//!
+//! Only [seventeen_clues] is a sample of a published collection. The difficulty buckets are this crate's own sets,
+//! generated with `sudoku generate -n 50 --difficulty <difficulty> --seed 1216` and rated with the
+//! [Difficulty](crate::Difficulty) of their name. They make runs of this crate comparable, but they aren't standard
+//! test sets that other solvers report results for.
+//!
+//! Published benchmark sets like top1465 aren't embedded yet, so there's no `top1465()` function. They have to be
+//! copied verbatim from their source, with attribution, e.g. into a `top1465.sdm` file.
+
+use crate::board::Board;
+use crate::formats::{parse_puzzles, PuzzleFormat};
+
+const EASY: &str = include_str!("easy.sdm");
+const MEDIUM: &str = include_str!("medium.sdm");
+const HARD: &str = include_str!("hard.sdm");
+const SEVENTEEN_CLUES: &str = include_str!("seventeen_clues.sdm");
+
+/// The names of the sets for [by_name], e.g. for `sudoku bench --corpus <name>`
+pub const NAMES: [&str; 4] = ["easy", "medium", "hard", "seventeen-clues"];
+
+/// Returns the set with the given name, see [NAMES]
+pub fn by_name(name: &str) -> Option<Vec<Board>> {
+    match name {
+        "easy" => Some(easy()),
+        "medium" => Some(medium()),
+        "hard" => Some(hard()),
+        "seventeen-clues" => Some(seventeen_clues()),
+        _ => None,
+    }
+}
+
+fn parse(puzzles: &str) -> Vec<Board> {
+    parse_puzzles(puzzles, Some(PuzzleFormat::Sdm)).expect("Embedded puzzles are valid")
+}
+
+/// 50 puzzles that are rated easy
+pub fn easy() -> Vec<Board> {
+    parse(EASY)
+}
+
+/// 50 puzzles that are rated medium
+pub fn medium() -> Vec<Board> {
+    parse(MEDIUM)
+}
+
+/// 50 puzzles that are rated hard
+pub fn hard() -> Vec<Board> {
+    parse(HARD)
+}
+
+/// 8 puzzles with only 17 given fields, the minimum for a unique solution, taken from Gordon Royle's collection
+pub fn seventeen_clues() -> Vec<Board> {
+    parse(SEVENTEEN_CLUES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rate, solve, Difficulty};
+
+    #[test]
+    fn buckets_are_rated_correctly() {
+        for (puzzles, difficulty) in [
+            (easy(), Difficulty::Easy),
+            (medium(), Difficulty::Medium),
+            (hard(), Difficulty::Hard),
+        ] {
+            assert_eq!(50, puzzles.len());
+            for puzzle in puzzles {
+                assert_eq!(difficulty, rate(&puzzle).unwrap().difficulty);
+            }
+        }
+    }
+
+    #[test]
+    fn names() {
+        for name in NAMES {
+            assert!(by_name(name).is_some_and(|puzzles| !puzzles.is_empty()));
+        }
+        assert_eq!(Some(hard()), by_name("hard"));
+        assert_eq!(None, by_name("top1465"));
+    }
+
+    #[test]
+    fn seventeen_clues_are_unique() {
+        let puzzles = seventeen_clues();
+        assert!(!puzzles.is_empty());
+        for puzzle in puzzles {
+            assert_eq!(17, puzzle.num_filled());
+            assert!(solve(puzzle).is_ok());
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::board::Board;
+use crate::generator::{generate_with_difficulty_seeded, Symmetry};
+use crate::rating::Difficulty;
+
+/// [daily_puzzle] returns the puzzle of the day. Everyone asking for the same date and difficulty gets the same
+/// puzzle, no matter if it's the CLI, a native app or the web build.
+///
+/// The puzzle is generated with [generate_with_difficulty_seeded] and rotational symmetry, seeded by [daily_seed].
+pub fn daily_puzzle(date: Date, difficulty: Difficulty) -> Board {
+    generate_with_difficulty_seeded(difficulty, Symmetry::Rotational, daily_seed(date, difficulty))
+}
+
+/// The seed is the 64 bit FNV-1a hash of `YYYY-MM-DD/difficulty`, e.g. `2024-01-31/medium`.
+/// This must never change, otherwise the daily puzzles change for everyone. The puzzle for a seed is stable as
+/// described in "Reproducibility" in the crate documentation.
+pub fn daily_seed(date: Date, difficulty: Difficulty) -> u64 {
+    fnv1a(format!("{date}/{difficulty}").as_bytes())
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// A day in the proleptic Gregorian calendar. It's written and parsed as `YYYY-MM-DD`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "String", into = "String"))]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Returns `None` if there's no such day, e.g. for February 30th
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        if (0..=9999).contains(&year) && (1..=12).contains(&month) && day > 0 && day <= Date::days_in_month(year, month) {
+            Some(Date { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    /// The current day in UTC
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time is before 1970")
+            .as_secs();
+        Date::from_days_since_epoch((seconds / 86400) as i64)
+    }
+
+    /// Converts days since 1970-01-01 to a date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    pub fn from_days_since_epoch(days: i64) -> Date {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+        let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+        let year = (year_of_era + era * 400 + i64::from(month <= 2)) as i32;
+        Date { year, month, day }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    fn days_in_month(year: i32, month: u32) -> u32 {
+        let is_leap_year = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        match month {
+            2 if is_leap_year => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid date '{s}', expected YYYY-MM-DD");
+        let mut parts = s.splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return Err(invalid());
+        }
+        let year: i32 = year.parse().map_err(|_| invalid())?;
+        let month: u32 = month.parse().map_err(|_| invalid())?;
+        let day: u32 = day.parse().map_err(|_| invalid())?;
+        Date::new(year, month, day).ok_or_else(invalid)
+    }
+}
+
+impl TryFrom<String> for Date {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Date> for String {
+    fn from(date: Date) -> Self {
+        date.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_date() {
+        assert_eq!(Date::new(2024, 2, 29), "2024-02-29".parse().ok());
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2024-13-01".parse::<Date>().is_err());
+        assert!("2024-1-01".parse::<Date>().is_err());
+        assert!("yesterday".parse::<Date>().is_err());
+        assert_eq!("2024-02-29", "2024-02-29".parse::<Date>().unwrap().to_string());
+    }
+
+    #[test]
+    fn days_since_epoch() {
+        assert_eq!(Date::new(1970, 1, 1), Some(Date::from_days_since_epoch(0)));
+        assert_eq!(Date::new(2000, 3, 1), Some(Date::from_days_since_epoch(11017)));
+        assert_eq!(Date::new(2024, 2, 29), Some(Date::from_days_since_epoch(19782)));
+    }
+
+    #[test]
+    fn seed_is_stable() {
+        assert_eq!(0xaf63dc4c8601ec8c, fnv1a(b"a"));
+        let date = "2024-01-31".parse().unwrap();
+        assert_eq!(0xd2fb0f672b3e9940, daily_seed(date, Difficulty::Medium));
+        assert_ne!(daily_seed(date, Difficulty::Medium), daily_seed(date, Difficulty::Hard));
+    }
+
+    #[test]
+    fn same_puzzle_for_same_day() {
+        let date = "2024-01-31".parse().unwrap();
+        assert_eq!(daily_puzzle(date, Difficulty::Easy), daily_puzzle(date, Difficulty::Easy));
+    }
+}
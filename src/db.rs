@@ -0,0 +1,327 @@
+use std::collections::{BTreeMap, HashSet};
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+use crate::board::Board;
+use crate::constraints::Constraint;
+use crate::formats::{parse_puzzles, BoardParseError};
+use crate::hints::Technique;
+#[cfg(feature = "pack")]
+use crate::pack::{PackError, PuzzlePack};
+use crate::rating::{rate_with_constraints, Difficulty};
+
+/// What to select from a [PuzzleDb], e.g. "10 hard puzzles with 24 to 26 givens that need at most an X-Wing":
+///
+/// ```
+/// # use sudoku::{Difficulty, Query, Technique};
+/// let query = Query {
+///     difficulty: Some(Difficulty::Hard),
+///     givens: Some(24..=26),
+///     max_technique: Some(Technique::XWing),
+///     distinct: true,
+///     limit: Some(10),
+/// };
+/// ```
+///
+/// Puzzles without a unique solution can't be rated, so they never match [Query::difficulty] or [Query::max_technique].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    pub difficulty: Option<Difficulty>,
+
+    /// The range of the number of given fields
+    pub givens: Option<RangeInclusive<usize>>,
+
+    /// The hardest technique the puzzle may need. Puzzles that require guessing need more than any technique.
+    pub max_technique: Option<Technique>,
+
+    /// Skip puzzles that can be transformed into a puzzle that was already returned, see [Board::fingerprint].
+    /// Variant puzzles are only skipped if they are identical to one that was already returned, including their
+    /// constraints.
+    pub distinct: bool,
+
+    /// The maximum number of puzzles to return
+    pub limit: Option<usize>,
+}
+
+/// The parts of a [TechniqueReport](crate::TechniqueReport) that queries look at
+#[derive(Clone, Copy, Debug)]
+struct Rating {
+    difficulty: Difficulty,
+    hardest_technique: Option<Technique>,
+    requires_guessing: bool,
+}
+
+#[derive(Default)]
+struct Entry {
+    /// The puzzle and its constraints. Decoded on the first query that reaches it if the puzzle is in a
+    /// [PuzzlePack](crate::PuzzlePack).
+    puzzle: OnceLock<(Board, Vec<Constraint>)>,
+
+    /// Rated on the first query that needs it. `None` if the puzzle doesn't have a unique solution.
+    rating: OnceLock<Option<Rating>>,
+}
+
+impl Entry {
+    fn rating(&self, (puzzle, constraints): &(Board, Vec<Constraint>)) -> Option<Rating> {
+        *self.rating.get_or_init(|| {
+            rate_with_constraints(puzzle, constraints).ok().map(|report| Rating {
+                difficulty: report.difficulty,
+                hardest_technique: report.hardest_technique(),
+                requires_guessing: report.requires_guessing,
+            })
+        })
+    }
+
+    fn matches(&self, puzzle: &(Board, Vec<Constraint>), query: &Query) -> bool {
+        if query.difficulty.is_none() && query.max_technique.is_none() {
+            return true;
+        }
+        let Some(rating) = self.rating(puzzle) else {
+            return false;
+        };
+        let difficulty_matches = query.difficulty.is_none_or(|difficulty| rating.difficulty == difficulty);
+        let technique_matches = query.max_technique.is_none_or(|max_technique| {
+            !rating.requires_guessing && rating.hardest_technique.is_none_or(|technique| technique <= max_technique)
+        });
+        difficulty_matches && technique_matches
+    }
+}
+
+/// A collection of puzzles that can be queried, e.g. for an app with an offline puzzle library.
+///
+/// Puzzles are indexed by their number of givens, so a query with [Query::givens] only looks at matching puzzles.
+/// Rating a puzzle is expensive, so puzzles are only rated once a query needs it, and the rating is kept for later
+/// queries. Queries are lazy: they stop rating puzzles once they have found enough.
+///
+/// With the `pack` feature, a database can be opened from a [PuzzlePack](crate::PuzzlePack). Only the number of
+/// givens of each puzzle is read up front, puzzles are decoded from the memory mapped file once a query reaches them.
+/// Variant puzzles are rated with their constraints, see [PuzzleDb::query_variants].
+pub struct PuzzleDb {
+    /// The puzzles of the first entries, if the database was opened from a pack
+    #[cfg(feature = "pack")]
+    pack: Option<PuzzlePack>,
+
+    entries: Vec<Entry>,
+
+    /// The indices of the entries for each number of givens, in the order they were added
+    by_givens: BTreeMap<usize, Vec<usize>>,
+}
+
+impl PuzzleDb {
+    pub fn new(puzzles: impl IntoIterator<Item = Board>) -> Self {
+        let mut db = Self {
+            #[cfg(feature = "pack")]
+            pack: None,
+            entries: vec![],
+            by_givens: BTreeMap::new(),
+        };
+        for puzzle in puzzles {
+            db.add(puzzle);
+        }
+        db
+    }
+
+    /// Loads the puzzles from a text in any of the [PuzzleFormat](crate::PuzzleFormat)s
+    pub fn parse(puzzles: &str) -> Result<Self, BoardParseError> {
+        Ok(Self::new(parse_puzzles(puzzles, None)?))
+    }
+
+    #[cfg(feature = "pack")]
+    pub fn open_pack(path: impl AsRef<std::path::Path>) -> Result<Self, PackError> {
+        Ok(Self::from_pack(PuzzlePack::open(path)?))
+    }
+
+    #[cfg(feature = "pack")]
+    pub fn from_pack(pack: PuzzlePack) -> Self {
+        let mut by_givens = BTreeMap::<usize, Vec<usize>>::new();
+        for index in 0..pack.len() {
+            by_givens.entry(pack.num_filled(index).unwrap()).or_default().push(index);
+        }
+        Self {
+            entries: (0..pack.len()).map(|_| Entry::default()).collect(),
+            pack: Some(pack),
+            by_givens,
+        }
+    }
+
+    pub fn add(&mut self, puzzle: Board) {
+        self.add_variant(puzzle, vec![]);
+    }
+
+    pub fn add_variant(&mut self, puzzle: Board, constraints: Vec<Constraint>) {
+        self.by_givens.entry(puzzle.num_filled()).or_default().push(self.entries.len());
+        self.entries.push(Entry {
+            puzzle: OnceLock::from((puzzle, constraints)),
+            rating: OnceLock::new(),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the puzzles that match the query, ordered by their number of givens and then in the order they were
+    /// added. Puzzles are only decoded and rated as the iterator advances. An empty range of givens matches nothing.
+    pub fn query<'a>(&'a self, query: &'a Query) -> impl Iterator<Item = &'a Board> + 'a {
+        self.query_variants(query).map(|(puzzle, _)| puzzle)
+    }
+
+    /// Like [PuzzleDb::query], but also returns the constraints of each puzzle, which are empty for classic ones
+    pub fn query_variants<'a>(&'a self, query: &'a Query) -> impl Iterator<Item = (&'a Board, &'a [Constraint])> + 'a {
+        let givens = query.givens.clone().unwrap_or(0..=usize::MAX);
+        // BTreeMap::range panics if the start is after the end
+        let givens = if givens.is_empty() { None } else { Some(self.by_givens.range(givens)) };
+        let mut seen = HashSet::new();
+        let mut seen_variants = HashSet::new();
+        givens
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, indices)| indices)
+            .map(|&index| (self.puzzle(index), &self.entries[index]))
+            .filter(|(puzzle, entry)| entry.matches(puzzle, query))
+            .filter(move |((puzzle, constraints), _)| {
+                !query.distinct
+                    || if constraints.is_empty() {
+                        seen.insert(puzzle.fingerprint())
+                    } else {
+                        seen_variants.insert((puzzle, constraints))
+                    }
+            })
+            .map(|((puzzle, constraints), _)| (puzzle, &constraints[..]))
+            .take(query.limit.unwrap_or(usize::MAX))
+    }
+
+    fn puzzle(&self, index: usize) -> &(Board, Vec<Constraint>) {
+        self.entries[index].puzzle.get_or_init(|| {
+            #[cfg(feature = "pack")]
+            if let Some(pack) = &self.pack {
+                return (pack.get(index).unwrap(), pack.constraints(index).unwrap());
+            }
+            unreachable!("Added puzzles are stored in their entry")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical::canonical_form;
+    use crate::formats::parse_line;
+    use crate::rating::rate;
+
+    /// Two easy, one medium and three hard puzzles
+    const PUZZLES: [&str; 6] = [
+        ".3.1..2..9..8.74........9.6...56.....123....4........1..4....5.79......2.2..45..9",
+        "28.3.7.....3..18........2....21.8......49.....79..6.1.5..9..4...3......29.46...83",
+        "62.4......1.7..9..3..5....1.......3....2.174...6.3..5....98.5..........8.75......",
+        ".1.......4..8.2.1.......849....2.69.1.9.743.......6.....6...7..7.4.8....8.......5",
+        "5..2.......36....7.6.4.............5.3..8..127..9......2....5461........48...79..",
+        "...8..2.6....37..8.8..16......3.....6...7..39.24..1...2.....6.4.......1..1...8.5.",
+    ];
+
+    fn puzzles() -> Vec<Board> {
+        PUZZLES.iter().map(|puzzle| parse_line(puzzle).unwrap()).collect()
+    }
+
+    fn db() -> PuzzleDb {
+        PuzzleDb::new(puzzles())
+    }
+
+    #[test]
+    fn query() {
+        let db = db();
+        assert_eq!(6, db.len());
+        assert_eq!(6, db.query(&Query::default()).count());
+
+        let query = Query {
+            difficulty: Some(Difficulty::Hard),
+            givens: Some(23..=24),
+            limit: Some(2),
+            ..Query::default()
+        };
+        let puzzles: Vec<&Board> = db.query(&query).collect();
+        assert_eq!(2, puzzles.len());
+        for puzzle in &puzzles {
+            assert!((23..=24).contains(&puzzle.num_filled()));
+            assert_eq!(Difficulty::Hard, rate(puzzle).unwrap().difficulty);
+        }
+        assert!(puzzles.windows(2).all(|pair| pair[0].num_filled() <= pair[1].num_filled()));
+
+        let query = Query {
+            max_technique: Some(Technique::HiddenSingle),
+            ..Query::default()
+        };
+        assert_eq!(2, db.query(&query).count());
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let query = Query {
+            givens: Some(26..=24),
+            ..Query::default()
+        };
+        assert_eq!(0, db.query(&query).count());
+    }
+
+    #[test]
+    fn distinct() {
+        let puzzle = puzzles()[0];
+        let mut db = PuzzleDb::new([puzzle, canonical_form(&puzzle), Board::new_empty()]);
+        db.add(puzzle);
+        let query = Query {
+            distinct: true,
+            ..Query::default()
+        };
+        assert_eq!(vec![&Board::new_empty(), &puzzle], db.query(&query).collect::<Vec<_>>());
+        let query = Query {
+            difficulty: Some(Difficulty::Easy),
+            ..Query::default()
+        };
+        assert_eq!(3, db.query(&query).count());
+
+        let mut db = PuzzleDb::new([]);
+        db.add_variant(puzzle, vec![Constraint::AntiKnight]);
+        db.add_variant(canonical_form(&puzzle), vec![Constraint::AntiKnight]);
+        db.add_variant(puzzle, vec![Constraint::AntiKnight]);
+        let query = Query {
+            distinct: true,
+            ..Query::default()
+        };
+        assert_eq!(2, db.query(&query).count());
+    }
+
+    #[cfg(feature = "pack")]
+    #[test]
+    fn pack() {
+        let puzzles = puzzles();
+        let path = std::env::temp_dir().join(format!("sudoku-db-{}.pack", std::process::id()));
+        PuzzlePack::write(&path, &puzzles).unwrap();
+        let mut db = PuzzleDb::open_pack(&path).unwrap();
+        db.add(Board::new_empty());
+        assert_eq!(7, db.len());
+        let query = Query {
+            difficulty: Some(Difficulty::Hard),
+            givens: Some(23..=24),
+            ..Query::default()
+        };
+        let in_memory = PuzzleDb::new(puzzles);
+        assert_eq!(in_memory.query(&query).collect::<Vec<_>>(), db.query(&query).collect::<Vec<_>>());
+        assert_eq!(Some(&Board::new_empty()), db.query(&Query::default()).next());
+        drop(db);
+
+        let constraints = [Constraint::GreaterThan {
+            greater: (0, 0),
+            smaller: (1, 0),
+        }];
+        PuzzlePack::write_variants(&path, [(Board::new_empty(), &constraints[..])]).unwrap();
+        let db = PuzzleDb::open_pack(&path).unwrap();
+        assert_eq!(
+            vec![(&Board::new_empty(), &constraints[..])],
+            db.query_variants(&Query::default()).collect::<Vec<_>>()
+        );
+        drop(db);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
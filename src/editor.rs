@@ -0,0 +1,178 @@
+use std::num::NonZeroU8;
+
+use crate::board::Board;
+use crate::rating::{rate_unique, Difficulty};
+use crate::solver::{find_solutions, SolverError};
+
+/// A [PuzzleEditor] helps authoring a puzzle cell by cell. After each edit, it reports whether the puzzle has a
+/// unique solution and how difficult it is.
+///
+/// It keeps up to two solutions of the current puzzle, so most edits can be checked against them instead of
+/// solving the puzzle again. E.g. adding a given that agrees with both known solutions of an ambiguous puzzle
+/// keeps it ambiguous, and adding a given to a puzzle with a unique solution either keeps it or makes it unsolvable.
+#[derive(Clone, Debug)]
+pub struct PuzzleEditor {
+    board: Board,
+
+    /// Up to two solutions of the board. If there are less than two, these are all of its solutions.
+    /// Empty if the board has conflicts.
+    solutions: Vec<Board>,
+
+    /// Only set if the solution is unique
+    difficulty: Option<Difficulty>,
+
+    /// How often the editor had to run the solver, to check that edits are handled incrementally
+    num_solves: usize,
+}
+
+impl PuzzleEditor {
+    /// Starts editing with an empty board
+    pub fn new() -> Self {
+        Self::from_board(Board::new_empty())
+    }
+
+    /// Starts editing an existing puzzle
+    pub fn from_board(board: Board) -> Self {
+        let mut editor = Self {
+            board,
+            solutions: vec![],
+            difficulty: None,
+            num_solves: 0,
+        };
+        editor.solve();
+        editor
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Sets a given, `None` removes it
+    pub fn set(&mut self, x: usize, y: usize, value: Option<NonZeroU8>) {
+        let before = self.board.field(x, y).get();
+        if before == value {
+            return;
+        }
+        let was_conflicting = self.board.has_conflicts();
+        self.board.field_mut(x, y).set(value);
+        if was_conflicting || before.is_some() {
+            // Removing a given can add solutions we don't know yet, unless we already know two of them
+            let num_before = self.solutions.len();
+            self.solutions.retain(|solution| solution.field(x, y).get() == value || value.is_none());
+            if was_conflicting || num_before < 2 || self.solutions.len() < 2 {
+                self.solve();
+                return;
+            }
+        } else {
+            // Adding a given can only remove solutions. If we knew all of them, the remaining ones are still all.
+            let num_before = self.solutions.len();
+            self.solutions.retain(|solution| solution.field(x, y).get() == value);
+            if num_before == 2 && self.solutions.len() < 2 {
+                self.solve();
+                return;
+            }
+        }
+        self.update_difficulty();
+    }
+
+    /// The solution if it's unique, otherwise why not
+    pub fn status(&self) -> Result<&Board, SolverError> {
+        if self.board.has_conflicts() {
+            return Err(SolverError::Conflicting);
+        }
+        match &self.solutions[..] {
+            [] => Err(SolverError::NotSolvable),
+            [solution] => Ok(solution),
+            _ => Err(SolverError::Ambigious),
+        }
+    }
+
+    /// The difficulty of the puzzle, if its solution is unique, see [crate::rate]
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        self.difficulty
+    }
+
+    fn solve(&mut self) {
+        self.solutions = if self.board.has_conflicts() {
+            vec![]
+        } else {
+            self.num_solves += 1;
+            find_solutions(self.board, 2)
+        };
+        self.update_difficulty();
+    }
+
+    fn update_difficulty(&mut self) {
+        self.difficulty = match self.status() {
+            Ok(_) => Some(rate_unique(&self.board, &[]).difficulty),
+            Err(_) => None,
+        };
+    }
+}
+
+impl Default for PuzzleEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle() -> Board {
+        Board::from_str(
+            "
+            53_ _7_ ___
+            6__ 195 ___
+            _98 ___ _6_
+
+            8__ _6_ __3
+            4__ 8_3 __1
+            7__ _2_ __6
+
+            _6_ ___ 28_
+            ___ 419 __5
+            ___ _8_ _79
+        ",
+        )
+    }
+
+    #[test]
+    fn status_after_edits() {
+        let mut editor = PuzzleEditor::from_board(puzzle());
+        let solution = *editor.status().unwrap();
+        assert!(editor.difficulty().is_some());
+
+        // Adding a correct given keeps the solution, a wrong one makes the puzzle unsolvable
+        editor.set(2, 0, solution.field(2, 0).get());
+        assert_eq!(Ok(&solution), editor.status());
+        editor.set(3, 0, NonZeroU8::new(2));
+        assert_eq!(Err(SolverError::NotSolvable), editor.status());
+        assert_eq!(None, editor.difficulty());
+        editor.set(3, 0, NonZeroU8::new(5));
+        assert_eq!(Err(SolverError::Conflicting), editor.status());
+        editor.set(3, 0, None);
+        assert_eq!(Ok(&solution), editor.status());
+        assert!(editor.difficulty().is_some());
+        assert_eq!(2, editor.num_solves);
+    }
+
+    #[test]
+    fn ambiguous_stays_ambiguous() {
+        let mut editor = PuzzleEditor::new();
+        assert_eq!(Err(SolverError::Ambigious), editor.status());
+        let [first, second] = editor.solutions[..] else {
+            panic!("Expected two known solutions");
+        };
+        let (x, y) = (0..9)
+            .flat_map(|x| (0..9).map(move |y| (x, y)))
+            .find(|&(x, y)| first.field(x, y).get() == second.field(x, y).get())
+            .unwrap();
+        editor.set(x, y, first.field(x, y).get());
+        assert_eq!(Err(SolverError::Ambigious), editor.status());
+        editor.set(x, y, None);
+        assert_eq!(Err(SolverError::Ambigious), editor.status());
+        assert_eq!(1, editor.num_solves);
+    }
+}
@@ -0,0 +1,282 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroU8;
+use core::str::FromStr;
+use thiserror::Error;
+
+use crate::board::{Board, HEIGHT, NUM_FIELDS, WIDTH};
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BoardParseError {
+    #[error("Expected {NUM_FIELDS} cells but found {0}")]
+    WrongLength(usize),
+
+    #[error("Invalid character '{0}' in board")]
+    InvalidCharacter(char),
+
+    #[error("No puzzle found")]
+    Empty,
+
+    #[error("Line {line}: {error}")]
+    InLine {
+        line: usize,
+        error: Box<BoardParseError>,
+    },
+}
+
+/// The text formats puzzles can be read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PuzzleFormat {
+    /// A single puzzle in one line, see [parse_line]
+    Line,
+
+    /// A single puzzle spread over multiple lines, see [parse_grid]
+    Grid,
+
+    /// Multiple puzzles, each one in the line format in its own line
+    Sdm,
+
+    /// Multiple puzzles with the puzzle in the first column of each row, e.g. `puzzle,solution`.
+    /// A header row is skipped.
+    Csv,
+}
+
+impl PuzzleFormat {
+    /// Guesses the format of the input. Returns `None` if the input doesn't contain anything.
+    pub fn detect(input: &str) -> Option<PuzzleFormat> {
+        let lines: Vec<&str> = non_empty_lines(input).map(|(_, line)| line).collect();
+        if lines.is_empty() {
+            None
+        } else if lines[0].contains(',') {
+            Some(PuzzleFormat::Csv)
+        } else if lines.iter().all(|line| line.chars().count() == NUM_FIELDS) {
+            if lines.len() == 1 {
+                Some(PuzzleFormat::Line)
+            } else {
+                Some(PuzzleFormat::Sdm)
+            }
+        } else {
+            Some(PuzzleFormat::Grid)
+        }
+    }
+}
+
+/// Parses all puzzles in the input. If `format` is `None`, the format is detected with [PuzzleFormat::detect].
+pub fn parse_puzzles(input: &str, format: Option<PuzzleFormat>) -> Result<Vec<Board>, BoardParseError> {
+    let format = match format {
+        Some(format) => format,
+        None => PuzzleFormat::detect(input).ok_or(BoardParseError::Empty)?,
+    };
+    match format {
+        PuzzleFormat::Line => Ok(vec![parse_line(input)?]),
+        PuzzleFormat::Grid => Ok(vec![parse_grid(input)?]),
+        PuzzleFormat::Sdm => non_empty_lines(input)
+            .map(|(index, line)| parse_line(line).map_err(|error| in_line(index, error)))
+            .collect(),
+        PuzzleFormat::Csv => {
+            let mut lines = non_empty_lines(input).peekable();
+            let is_header = lines
+                .peek()
+                .is_some_and(|(_, line)| parse_line(csv_puzzle(line)).is_err());
+            if is_header {
+                lines.next();
+            }
+            lines
+                .map(|(index, line)| parse_line(csv_puzzle(line)).map_err(|error| in_line(index, error)))
+                .collect()
+        }
+    }
+}
+
+fn non_empty_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+fn csv_puzzle(line: &str) -> &str {
+    line.split(',').next().unwrap().trim().trim_matches('"')
+}
+
+fn in_line(index: usize, error: BoardParseError) -> BoardParseError {
+    BoardParseError::InLine {
+        line: index + 1,
+        error: Box::new(error),
+    }
+}
+
+/// Parses a board in the line format, i.e. 81 characters listing the cells row by row.
+/// Digits 1-9 are values, `0`, `.` and `_` are empty cells.
+pub fn parse_line(line: &str) -> Result<Board, BoardParseError> {
+    let line = line.trim();
+    let num_cells = line.chars().count();
+    if num_cells != NUM_FIELDS {
+        return Err(BoardParseError::WrongLength(num_cells));
+    }
+    let mut board = Board::new_empty();
+    for (index, c) in line.chars().enumerate() {
+        let value = parse_cell(c)?;
+        board.field_mut(index % WIDTH, index / WIDTH).set(value);
+    }
+    Ok(board)
+}
+
+/// Parses a board in the grid format, i.e. the cells row by row spread over multiple lines.
+/// Whitespace and the separators `|`, `-` and `+` are ignored, so this reads the output of [format_grid]
+/// as well as the usual ASCII art grids.
+pub fn parse_grid(grid: &str) -> Result<Board, BoardParseError> {
+    let cells: Vec<Option<NonZeroU8>> = grid
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '|' | '-' | '+'))
+        .map(parse_cell)
+        .collect::<Result<_, _>>()?;
+    if cells.len() != NUM_FIELDS {
+        return Err(BoardParseError::WrongLength(cells.len()));
+    }
+    let mut board = Board::new_empty();
+    for (index, value) in cells.into_iter().enumerate() {
+        board.field_mut(index % WIDTH, index / WIDTH).set(value);
+    }
+    Ok(board)
+}
+
+fn parse_cell(c: char) -> Result<Option<NonZeroU8>, BoardParseError> {
+    match c {
+        '0' | '.' | '_' => Ok(None),
+        '1'..='9' => Ok(NonZeroU8::new(c as u8 - b'0')),
+        _ => Err(BoardParseError::InvalidCharacter(c)),
+    }
+}
+
+/// Parses a board with [parse_grid], which also reads the line format
+impl FromStr for Board {
+    type Err = BoardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_grid(s)
+    }
+}
+
+/// Writes a board in the line format, using `.` for empty cells
+pub fn format_line(board: &Board) -> String {
+    (0..HEIGHT)
+        .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            board
+                .field(x, y)
+                .get()
+                .map(|value| char::from(b'0' + value.get()))
+                .unwrap_or('.')
+        })
+        .collect()
+}
+
+/// Writes a board in the grid format, i.e. 9 lines with spaces and empty lines separating the 3x3 regions
+pub fn format_grid(board: &Board) -> String {
+    format!("{board:?}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve;
+
+    const LINE: &str =
+        "..468..19..3..92.5.6......46.....7.2.....7......9....18...5...7.413.8....2..91...";
+
+    #[test]
+    fn line_roundtrip() {
+        let board = parse_line(LINE).unwrap();
+        assert_eq!(LINE, format_line(&board));
+        assert_eq!(
+            Board::from_str(
+                "
+                __4 68_ _19
+                __3 __9 2_5
+                _6_ ___ __4
+
+                6__ ___ 7_2
+                ___ __7 ___
+                ___ 9__ __1
+
+                8__ _5_ __7
+                _41 3_8 ___
+                _2_ _91 ___
+            "
+            ),
+            board
+        );
+        assert_eq!(Ok(board), parse_line(&LINE.replace('.', "0")));
+    }
+
+    #[test]
+    fn line_errors() {
+        assert_eq!(Err(BoardParseError::WrongLength(80)), parse_line(&LINE[1..]));
+        assert_eq!(
+            Err(BoardParseError::InvalidCharacter('x')),
+            parse_line(&LINE.replacen('.', "x", 1))
+        );
+    }
+
+    #[test]
+    fn grid_roundtrip() {
+        let board = parse_line(LINE).unwrap();
+        assert_eq!(Ok(board), parse_grid(&format_grid(&board)));
+        let ascii_art = "
+            . . 4 | 6 8 . | . 1 9
+            . . 3 | . . 9 | 2 . 5
+            . 6 . | . . . | . . 4
+            ------+-------+------
+            6 . . | . . . | 7 . 2
+            . . . | . . 7 | . . .
+            . . . | 9 . . | . . 1
+            ------+-------+------
+            8 . . | . 5 . | . . 7
+            . 4 1 | 3 . 8 | . . .
+            . 2 . | . 9 1 | . . .
+        ";
+        assert_eq!(Ok(board), parse_grid(ascii_art));
+        assert_eq!(Err(BoardParseError::WrongLength(80)), parse_grid(&format_grid(&board)[1..]));
+    }
+
+    #[test]
+    fn from_str() {
+        let board = parse_line(LINE).unwrap();
+        assert_eq!(Ok(board), LINE.parse());
+        assert_eq!(Ok(board), format_grid(&board).parse());
+        assert_eq!(Err(BoardParseError::WrongLength(82)), format!("{LINE}1").parse::<Board>());
+        assert_eq!(Err(BoardParseError::InvalidCharacter('x')), LINE.replacen('.', "x", 1).parse::<Board>());
+        assert_eq!(Err(BoardParseError::InvalidCharacter('５')), LINE.replacen('.', "５", 1).parse::<Board>());
+        assert_eq!(Err(BoardParseError::InvalidCharacter('½')), LINE.replacen('.', "½", 1).parse::<Board>());
+        assert_eq!(Err(BoardParseError::InvalidCharacter('Ⅷ')), LINE.replacen('.', "Ⅷ", 1).parse::<Board>());
+    }
+
+    #[test]
+    fn detect_format() {
+        let board = parse_line(LINE).unwrap();
+        let sdm = format!("{LINE}\n{}\n", LINE.replace('.', "0"));
+        let csv = format!("quizzes,solutions\n{LINE},{}\n", format_line(&solve(board).unwrap()));
+        assert_eq!(None, PuzzleFormat::detect("  \n"));
+        assert_eq!(Some(PuzzleFormat::Line), PuzzleFormat::detect(LINE));
+        assert_eq!(Some(PuzzleFormat::Grid), PuzzleFormat::detect(&format_grid(&board)));
+        assert_eq!(Some(PuzzleFormat::Sdm), PuzzleFormat::detect(&sdm));
+        assert_eq!(Some(PuzzleFormat::Csv), PuzzleFormat::detect(&csv));
+
+        assert_eq!(Ok(vec![board]), parse_puzzles(&format_grid(&board), None));
+        assert_eq!(Ok(vec![board, board]), parse_puzzles(&sdm, None));
+        assert_eq!(Ok(vec![board]), parse_puzzles(&csv, None));
+        assert_eq!(Err(BoardParseError::Empty), parse_puzzles("", None));
+        assert_eq!(
+            Err(BoardParseError::InLine {
+                line: 2,
+                error: Box::new(BoardParseError::InvalidCharacter('x')),
+            }),
+            parse_puzzles(&format!("{LINE}\n{}", LINE.replacen('.', "x", 1)), Some(PuzzleFormat::Sdm))
+        );
+    }
+}
@@ -0,0 +1,250 @@
+//! Generating boards for fuzz targets and property tests, with the `arbitrary` and `proptest` features.
+//!
+//! Boards with random values almost always have conflicts, and puzzles with random fields removed almost always
+//! have many solutions. So this also generates the boards that exercise the interesting code paths:
+//! puzzles with a unique solution, and puzzles that are one field away from being ambiguous or conflicting.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::num::NonZeroU8;
+
+use crate::board::{Board, HEIGHT, WIDTH};
+use crate::generator::{generate_symmetric_seeded, seeded_rng, Symmetry};
+use crate::geometry::peers;
+use crate::solver::generate_classic_with_rng;
+#[cfg(feature = "arbitrary")]
+use crate::solver::SolverContext;
+
+/// The kinds of boards [board_of_kind] generates
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BoardKind {
+    /// A solved board with random fields removed. It has at least one solution, but usually many.
+    Solvable,
+
+    /// A puzzle with a unique solution
+    Unique,
+
+    /// A puzzle with a unique solution, with one more given field removed. It's often ambiguous, but not always.
+    NearUnique,
+
+    /// A board that is one value away from a valid puzzle: a field has the value of one of its peers, so the board
+    /// always has a conflict
+    NearConflicting,
+}
+
+impl BoardKind {
+    pub const ALL: [BoardKind; 4] = [
+        BoardKind::Solvable,
+        BoardKind::Unique,
+        BoardKind::NearUnique,
+        BoardKind::NearConflicting,
+    ];
+}
+
+/// Generates a board of the given kind. The same seed always generates the same board.
+pub fn board_of_kind(kind: BoardKind, seed: u64) -> Board {
+    let mut rng = seeded_rng(seed);
+    match kind {
+        BoardKind::Solvable => {
+            let mut board = generate_classic_with_rng(&mut rng);
+            let removal_probability = rng.gen_range(0.0..=1.0);
+            for (x, y) in all_fields() {
+                if rng.gen_bool(removal_probability) {
+                    board.field_mut(x, y).set(None);
+                }
+            }
+            board
+        }
+        BoardKind::Unique => generate_symmetric_seeded(Symmetry::None, rng.gen()),
+        BoardKind::NearUnique => {
+            let mut board = generate_symmetric_seeded(Symmetry::None, rng.gen());
+            let filled: Vec<(usize, usize)> = all_fields().filter(|&(x, y)| !board.field(x, y).is_empty()).collect();
+            if let Some(&(x, y)) = filled.choose(&mut rng) {
+                board.field_mut(x, y).set(None);
+            }
+            board
+        }
+        BoardKind::NearConflicting => {
+            let mut board = generate_symmetric_seeded(Symmetry::None, rng.gen());
+            let mut empty: Vec<(usize, usize)> = all_fields().filter(|&(x, y)| board.field(x, y).is_empty()).collect();
+            empty.shuffle(&mut rng);
+            for (x, y) in empty {
+                let peer_values: Vec<NonZeroU8> = peers(x, y).filter_map(|(px, py)| board.field(px, py).get()).collect();
+                if let Some(&value) = peer_values.choose(&mut rng) {
+                    board.field_mut(x, y).set(Some(value));
+                    break;
+                }
+            }
+            board
+        }
+    }
+}
+
+fn all_fields() -> impl Iterator<Item = (usize, usize)> {
+    (0..WIDTH).flat_map(|x| (0..HEIGHT).map(move |y| (x, y)))
+}
+
+fn board_from_values(values: &[u8]) -> Board {
+    let mut board = Board::new_empty();
+    for ((x, y), &value) in all_fields().zip(values) {
+        board.field_mut(x, y).set(NonZeroU8::new(value));
+    }
+    board
+}
+
+/// Reads any board with values between 0 and 9 in a fifth of the cases, and a board of a random [BoardKind]
+/// otherwise, so fuzz targets get boards that are worth solving. Unlike [board_of_kind], only the solution comes from
+/// a seed. Which fields are removed or changed is read from the bytes directly, so the fuzzer can steer them.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Board {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let kind = u.int_in_range(0..=BoardKind::ALL.len())?;
+        if kind == BoardKind::ALL.len() {
+            let mut values = [0; WIDTH * HEIGHT];
+            for value in &mut values {
+                *value = u.int_in_range(0..=9)?;
+            }
+            Ok(board_from_values(&values))
+        } else {
+            arbitrary_board_of_kind(BoardKind::ALL[kind], u)
+        }
+    }
+}
+
+/// Reads a solution and the fields to remove from it from the bytes. Only [BoardKind::Unique] and
+/// [BoardKind::NearUnique] need the solver, to keep the removed fields that keep the solution unique.
+#[cfg(feature = "arbitrary")]
+fn arbitrary_board_of_kind(kind: BoardKind, u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Board> {
+    let solution = arbitrary_solution(u)?;
+    let removed: u128 = u.arbitrary()?;
+    let removed = all_fields().enumerate().filter(move |(index, _)| removed >> index & 1 == 1).map(|(_, field)| field);
+    let mut board = solution;
+    match kind {
+        BoardKind::Solvable => {
+            for (x, y) in removed {
+                board.field_mut(x, y).set(None);
+            }
+        }
+        BoardKind::Unique | BoardKind::NearUnique => {
+            let mut context = SolverContext::new();
+            for (x, y) in removed {
+                board.field_mut(x, y).set(None);
+                if context.solve(board).is_err() {
+                    board.field_mut(x, y).set(solution.field(x, y).get());
+                }
+            }
+            if kind == BoardKind::NearUnique {
+                let filled: Vec<(usize, usize)> = all_fields().filter(|&(x, y)| !board.field(x, y).is_empty()).collect();
+                let (x, y) = filled[u.choose_index(filled.len())?];
+                board.field_mut(x, y).set(None);
+            }
+        }
+        BoardKind::NearConflicting => {
+            for (x, y) in removed {
+                board.field_mut(x, y).set(None);
+            }
+            let (x, y) = u.choose(&all_fields().collect::<Vec<_>>())?.to_owned();
+            let (peer_x, peer_y) = u.choose(&peers(x, y).collect::<Vec<_>>())?.to_owned();
+            let value = solution.field(peer_x, peer_y).get();
+            board.field_mut(peer_x, peer_y).set(value);
+            board.field_mut(x, y).set(value);
+        }
+    }
+    Ok(board)
+}
+
+/// Reads a solved board from the bytes by seeding the generator of solved boards with them. So the solutions come
+/// from all the essentially different grids, not just the relabelings and permutations of one of them.
+#[cfg(feature = "arbitrary")]
+fn arbitrary_solution(u: &mut arbitrary::Unstructured<'_>) -> arbitrary::Result<Board> {
+    Ok(generate_classic_with_rng(&mut seeded_rng(u.arbitrary()?)))
+}
+
+/// Any board with values between 0 and 9 in each field, which almost always has conflicts
+#[cfg(feature = "proptest")]
+pub fn any_board() -> impl proptest::strategy::Strategy<Value = Board> {
+    use proptest::strategy::Strategy;
+
+    proptest::collection::vec(0..=9u8, WIDTH * HEIGHT).prop_map(|values| board_from_values(&values))
+}
+
+/// Boards of the given kind, see [board_of_kind]
+#[cfg(feature = "proptest")]
+pub fn boards_of_kind(kind: BoardKind) -> impl proptest::strategy::Strategy<Value = Board> {
+    use proptest::strategy::Strategy;
+
+    proptest::num::u64::ANY.prop_map(move |seed| board_of_kind(kind, seed))
+}
+
+/// Boards that have at least one solution, see [BoardKind::Solvable]
+#[cfg(feature = "proptest")]
+pub fn solvable_board() -> impl proptest::strategy::Strategy<Value = Board> {
+    boards_of_kind(BoardKind::Solvable)
+}
+
+/// Puzzles with a unique solution, see [BoardKind::Unique]
+#[cfg(feature = "proptest")]
+pub fn unique_board() -> impl proptest::strategy::Strategy<Value = Board> {
+    boards_of_kind(BoardKind::Unique)
+}
+
+/// Boards of any [BoardKind]
+#[cfg(feature = "proptest")]
+pub fn interesting_board() -> impl proptest::strategy::Strategy<Value = Board> {
+    use proptest::strategy::Strategy;
+
+    proptest::sample::select(BoardKind::ALL.to_vec()).prop_flat_map(boards_of_kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{canonical_form, solve, SolverError};
+
+    #[test]
+    fn kinds() {
+        for seed in 0..5 {
+            assert!(solve(board_of_kind(BoardKind::Solvable, seed)) != Err(SolverError::NotSolvable));
+            assert!(solve(board_of_kind(BoardKind::Unique, seed)).is_ok());
+            assert_ne!(
+                Err(SolverError::Conflicting),
+                solve(board_of_kind(BoardKind::NearUnique, seed))
+            );
+            assert!(board_of_kind(BoardKind::NearConflicting, seed).has_conflicts());
+            assert_eq!(board_of_kind(BoardKind::Unique, seed), board_of_kind(BoardKind::Unique, seed));
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_kinds() {
+        use rand::RngCore;
+
+        let mut rng = seeded_rng(1218);
+        for _ in 0..10 {
+            let mut bytes = [0; 64];
+            rng.fill_bytes(&mut bytes);
+            let board = |kind| arbitrary_board_of_kind(kind, &mut arbitrary::Unstructured::new(&bytes)).unwrap();
+            let solvable = board(BoardKind::Solvable);
+            assert!(!solvable.has_conflicts());
+            assert_ne!(Err(SolverError::NotSolvable), solve(solvable));
+            assert!(solve(board(BoardKind::Unique)).is_ok());
+            assert_ne!(Err(SolverError::Conflicting), solve(board(BoardKind::NearUnique)));
+            assert!(board(BoardKind::NearConflicting).has_conflicts());
+        }
+        let solution = |bytes: &[u8]| arbitrary_solution(&mut arbitrary::Unstructured::new(bytes)).unwrap();
+        assert!(solution(&[]).is_filled() && !solution(&[]).has_conflicts());
+        // Not just relabelings and permutations of one grid
+        assert_ne!(canonical_form(&solution(&[1; 8])), canonical_form(&solution(&[2; 8])));
+    }
+
+    #[cfg(feature = "proptest")]
+    proptest::proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(10))]
+
+        #[test]
+        fn solvable_boards_have_a_solution(board in solvable_board()) {
+            proptest::prop_assert_ne!(Err(SolverError::NotSolvable), solve(board));
+        }
+    }
+}
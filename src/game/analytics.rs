@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::{Change, Move};
+use crate::hints::{HouseId, Technique};
+
+/// How many of the cells with the longest thinking time [Analytics::hotspots] contains
+const NUM_HOTSPOTS: usize = 5;
+
+/// A post-game breakdown of a [Game](super::Game), see [Game::analytics](super::Game::analytics).
+///
+/// With the `serde` feature, this can be exported, e.g. as JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Analytics {
+    pub play_time: Duration,
+
+    /// The time spent on each cell the player changed, row by row.
+    /// The time between two moves counts for the cells the second move changed.
+    pub cell_times: Vec<CellTime>,
+
+    /// The cells the player spent the most time on, longest first
+    pub hotspots: Vec<CellTime>,
+
+    /// Hints and mistakes per row, column and region, for houses with at least one of them
+    pub houses: Vec<HouseStats>,
+
+    /// How often the hint engine needs each technique to solve the puzzle
+    pub techniques: BTreeMap<Technique, usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CellTime {
+    pub x: usize,
+    pub y: usize,
+    pub time: Duration,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HouseStats {
+    pub house: HouseId,
+    pub hints: usize,
+    pub mistakes: usize,
+}
+
+/// Splits the time before each move evenly between the cells whose values it changed,
+/// or the cells whose notes it changed if it didn't change any values
+pub(super) fn cell_times(history: &[Move]) -> Vec<CellTime> {
+    let mut times = BTreeMap::<(usize, usize), Duration>::new();
+    let mut previous = Duration::ZERO;
+    for played in history {
+        let spent = played.time.saturating_sub(previous);
+        previous = previous.max(played.time);
+        let mut cells: Vec<(usize, usize)> = played
+            .changes
+            .iter()
+            .filter_map(|change| match *change {
+                Change::Value { x, y, .. } => Some((x, y)),
+                Change::Note { .. } => None,
+            })
+            .collect();
+        if cells.is_empty() {
+            cells = played
+                .changes
+                .iter()
+                .map(|change| match *change {
+                    Change::Value { x, y, .. } | Change::Note { x, y, .. } => (x, y),
+                })
+                .collect();
+        }
+        cells.sort_unstable();
+        cells.dedup();
+        for &cell in &cells {
+            *times.entry(cell).or_default() += spent / cells.len() as u32;
+        }
+    }
+    let mut cell_times: Vec<CellTime> = times.into_iter().map(|((x, y), time)| CellTime { x, y, time }).collect();
+    cell_times.sort_by_key(|cell_time| (cell_time.y, cell_time.x));
+    cell_times
+}
+
+pub(super) fn hotspots(cell_times: &[CellTime]) -> Vec<CellTime> {
+    let mut hotspots = cell_times.to_vec();
+    hotspots.sort_by_key(|cell_time| std::cmp::Reverse(cell_time.time));
+    hotspots.truncate(NUM_HOTSPOTS);
+    hotspots
+}
+
+/// Counts each hint and mistake for the row, column and region of its cell
+pub(super) fn house_stats(hint_cells: &[(usize, usize)], mistake_cells: &[(usize, usize)]) -> Vec<HouseStats> {
+    HouseId::all(&[])
+        .into_iter()
+        .map(|house| {
+            let cells = house.cells();
+            HouseStats {
+                house,
+                hints: hint_cells.iter().filter(|cell| cells.contains(cell)).count(),
+                mistakes: mistake_cells.iter().filter(|cell| cells.contains(cell)).count(),
+            }
+        })
+        .filter(|stats| stats.hints > 0 || stats.mistakes > 0)
+        .collect()
+}
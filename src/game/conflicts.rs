@@ -0,0 +1,107 @@
+use std::num::NonZeroU8;
+use std::ops::Deref;
+
+use crate::board::{Board, HEIGHT, WIDTH};
+use crate::geometry::peers;
+
+/// The values of a [Game](super::Game), together with the cells that conflict with another cell in the same row,
+/// column or region. Conflicts are updated with each change, so they don't have to be recomputed for the whole board.
+///
+/// With the `serde` feature, this is serialized like the board itself, the conflicts are recomputed when loading.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Board", into = "Board"))]
+pub(super) struct TrackedBoard {
+    board: Board,
+
+    /// For each cell with a value, the number of peers with the same value
+    conflicts: [[u8; HEIGHT]; WIDTH],
+}
+
+impl TrackedBoard {
+    pub fn set(&mut self, x: usize, y: usize, value: Option<NonZeroU8>) {
+        if let Some(before) = self.board.field(x, y).get() {
+            for (peer_x, peer_y) in self.peers_with(x, y, before) {
+                self.conflicts[peer_x][peer_y] -= 1;
+            }
+            self.conflicts[x][y] = 0;
+        }
+        self.board.field_mut(x, y).set(value);
+        if let Some(after) = value {
+            for (peer_x, peer_y) in self.peers_with(x, y, after) {
+                self.conflicts[peer_x][peer_y] += 1;
+                self.conflicts[x][y] += 1;
+            }
+        }
+    }
+
+    pub fn is_conflicting(&self, x: usize, y: usize) -> bool {
+        self.conflicts[x][y] > 0
+    }
+
+    /// Like [Board::has_conflicts], but without looking at the values
+    pub fn has_conflicts(&self) -> bool {
+        self.conflicts.iter().flatten().any(|&count| count > 0)
+    }
+
+    fn peers_with(&self, x: usize, y: usize, value: NonZeroU8) -> Vec<(usize, usize)> {
+        peers(x, y)
+            .filter(|&(peer_x, peer_y)| self.board.field(peer_x, peer_y).get() == Some(value))
+            .collect()
+    }
+}
+
+impl From<Board> for TrackedBoard {
+    fn from(board: Board) -> Self {
+        let mut tracked = Self {
+            board: Board::new_empty(),
+            conflicts: [[0; HEIGHT]; WIDTH],
+        };
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                tracked.set(x, y, board.field(x, y).get());
+            }
+        }
+        tracked
+    }
+}
+
+impl From<TrackedBoard> for Board {
+    fn from(tracked: TrackedBoard) -> Self {
+        tracked.board
+    }
+}
+
+impl Deref for TrackedBoard {
+    type Target = Board;
+
+    fn deref(&self) -> &Board {
+        &self.board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_full_recomputation() {
+        let values = [(0, 0, 5), (4, 0, 5), (4, 4, 5), (3, 1, 5), (0, 0, 0), (4, 0, 7), (8, 8, 5), (3, 1, 0)];
+        let mut tracked = TrackedBoard::from(Board::new_empty());
+        for (x, y, value) in values {
+            tracked.set(x, y, NonZeroU8::new(value));
+            let conflicting = crate::validate_with(&tracked, &[])
+                .violations
+                .into_iter()
+                .flat_map(|violation| violation.cells)
+                .collect::<Vec<_>>();
+            for x in 0..WIDTH {
+                for y in 0..HEIGHT {
+                    assert_eq!(conflicting.contains(&(x, y)), tracked.is_conflicting(x, y), "({x}, {y})");
+                }
+            }
+            assert_eq!(tracked.board.has_conflicts(), tracked.has_conflicts());
+            assert_eq!(tracked, TrackedBoard::from(tracked.board));
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::fmt::{self, Debug};
+use std::num::NonZeroU8;
+
+use crate::hints::Step;
+
+/// What happened in a [crate::Game], passed to the callbacks registered with [crate::Game::on_change].
+/// Undoing and redoing moves emits the events for the resulting changes, e.g. undoing a placement emits
+/// [GameEvent::Erased] if the cell was empty before.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    Placed { x: usize, y: usize, value: NonZeroU8 },
+    Erased { x: usize, y: usize },
+    NoteAdded { x: usize, y: usize, value: NonZeroU8 },
+    NoteRemoved { x: usize, y: usize, value: NonZeroU8 },
+    Hint(Step),
+
+    /// The board got filled without conflicts
+    Solved,
+}
+
+/// The callbacks registered with [crate::Game::on_change]. Callbacks belong to the UI showing a game, so they aren't
+/// cloned or serialized with it and don't affect equality.
+#[derive(Default)]
+pub struct Observers(Vec<Callback>);
+
+type Callback = Box<dyn FnMut(&GameEvent) + Send>;
+
+impl Observers {
+    pub fn add(&mut self, callback: impl FnMut(&GameEvent) + Send + 'static) {
+        self.0.push(Box::new(callback));
+    }
+
+    pub fn emit(&mut self, event: GameEvent) {
+        for callback in &mut self.0 {
+            callback(&event);
+        }
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} observers", self.0.len())
+    }
+}
+
+impl PartialEq for Observers {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Observers {}
@@ -0,0 +1,1309 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroU8;
+use thiserror::Error;
+
+use std::time::Duration;
+
+use crate::board::{Board, HEIGHT, MAX_VALUE, NUM_FIELDS, WIDTH};
+use crate::candidates::Candidates;
+use crate::geometry::peers;
+use crate::hints::{hint, nudge, Nudge, Step};
+use crate::rating::{rate, Difficulty};
+use crate::solver::possible_values::PossibleValues;
+use crate::solver::{find_singles, solve, SolverError};
+
+mod analytics;
+mod conflicts;
+mod events;
+mod notes;
+#[cfg(feature = "rand")]
+mod race;
+mod replay;
+mod scoring;
+mod session;
+mod teaching;
+mod timer;
+
+pub use analytics::{Analytics, CellTime, HouseStats};
+pub use events::GameEvent;
+pub use notes::Notes;
+#[cfg(feature = "rand")]
+pub use race::{Race, RaceEntry, RaceError, RaceResult};
+pub use replay::{write_replay, ReplayError, ReplayReader};
+pub use scoring::{DifficultyScoring, Score, ScoringConfig};
+pub use session::{DifficultyStats, GameResult, Session};
+pub use teaching::{Annotation, Justification};
+pub use timer::Timer;
+
+use conflicts::TrackedBoard;
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameError {
+    #[error("Cell r{}c{} is outside of the board", .y + 1, .x + 1)]
+    OutOfBounds { x: usize, y: usize },
+
+    #[error("Cell r{}c{} is given and can't be changed", .y + 1, .x + 1)]
+    Given { x: usize, y: usize },
+
+    #[error("Value {0} is invalid, values must be between 1 and {MAX_VALUE}")]
+    InvalidValue(u8),
+
+    #[error("Cell r{}c{} already has a value, notes can only be added to empty cells", .y + 1, .x + 1)]
+    Filled { x: usize, y: usize },
+
+    #[error("Only games created with Game::branch can be committed")]
+    NotABranch,
+
+    #[error("The game changed since the branch was created")]
+    BranchOutdated,
+}
+
+/// A single change to the state of a [Game]. A [Move] consists of one or more changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "change", rename_all = "snake_case"))]
+pub enum Change {
+    /// The value of a cell changed, `None` means the cell is empty
+    Value {
+        x: usize,
+        y: usize,
+        before: Option<NonZeroU8>,
+        after: Option<NonZeroU8>,
+    },
+
+    /// A note was added to or removed from a cell
+    Note {
+        x: usize,
+        y: usize,
+        value: NonZeroU8,
+        added: bool,
+
+        /// True if the game made this change automatically instead of the player, see [Game::set_auto_remove_notes]
+        #[cfg_attr(feature = "serde", serde(default))]
+        derived: bool,
+    },
+}
+
+/// One action of the player. [Game::undo] and [Game::redo] always undo or redo a whole move.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Move {
+    pub changes: Vec<Change>,
+
+    /// The play time when the move was made, see [Game::elapsed]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub time: Duration,
+}
+
+/// Whether a value can be placed in a cell, see [Game::can_place]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Legality {
+    Allowed,
+
+    /// The cell is given and can't be changed
+    Given,
+
+    /// Another cell in the same row, column or region already has the value
+    Conflict { x: usize, y: usize },
+
+    /// The value doesn't break the rules yet, but it differs from the solution
+    Wrong,
+}
+
+/// How [Game::check_errors] decides whether an entry is wrong
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ErrorCheck {
+    /// Entries that differ from the solution are wrong, even if there's no conflict with other values yet.
+    /// If the puzzle doesn't have a unique solution, this falls back to [ErrorCheck::Conflicts].
+    #[default]
+    Solution,
+
+    /// Only entries that conflict with another value in the same row, column or region are wrong.
+    /// This doesn't give away anything the player couldn't see on the board.
+    Conflicts,
+}
+
+/// How far a [Game] is, see [Game::progress]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Progress {
+    /// Cells with a value, including the givens
+    pub filled: usize,
+
+    /// Empty cells
+    pub remaining: usize,
+
+    /// Notes over all cells, including notes of cells that have a value by now
+    pub notes: usize,
+
+    /// False if the entries so far contradict every solution, i.e. the player made a mistake somewhere
+    pub consistent: bool,
+}
+
+/// A [Game] is the state of a sudoku while a player solves it. It tells the givens of the puzzle apart from
+/// the values the player entered, and keeps a history of moves so they can be undone and redone.
+///
+/// With the `serde` feature, a game can be serialized to save it and continue later.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Game {
+    /// The givens. These can't be changed by the player.
+    puzzle: Board,
+
+    /// The givens plus the values entered by the player
+    board: TrackedBoard,
+
+    /// The solution of the puzzle, or `None` if it doesn't have a unique solution
+    #[cfg_attr(feature = "serde", serde(default))]
+    solution: Option<Board>,
+
+    /// The candidates the player noted down. These are independent from the values that are actually possible.
+    notes: Notes,
+
+    /// Moves that were made, oldest first
+    history: Vec<Move>,
+
+    /// Moves that were undone and can be redone, most recently undone last.
+    /// Making a new move clears this, like in any editor.
+    undone: Vec<Move>,
+
+    /// If set, placing a value removes it from the notes of the cells in the same row, column and region
+    #[cfg_attr(feature = "serde", serde(default))]
+    auto_remove_notes: bool,
+
+    /// How many hints the player asked for with [Game::hint]
+    #[cfg_attr(feature = "serde", serde(default))]
+    hints: usize,
+
+    /// How many values the player entered that differ from the solution. Correcting them doesn't undo the mistake.
+    #[cfg_attr(feature = "serde", serde(default))]
+    mistakes: usize,
+
+    /// The cell of each hint, see [Game::analytics]
+    #[cfg_attr(feature = "serde", serde(default))]
+    hint_cells: Vec<(usize, usize)>,
+
+    /// The cell of each mistake, see [Game::analytics]
+    #[cfg_attr(feature = "serde", serde(default))]
+    mistake_cells: Vec<(usize, usize)>,
+
+    /// If set, each value the player enters is checked against the logical steps available, see [Game::set_teaching]
+    #[cfg_attr(feature = "serde", serde(default))]
+    teaching: bool,
+
+    /// The coaching report of teaching mode, oldest first
+    #[cfg_attr(feature = "serde", serde(default))]
+    annotations: Vec<Annotation>,
+
+    /// The play time, which starts with [Game::start] and stops when the game is solved
+    #[cfg_attr(feature = "serde", serde(default))]
+    timer: Timer,
+
+    /// For games created with [Game::branch], the values and notes of the game it was branched from at that time
+    #[cfg_attr(feature = "serde", serde(default))]
+    branch_base: Option<(Board, Notes)>,
+
+    /// Named states to return to with [Game::restore]
+    #[cfg_attr(feature = "serde", serde(default))]
+    snapshots: BTreeMap<String, (Board, Notes)>,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observers: events::Observers,
+}
+
+impl Game {
+    /// Starts a game for the puzzle. This solves the puzzle, so [Game::check_errors] can compare against the solution.
+    pub fn new(puzzle: Board) -> Self {
+        Self::with_solution(puzzle, solve(puzzle).ok())
+    }
+
+    /// Like [Game::new], but with a solution that's already known, e.g. because the puzzle was generated from it
+    pub fn with_solution(puzzle: Board, solution: Option<Board>) -> Self {
+        Self {
+            puzzle,
+            board: puzzle.into(),
+            solution,
+            notes: Notes::default(),
+            history: vec![],
+            undone: vec![],
+            auto_remove_notes: false,
+            hints: 0,
+            mistakes: 0,
+            hint_cells: vec![],
+            mistake_cells: vec![],
+            teaching: false,
+            annotations: vec![],
+            timer: Timer::default(),
+            branch_base: None,
+            snapshots: BTreeMap::new(),
+            observers: events::Observers::default(),
+        }
+    }
+
+    pub fn puzzle(&self) -> &Board {
+        &self.puzzle
+    }
+
+    /// The givens plus the values entered by the player
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn is_given(&self, x: usize, y: usize) -> bool {
+        !self.puzzle.field(x, y).is_empty()
+    }
+
+    pub fn solution(&self) -> Option<&Board> {
+        self.solution.as_ref()
+    }
+
+    /// Checks whether placing the value in the cell would be allowed, e.g. to color the digit buttons of a UI
+    /// before the player picks one. This doesn't change the game.
+    pub fn can_place(&self, x: usize, y: usize, value: u8) -> Result<Legality, GameError> {
+        let value = Self::check_value(value)?;
+        match self.check_editable(x, y) {
+            Err(GameError::Given { .. }) => return Ok(Legality::Given),
+            result => result?,
+        }
+        let conflict = peers(x, y).find(|&(peer_x, peer_y)| self.board.field(peer_x, peer_y).get() == Some(value));
+        if let Some((peer_x, peer_y)) = conflict {
+            return Ok(Legality::Conflict { x: peer_x, y: peer_y });
+        }
+        match self.solution {
+            Some(solution) if solution.field(x, y).get() != Some(value) => Ok(Legality::Wrong),
+            _ => Ok(Legality::Allowed),
+        }
+    }
+
+    /// Returns the cells with wrong entries, row by row. Givens and empty cells are never wrong.
+    pub fn check_errors(&self, check: ErrorCheck) -> Vec<(usize, usize)> {
+        let entries = (0..HEIGHT)
+            .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+            .filter(|&(x, y)| !self.is_given(x, y) && !self.board.field(x, y).is_empty());
+        match (check, self.solution) {
+            (ErrorCheck::Solution, Some(solution)) => entries
+                .filter(|&(x, y)| self.board.field(x, y).get() != solution.field(x, y).get())
+                .collect(),
+            (ErrorCheck::Solution, None) | (ErrorCheck::Conflicts, _) => {
+                entries.filter(|&(x, y)| self.board.is_conflicting(x, y)).collect()
+            }
+        }
+    }
+
+    pub fn progress(&self) -> Progress {
+        let remaining = self.board.num_empty();
+        let consistent = match self.solution {
+            Some(solution) => self.board.is_subset_of(&solution),
+            None => !matches!(
+                solve(*self.board),
+                Err(SolverError::Conflicting | SolverError::NotSolvable)
+            ),
+        };
+        Progress {
+            filled: NUM_FIELDS - remaining,
+            remaining,
+            notes: self.notes.count(),
+            consistent,
+        }
+    }
+
+    /// Returns the cells whose value appears more than once in a row, column or region, row by row.
+    /// Unlike [ErrorCheck::Conflicts], this includes givens. It's kept up to date with each move, so it's cheap to call.
+    pub fn conflicts(&self) -> Vec<(usize, usize)> {
+        (0..HEIGHT)
+            .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.board.is_conflicting(x, y))
+            .collect()
+    }
+
+    pub fn is_conflicting(&self, x: usize, y: usize) -> bool {
+        self.board.is_conflicting(x, y)
+    }
+
+    /// Returns the cells that currently contain the value, row by row, e.g. to highlight all of them
+    pub fn cells_with_digit(&self, value: NonZeroU8) -> Vec<(usize, usize)> {
+        (0..HEIGHT)
+            .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.board.field(x, y).get() == Some(value))
+            .collect()
+    }
+
+    /// Returns the values that are still possible in each empty cell given the current board, independent of the
+    /// notes the player took. Wrong values on the board are taken into account like any other value.
+    pub fn candidates(&self) -> Candidates {
+        Candidates::new(&self.board)
+    }
+
+    /// Returns the cells that share a row, column or region with the cell. The cell itself isn't included.
+    pub fn peers_of(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        assert!(x < WIDTH && y < HEIGHT, "Cell ({x}, {y}) is out of bounds");
+        peers(x, y)
+    }
+
+    /// Returns how many more times the value has to be entered to complete the board.
+    /// This is zero once all nine are placed, but can't go below zero if the value is entered too often.
+    pub fn remaining_count(&self, value: NonZeroU8) -> usize {
+        // Each value appears once per row in the solution
+        HEIGHT.saturating_sub(self.cells_with_digit(value).len())
+    }
+
+    /// True if the board is filled without conflicts
+    pub fn is_solved(&self) -> bool {
+        self.board.is_filled() && !self.board.has_conflicts()
+    }
+
+    /// Enters a value into a cell, replacing the value that was there before
+    pub fn place(&mut self, x: usize, y: usize, value: u8) -> Result<(), GameError> {
+        self.set(x, y, Some(Self::check_value(value)?))
+    }
+
+    pub fn erase(&mut self, x: usize, y: usize) -> Result<(), GameError> {
+        self.set(x, y, None)
+    }
+
+    /// Sets the value of a cell that isn't given, `None` erases it. Setting a cell to the value it already has
+    /// doesn't add a move to the history.
+    pub fn set(&mut self, x: usize, y: usize, value: Option<NonZeroU8>) -> Result<(), GameError> {
+        self.check_editable(x, y)?;
+        if self.board.field(x, y).get() != value {
+            if let (Some(value), Some(solution)) = (value, self.solution) {
+                if solution.field(x, y).get() != Some(value) {
+                    self.mistakes += 1;
+                    self.mistake_cells.push((x, y));
+                }
+            }
+            if let (Some(value), true) = (value, self.teaching) {
+                if !self.board.has_conflicts() {
+                    self.annotations.push(Annotation::new(&self.board, x, y, value));
+                }
+            }
+            let changes = self.value_changes(x, y, value);
+            self.make_move(changes);
+        }
+        Ok(())
+    }
+
+    /// The changes for setting the value of a cell, including removing notes if [Game::auto_remove_notes] is enabled
+    fn value_changes(&self, x: usize, y: usize, value: Option<NonZeroU8>) -> Vec<Change> {
+        let before = self.board.field(x, y).get();
+        let mut changes = vec![Change::Value {
+            x,
+            y,
+            before,
+            after: value,
+        }];
+        if let (Some(value), true) = (value, self.auto_remove_notes) {
+            // These are part of the same move, so undoing the placement brings the notes back
+            changes.extend(
+                peers(x, y)
+                    .filter(|&(peer_x, peer_y)| self.notes.contains(peer_x, peer_y, value))
+                    .map(|(peer_x, peer_y)| Change::Note {
+                        x: peer_x,
+                        y: peer_y,
+                        value,
+                        added: false,
+                        derived: true,
+                    }),
+            );
+        }
+        changes
+    }
+
+    /// Fills all naked and hidden singles of the current board, i.e. cells with only one possible value and values
+    /// with only one possible cell in a row, column or region. This is a single move, so it's undone in one step.
+    /// Singles that only appear after filling these aren't filled. Returns how many cells were filled,
+    /// which is 0 if the board has conflicts.
+    pub fn autofill_singles(&mut self) -> usize {
+        if self.board.has_conflicts() {
+            return 0;
+        }
+        let singles = find_singles(&self.board, &PossibleValues::from_board(&self.board, &[]));
+        let mut changes = vec![];
+        for &(x, y, value) in &singles {
+            // Apply right away, so notes removed by an earlier single aren't removed twice
+            let single_changes = self.value_changes(x, y, Some(value));
+            for change in &single_changes {
+                self.apply(change, false);
+            }
+            changes.extend(single_changes);
+        }
+        if !changes.is_empty() {
+            self.push_move(changes);
+        }
+        singles.len()
+    }
+
+    /// If enabled, placing a value removes it from the notes of the other cells in its row, column and region,
+    /// like in most sudoku apps. This is off by default.
+    ///
+    /// Enabling it also removes the notes that contradict values already on the board. Disabling it brings back the
+    /// notes it removed, unless the player changed them since, see [Game::auto_removed_notes].
+    /// Either is a move, so it can be undone.
+    pub fn set_auto_remove_notes(&mut self, enabled: bool) {
+        if enabled == self.auto_remove_notes {
+            return;
+        }
+        self.auto_remove_notes = enabled;
+        let notes = if enabled {
+            self.contradicted_notes()
+        } else {
+            self.auto_removed_notes()
+        };
+        let changes: Vec<Change> = (0..HEIGHT)
+            .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+            .flat_map(|(x, y)| notes.values(x, y).map(move |value| (x, y, value)))
+            .map(|(x, y, value)| Change::Note {
+                x,
+                y,
+                value,
+                added: !enabled,
+                derived: true,
+            })
+            .collect();
+        if !changes.is_empty() {
+            self.make_move(changes);
+        }
+    }
+
+    /// The notes that were removed automatically and that the player didn't add or remove since.
+    /// Unlike the notes the player removed, these come back when [Game::set_auto_remove_notes] is disabled.
+    pub fn auto_removed_notes(&self) -> Notes {
+        let mut removed = Notes::default();
+        for change in self.history.iter().flat_map(|game_move| &game_move.changes) {
+            if let Change::Note { x, y, value, added, derived } = *change {
+                removed.set(x, y, value, derived && !added);
+            }
+        }
+        removed
+    }
+
+    /// Notes whose value is in another cell of the same row, column or region
+    fn contradicted_notes(&self) -> Notes {
+        let mut contradicted = Notes::default();
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                for value in self.notes.values(x, y) {
+                    if peers(x, y).any(|(peer_x, peer_y)| self.board.field(peer_x, peer_y).get() == Some(value)) {
+                        contradicted.set(x, y, value, true);
+                    }
+                }
+            }
+        }
+        contradicted
+    }
+
+    pub fn auto_remove_notes(&self) -> bool {
+        self.auto_remove_notes
+    }
+
+    /// Turns teaching mode on or off. In teaching mode, each value the player enters is annotated with the logical
+    /// deduction that justifies it, or with the deduction that was available instead if the player guessed.
+    /// Values entered while the board has conflicts aren't annotated.
+    pub fn set_teaching(&mut self, enabled: bool) {
+        self.teaching = enabled;
+    }
+
+    pub fn teaching(&self) -> bool {
+        self.teaching
+    }
+
+    /// The coaching report: the annotations of the values entered in teaching mode so far, oldest first.
+    /// Undoing a move keeps its annotation.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// The notes of all cells. Notes of cells that got a value are kept, so they come back when the value is erased.
+    pub fn notes(&self) -> &Notes {
+        &self.notes
+    }
+
+    pub fn add_note(&mut self, x: usize, y: usize, value: u8) -> Result<(), GameError> {
+        self.set_note(x, y, value, true)
+    }
+
+    pub fn remove_note(&mut self, x: usize, y: usize, value: u8) -> Result<(), GameError> {
+        self.set_note(x, y, value, false)
+    }
+
+    pub fn toggle_note(&mut self, x: usize, y: usize, value: u8) -> Result<(), GameError> {
+        let value = Self::check_value(value)?;
+        let has_note = x < WIDTH && y < HEIGHT && self.notes.contains(x, y, value);
+        self.set_note(x, y, value.get(), !has_note)
+    }
+
+    /// Adds or removes a note. Doesn't add a move to the history if the note already is in that state.
+    fn set_note(&mut self, x: usize, y: usize, value: u8, added: bool) -> Result<(), GameError> {
+        let value = Self::check_value(value)?;
+        self.check_editable(x, y)?;
+        if !self.board.field(x, y).is_empty() {
+            return Err(GameError::Filled { x, y });
+        }
+        if self.notes.contains(x, y, value) != added {
+            self.make_move(vec![Change::Note {
+                x,
+                y,
+                value,
+                added,
+                derived: false,
+            }]);
+        }
+        Ok(())
+    }
+
+    fn check_value(value: u8) -> Result<NonZeroU8, GameError> {
+        NonZeroU8::new(value)
+            .filter(|value| value.get() <= MAX_VALUE)
+            .ok_or(GameError::InvalidValue(value))
+    }
+
+    fn check_editable(&self, x: usize, y: usize) -> Result<(), GameError> {
+        if x >= WIDTH || y >= HEIGHT {
+            Err(GameError::OutOfBounds { x, y })
+        } else if self.is_given(x, y) {
+            Err(GameError::Given { x, y })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn make_move(&mut self, changes: Vec<Change>) {
+        for change in &changes {
+            self.apply(change, false);
+        }
+        self.push_move(changes);
+    }
+
+    /// Adds a move whose changes were already applied to the history
+    fn push_move(&mut self, changes: Vec<Change>) {
+        self.history.push(Move {
+            changes,
+            time: self.timer.elapsed(),
+        });
+        self.undone.clear();
+        self.check_solved();
+    }
+
+    fn apply(&mut self, change: &Change, reverse: bool) {
+        let event = match *change {
+            Change::Value { x, y, before, after } => {
+                let value = if reverse { before } else { after };
+                self.board.set(x, y, value);
+                match value {
+                    Some(value) => GameEvent::Placed { x, y, value },
+                    None => GameEvent::Erased { x, y },
+                }
+            }
+            Change::Note { x, y, value, added, .. } => {
+                let added = added != reverse;
+                self.notes.set(x, y, value, added);
+                if added {
+                    GameEvent::NoteAdded { x, y, value }
+                } else {
+                    GameEvent::NoteRemoved { x, y, value }
+                }
+            }
+        };
+        self.observers.emit(event);
+    }
+
+    /// Saves the current values and notes under the label, replacing an earlier snapshot with the same label.
+    /// Unlike the undo history, snapshots stay until they're removed, e.g. to try out a guess and come back to
+    /// the point before it later. They're part of the saved game.
+    pub fn snapshot(&mut self, label: impl Into<String>) {
+        self.snapshots.insert(label.into(), (*self.board, self.notes));
+    }
+
+    /// Returns to the values and notes of the snapshot with the label. This is a move, so it can be undone.
+    /// Returns false if there is no such snapshot.
+    pub fn restore(&mut self, label: &str) -> bool {
+        let Some(&(board, notes)) = self.snapshots.get(label) else {
+            return false;
+        };
+        let mut changes = vec![];
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let (before, after) = (self.board.field(x, y).get(), board.field(x, y).get());
+                if before != after {
+                    changes.push(Change::Value { x, y, before, after });
+                }
+                for value in (1..=MAX_VALUE).filter_map(NonZeroU8::new) {
+                    let added = notes.contains(x, y, value);
+                    if self.notes.contains(x, y, value) != added {
+                        changes.push(Change::Note {
+                            x,
+                            y,
+                            value,
+                            added,
+                            derived: false,
+                        });
+                    }
+                }
+            }
+        }
+        if !changes.is_empty() {
+            self.make_move(changes);
+        }
+        true
+    }
+
+    /// The labels of all snapshots, in alphabetical order
+    pub fn snapshots(&self) -> impl Iterator<Item = &str> {
+        self.snapshots.keys().map(String::as_str)
+    }
+
+    /// Returns false if there is no snapshot with the label
+    pub fn remove_snapshot(&mut self, label: &str) -> bool {
+        self.snapshots.remove(label).is_some()
+    }
+
+    /// Registers a callback that is called for every change to the game, so a UI can update only what changed.
+    /// Callbacks are called after the change was made. They aren't copied to clones or branches of the game.
+    pub fn on_change(&mut self, callback: impl FnMut(&GameEvent) + Send + 'static) {
+        self.observers.add(callback);
+    }
+
+    /// Emits [GameEvent::Solved] if the game is solved. Called after each move that changes values.
+    fn check_solved(&mut self) {
+        if self.is_solved() {
+            self.timer.pause();
+            self.observers.emit(GameEvent::Solved);
+        }
+    }
+
+    /// Returns where to look for the next logical step without revealing it, see [crate::nudge].
+    /// Unlike [Game::hint], this doesn't count against the score.
+    pub fn nudge(&self) -> Option<Nudge> {
+        nudge(&self.board, &[])
+    }
+
+    /// Returns the next logical step, see [crate::hint]. Each hint counts against the [Game::score].
+    pub fn hint(&mut self) -> Option<Step> {
+        let step = hint(&self.board, &[])?;
+        self.hints += 1;
+        let cell = step.placement.iter().chain(&step.eliminations).map(|candidate| (candidate.x, candidate.y)).next();
+        if let Some(cell) = cell.or_else(|| step.cells.first().copied()) {
+            self.hint_cells.push(cell);
+        }
+        self.observers.emit(GameEvent::Hint(step.clone()));
+        Some(step)
+    }
+
+    pub fn hints(&self) -> usize {
+        self.hints
+    }
+
+    /// How many values the player entered that differ from the solution, including ones that were corrected later
+    pub fn mistakes(&self) -> usize {
+        self.mistakes
+    }
+
+    /// The score based on the difficulty of the puzzle, the play time and the hints and mistakes so far.
+    /// Puzzles without a unique solution are rated [Difficulty::Extreme].
+    pub fn score(&self) -> Score {
+        self.score_with(&ScoringConfig::default())
+    }
+
+    /// Like [Game::score], but with a custom scoring model
+    pub fn score_with(&self, config: &ScoringConfig) -> Score {
+        self.score_for(config, self.elapsed())
+    }
+
+    /// Summarizes the game for a [Session], e.g. when the player solved it or gave up.
+    /// Games that aren't solved get a score of 0.
+    pub fn result(&self) -> GameResult {
+        let score = self.score();
+        let solved = self.is_solved();
+        GameResult {
+            difficulty: score.difficulty,
+            solved,
+            play_time: self.elapsed(),
+            hints: self.hints,
+            mistakes: self.mistakes,
+            score: if solved { score.total } else { 0 },
+            techniques: rate(&self.puzzle)
+                .map(|report| report.technique_counts().into_iter().collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// A breakdown of the game so far: where the player spent their time, where they needed hints or made mistakes,
+    /// and which techniques the puzzle requires
+    pub fn analytics(&self) -> Analytics {
+        let cell_times = analytics::cell_times(&self.history);
+        Analytics {
+            play_time: self.elapsed(),
+            hotspots: analytics::hotspots(&cell_times),
+            cell_times,
+            houses: analytics::house_stats(&self.hint_cells, &self.mistake_cells),
+            techniques: rate(&self.puzzle)
+                .map(|report| report.technique_counts().into_iter().collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn score_for(&self, config: &ScoringConfig, play_time: Duration) -> Score {
+        let difficulty = rate(&self.puzzle)
+            .map(|report| report.difficulty)
+            .unwrap_or(Difficulty::Extreme);
+        config.score(difficulty, play_time, self.hints, self.mistakes)
+    }
+
+    /// Starts or resumes the timer, e.g. when the player sees the puzzle or comes back to the app
+    pub fn start(&mut self) {
+        if !self.is_solved() {
+            self.timer.start();
+        }
+    }
+
+    /// Pauses the timer, e.g. when the app goes to the background
+    pub fn pause(&mut self) {
+        self.timer.pause();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        !self.timer.is_running()
+    }
+
+    /// The play time so far, not counting the time while paused
+    pub fn elapsed(&self) -> Duration {
+        self.timer.elapsed()
+    }
+
+    /// Writes the puzzle and the moves so far in the replay format, see [write_replay]
+    pub fn export_replay(&self) -> String {
+        write_replay(&self.puzzle, &self.history)
+    }
+
+    /// Creates a scratch copy of the game to try out moves, e.g. to find out where guessing a value leads.
+    /// The moves made in the branch can be added to this game with [Game::commit], or discarded by dropping the branch.
+    /// The branch starts with an empty history, so undoing in it can't go back further than the branch point.
+    /// Branches can be branched again.
+    pub fn branch(&self) -> Game {
+        Game {
+            history: vec![],
+            undone: vec![],
+            branch_base: Some((*self.board, self.notes)),
+            ..self.clone()
+        }
+    }
+
+    /// Adds the moves of a branch created with [Game::branch] to this game, including the hints and mistakes made in it.
+    /// This fails if the values or notes of this game changed since the branch was created.
+    pub fn commit(&mut self, branch: Game) -> Result<(), GameError> {
+        let (base_board, base_notes) = branch.branch_base.ok_or(GameError::NotABranch)?;
+        if base_board != *self.board || base_notes != self.notes || branch.puzzle != self.puzzle {
+            return Err(GameError::BranchOutdated);
+        }
+        self.hints = branch.hints;
+        self.mistakes = branch.mistakes;
+        self.hint_cells = branch.hint_cells;
+        self.mistake_cells = branch.mistake_cells;
+        self.annotations = branch.annotations;
+        if !branch.history.is_empty() {
+            // Apply the changes instead of copying the board, so the observers of this game see them
+            for change in branch.history.iter().flat_map(|branch_move| &branch_move.changes) {
+                self.apply(change, false);
+            }
+            self.history.extend(branch.history);
+            self.undone.clear();
+            self.check_solved();
+        }
+        Ok(())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Undoes the last move. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(last_move) = self.history.pop() else {
+            return false;
+        };
+        for change in last_move.changes.iter().rev() {
+            self.apply(change, true);
+        }
+        self.undone.push(last_move);
+        true
+    }
+
+    /// Redoes the last undone move. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(undone_move) = self.undone.pop() else {
+            return false;
+        };
+        for change in &undone_move.changes {
+            self.apply(change, false);
+        }
+        self.history.push(undone_move);
+        self.check_solved();
+        true
+    }
+
+    /// The moves made so far, oldest first. Undone moves aren't included.
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hints::HouseId;
+
+    fn game() -> Game {
+        Game::new(Board::from_str(
+            "
+            53_ _7_ ___
+            6__ 195 ___
+            _98 ___ _6_
+
+            8__ _6_ __3
+            4__ 8_3 __1
+            7__ _2_ __6
+
+            _6_ ___ 28_
+            ___ 419 __5
+            ___ _8_ _79
+        ",
+        ))
+    }
+
+    #[test]
+    fn givens_cant_be_changed() {
+        let mut game = game();
+        assert_eq!(Err(GameError::Given { x: 0, y: 0 }), game.place(0, 0, 1));
+        assert_eq!(Err(GameError::OutOfBounds { x: 9, y: 0 }), game.place(9, 0, 1));
+        assert_eq!(Err(GameError::InvalidValue(10)), game.place(2, 0, 10));
+        assert!(!game.can_undo());
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut game = game();
+        game.place(2, 0, 4).unwrap();
+        game.place(2, 0, 1).unwrap();
+        game.erase(3, 0).unwrap();
+        assert_eq!(2, game.history().len());
+        assert_eq!(NonZeroU8::new(1), game.board().field(2, 0).get());
+
+        assert!(game.undo());
+        assert_eq!(NonZeroU8::new(4), game.board().field(2, 0).get());
+        assert!(game.undo());
+        assert_eq!(game.puzzle(), game.board());
+        assert!(!game.undo());
+
+        assert!(game.redo());
+        assert_eq!(NonZeroU8::new(4), game.board().field(2, 0).get());
+        // A new move discards the moves that could be redone
+        game.place(3, 0, 6).unwrap();
+        assert!(!game.can_redo());
+        assert!(!game.redo());
+        assert_eq!(NonZeroU8::new(6), game.board().field(3, 0).get());
+    }
+
+    #[test]
+    fn notes() {
+        let mut game = game();
+        game.add_note(2, 0, 1).unwrap();
+        game.toggle_note(2, 0, 2).unwrap();
+        game.toggle_note(2, 0, 4).unwrap();
+        game.toggle_note(2, 0, 2).unwrap();
+        // Already there, so this isn't a move
+        game.add_note(2, 0, 1).unwrap();
+        assert_eq!(vec![1, 4], game.notes().values(2, 0).map(NonZeroU8::get).collect::<Vec<_>>());
+        assert_eq!(4, game.history().len());
+        assert_eq!(Err(GameError::Given { x: 0, y: 0 }), game.add_note(0, 0, 1));
+        assert_eq!(Err(GameError::InvalidValue(0)), game.toggle_note(2, 0, 0));
+
+        game.place(2, 0, 4).unwrap();
+        assert_eq!(Err(GameError::Filled { x: 2, y: 0 }), game.add_note(2, 0, 2));
+
+        game.undo();
+        game.undo();
+        assert_eq!(vec![1, 2, 4], game.notes().values(2, 0).map(NonZeroU8::get).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn auto_remove_notes() {
+        let mut game = game();
+        game.set_auto_remove_notes(true);
+        game.add_note(2, 1, 4).unwrap();
+        game.add_note(2, 1, 2).unwrap();
+        game.add_note(6, 0, 4).unwrap();
+        game.add_note(6, 1, 4).unwrap();
+        game.place(2, 0, 4).unwrap();
+        // Same column and same row, but not the cell in neither
+        assert_eq!(vec![2], game.notes().values(2, 1).map(NonZeroU8::get).collect::<Vec<_>>());
+        assert!(game.notes().is_empty(6, 0));
+        assert!(!game.notes().is_empty(6, 1));
+
+        game.undo();
+        assert_eq!(vec![2, 4], game.notes().values(2, 1).map(NonZeroU8::get).collect::<Vec<_>>());
+        assert!(!game.notes().is_empty(6, 0));
+    }
+
+    #[test]
+    fn auto_removed_notes_come_back() {
+        let mut game = game();
+        game.add_note(2, 1, 4).unwrap();
+        game.add_note(2, 3, 4).unwrap();
+        game.add_note(2, 1, 7).unwrap();
+        game.set_auto_remove_notes(true);
+        game.place(2, 0, 4).unwrap();
+        assert!(!game.notes().contains(2, 1, NonZeroU8::new(4).unwrap()));
+        // The player takes one of the notes into their own hands, and removes another one manually
+        game.add_note(2, 3, 4).unwrap();
+        game.remove_note(2, 3, 4).unwrap();
+        game.remove_note(2, 1, 7).unwrap();
+        assert_eq!(1, game.auto_removed_notes().count());
+
+        game.set_auto_remove_notes(false);
+        assert!(game.notes().contains(2, 1, NonZeroU8::new(4).unwrap()));
+        assert!(!game.notes().contains(2, 3, NonZeroU8::new(4).unwrap()));
+        assert!(!game.notes().contains(2, 1, NonZeroU8::new(7).unwrap()));
+        assert_eq!(0, game.auto_removed_notes().count());
+
+        // Enabling it again removes the notes that contradict the board
+        game.set_auto_remove_notes(true);
+        assert!(!game.notes().contains(2, 1, NonZeroU8::new(4).unwrap()));
+        assert!(game.undo());
+        assert!(game.notes().contains(2, 1, NonZeroU8::new(4).unwrap()));
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut game = game();
+        game.place(2, 0, 4).unwrap();
+        game.snapshot("before guess");
+        game.place(3, 0, 2).unwrap();
+        game.add_note(2, 1, 7).unwrap();
+        game.snapshot("guess");
+        game.place(5, 0, 8).unwrap();
+
+        assert!(game.restore("before guess"));
+        assert_eq!(NonZeroU8::new(4), game.board().field(2, 0).get());
+        assert!(game.board().field(3, 0).is_empty() && game.board().field(5, 0).is_empty());
+        assert_eq!(0, game.notes().count());
+        assert!(game.restore("guess"));
+        assert_eq!(NonZeroU8::new(2), game.board().field(3, 0).get());
+        assert_eq!(1, game.notes().count());
+        assert!(game.undo());
+        assert!(game.board().field(3, 0).is_empty());
+
+        assert!(!game.restore("unknown"));
+        assert_eq!(vec!["before guess", "guess"], game.snapshots().collect::<Vec<_>>());
+        assert!(game.remove_snapshot("guess"));
+        assert!(!game.restore("guess"));
+    }
+
+    #[test]
+    fn check_errors() {
+        let mut game = game();
+        // Correct
+        game.place(2, 0, 4).unwrap();
+        // Wrong, but doesn't conflict with anything yet
+        game.place(3, 0, 2).unwrap();
+        // Conflicts with the given 5 in the same row
+        game.place(5, 0, 5).unwrap();
+        assert_eq!(vec![(3, 0), (5, 0)], game.check_errors(ErrorCheck::Solution));
+        assert_eq!(vec![(5, 0)], game.check_errors(ErrorCheck::Conflicts));
+
+        let mut without_solution = Game::with_solution(*game.puzzle(), None);
+        without_solution.place(3, 0, 2).unwrap();
+        assert_eq!(Vec::<(usize, usize)>::new(), without_solution.check_errors(ErrorCheck::Solution));
+    }
+
+    #[test]
+    fn progress() {
+        let mut game = game();
+        game.place(2, 0, 4).unwrap();
+        game.add_note(3, 0, 2).unwrap();
+        game.add_note(3, 0, 6).unwrap();
+        assert_eq!(
+            Progress {
+                filled: 31,
+                remaining: 50,
+                notes: 2,
+                consistent: true,
+            },
+            game.progress()
+        );
+        game.place(3, 0, 2).unwrap();
+        assert!(!game.progress().consistent);
+
+        let mut without_solution = Game::with_solution(*game.puzzle(), None);
+        without_solution.place(2, 0, 4).unwrap();
+        assert!(without_solution.progress().consistent);
+        without_solution.place(3, 0, 2).unwrap();
+        assert!(!without_solution.progress().consistent);
+    }
+
+    #[test]
+    fn autofill_singles() {
+        let mut game = game();
+        let filled = game.autofill_singles();
+        assert!(filled > 0);
+        assert_eq!(1, game.history().len());
+        assert_eq!(filled, game.puzzle().num_empty() - game.board().num_empty());
+        assert!(game.progress().consistent);
+        game.undo();
+        assert_eq!(game.puzzle(), game.board());
+    }
+
+    #[test]
+    fn branches() {
+        let mut game = game();
+        game.place(2, 0, 4).unwrap();
+
+        let mut branch = game.branch();
+        branch.place(3, 0, 6).unwrap();
+        assert!(branch.undo());
+        // Can't undo past the branch point
+        assert!(!branch.undo());
+        branch.place(3, 0, 2).unwrap();
+        let mut nested = branch.branch();
+        nested.add_note(5, 0, 8).unwrap();
+        branch.commit(nested).unwrap();
+        assert!(game.board().field(3, 0).is_empty());
+
+        game.commit(branch.clone()).unwrap();
+        assert_eq!(NonZeroU8::new(2), game.board().field(3, 0).get());
+        assert_eq!(3, game.history().len());
+        game.undo();
+        game.undo();
+        assert_eq!(NonZeroU8::new(4), game.board().field(2, 0).get());
+        assert!(game.board().field(3, 0).is_empty());
+
+        // The game changed since branching
+        game.place(6, 0, 9).unwrap();
+        assert_eq!(Err(GameError::BranchOutdated), game.commit(branch));
+        assert_eq!(Err(GameError::NotABranch), game.commit(game.clone()));
+    }
+
+    #[test]
+    fn score() {
+        let mut game = game();
+        let config = ScoringConfig::default();
+        let perfect = game.score_for(&config, Duration::ZERO);
+        assert_eq!(perfect.base + perfect.base / 2, perfect.total);
+        assert_eq!(perfect.base, game.score_for(&config, Duration::from_secs(3600)).total);
+
+        assert!(game.hint().is_some());
+        game.place(3, 0, 2).unwrap();
+        game.place(3, 0, 6).unwrap();
+        assert_eq!(1, game.hints());
+        assert_eq!(1, game.mistakes());
+        let score = game.score_for(&config, Duration::from_secs(3600));
+        assert_eq!(score.base / 10, score.hint_penalty);
+        assert_eq!(score.base / 20, score.mistake_penalty);
+        assert_eq!(score.base - score.hint_penalty - score.mistake_penalty, score.total);
+    }
+
+    #[test]
+    fn result() {
+        let mut game = game();
+        let result = game.result();
+        assert!(!result.solved);
+        assert_eq!(0, result.score);
+        assert_eq!(game.puzzle().num_empty(), result.techniques.values().sum::<usize>());
+
+        let solution = *game.solution().unwrap();
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                if !game.is_given(x, y) {
+                    game.set(x, y, solution.field(x, y).get()).unwrap();
+                }
+            }
+        }
+        let result = game.result();
+        assert!(result.solved);
+        assert_eq!(game.score().total, result.score);
+    }
+
+    #[test]
+    fn analytics() {
+        let mut game = game();
+        game.place(2, 0, 4).unwrap();
+        game.history[0].time = Duration::from_secs(10);
+        game.place(3, 0, 2).unwrap();
+        game.history[1].time = Duration::from_secs(40);
+        game.toggle_note(2, 1, 7).unwrap();
+        game.history[2].time = Duration::from_secs(45);
+
+        assert!(game.hint().is_some());
+
+        let analytics = game.analytics();
+        let cell_time = |x, y, secs| CellTime { x, y, time: Duration::from_secs(secs) };
+        assert_eq!(vec![cell_time(2, 0, 10), cell_time(3, 0, 30), cell_time(2, 1, 5)], analytics.cell_times);
+        assert_eq!(cell_time(3, 0, 30), analytics.hotspots[0]);
+        let row = HouseStats { house: HouseId::Row(0), hints: 0, mistakes: 1 };
+        assert!(analytics.houses.contains(&row) || analytics.houses.contains(&HouseStats { hints: 1, ..row }));
+        assert_eq!(3, analytics.houses.iter().map(|stats| stats.mistakes).sum::<usize>());
+        assert_eq!(3, analytics.houses.iter().map(|stats| stats.hints).sum::<usize>());
+        assert_eq!(game.puzzle().num_empty(), analytics.techniques.values().sum::<usize>());
+    }
+
+    #[test]
+    fn teaching() {
+        let mut game = game();
+        game.place(2, 0, 4).unwrap();
+        assert!(game.annotations().is_empty());
+        game.set_teaching(true);
+        let step = hint(game.board(), &[]).unwrap();
+        let placement = step.placement.unwrap();
+        game.place(placement.x, placement.y, placement.value.get()).unwrap();
+        // r1c4 is 6 in the solution, but the hint engine needs more than one step for it
+        game.place(3, 0, 6).unwrap();
+        game.place(3, 0, 2).unwrap();
+
+        let annotations = game.annotations();
+        assert_eq!(3, annotations.len());
+        assert_eq!(Justification::Deduced { technique: step.technique, steps: 1 }, annotations[0].justification);
+        assert!(matches!(annotations[1].justification, Justification::Deduced { steps, .. } if steps > 1));
+        assert_eq!(Justification::Wrong, annotations[2].justification);
+        assert!(annotations[2].to_string().starts_with("r1c4=2"));
+    }
+
+    #[test]
+    fn timer_stops_when_solved() {
+        let mut game = game();
+        assert!(game.is_paused());
+        game.start();
+        assert!(!game.is_paused());
+        let solution = *game.solution().unwrap();
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                if !game.is_given(x, y) {
+                    game.set(x, y, solution.field(x, y).get()).unwrap();
+                }
+            }
+        }
+        assert!(game.is_solved());
+        assert!(game.is_paused());
+        game.start();
+        assert!(game.is_paused());
+    }
+
+    #[test]
+    fn replay_roundtrip() {
+        let mut game = game();
+        game.set_auto_remove_notes(true);
+        game.add_note(2, 1, 4).unwrap();
+        game.add_note(2, 1, 2).unwrap();
+        game.place(2, 0, 4).unwrap();
+        game.place(3, 0, 2).unwrap();
+        game.erase(3, 0).unwrap();
+        let replay = game.export_replay();
+        let mut lines = replay.lines().skip(2);
+        assert_eq!(Some("r2c3+4"), lines.next().and_then(|line| line.split_once(' ')).map(|(_, changes)| changes));
+        assert_eq!(Some("r1c3=4 r2c3-4*"), lines.nth(1).and_then(|line| line.split_once(' ')).map(|(_, changes)| changes));
+
+        let reader = ReplayReader::new(&replay).unwrap();
+        assert_eq!(game.puzzle(), reader.puzzle());
+        let moves: Vec<Move> = reader.collect::<Result<_, _>>().unwrap();
+        // Times are rounded to milliseconds
+        let without_times = |moves: &[Move]| moves.iter().map(|game_move| game_move.changes.clone()).collect::<Vec<_>>();
+        assert_eq!(without_times(game.history()), without_times(&moves));
+    }
+
+    #[test]
+    fn replay_errors() {
+        let puzzle = crate::formats::format_line(game().puzzle());
+        assert!(matches!(ReplayReader::new("not a replay"), Err(ReplayError::Header)));
+        let replay = format!("sudoku-replay 1\n{puzzle}\n100 r1c3=4\n200 r1c1=4\n");
+        let results: Vec<_> = ReplayReader::new(&replay).unwrap().collect();
+        assert!(results[0].is_ok());
+        assert_eq!(Err(ReplayError::Given { line: 4, x: 0, y: 0 }), results[1]);
+        let replay = format!("sudoku-replay 1\n{puzzle}\n100 r1c3+.\n");
+        assert!(matches!(
+            ReplayReader::new(&replay).unwrap().next(),
+            Some(Err(ReplayError::InvalidMove { line: 3, .. }))
+        ));
+    }
+
+    #[test]
+    fn can_place() {
+        let game = game();
+        assert_eq!(Ok(Legality::Allowed), game.can_place(2, 0, 4));
+        assert_eq!(Ok(Legality::Given), game.can_place(0, 0, 4));
+        assert_eq!(Ok(Legality::Conflict { x: 0, y: 0 }), game.can_place(2, 0, 5));
+        assert_eq!(Ok(Legality::Wrong), game.can_place(2, 0, 2));
+        assert_eq!(Err(GameError::InvalidValue(0)), game.can_place(2, 0, 0));
+        assert_eq!(Err(GameError::OutOfBounds { x: 2, y: 9 }), game.can_place(2, 9, 1));
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut game = game();
+        assert!(game.conflicts().is_empty());
+        game.place(2, 0, 5).unwrap();
+        game.place(1, 1, 9).unwrap();
+        assert_eq!(vec![(0, 0), (2, 0), (1, 1), (4, 1), (1, 2)], game.conflicts());
+        assert!(game.is_conflicting(4, 1));
+        game.undo();
+        assert_eq!(vec![(0, 0), (2, 0)], game.conflicts());
+        game.place(2, 0, 4).unwrap();
+        assert!(game.conflicts().is_empty());
+        game.undo();
+        game.redo();
+        assert!(!game.is_conflicting(0, 0));
+    }
+
+    #[test]
+    fn selection_helpers() {
+        let mut game = game();
+        let five = NonZeroU8::new(5).unwrap();
+        assert_eq!(vec![(0, 0), (5, 1), (8, 7)], game.cells_with_digit(five));
+        assert_eq!(6, game.remaining_count(five));
+        game.place(2, 0, 4).unwrap();
+        assert_eq!(vec![(2, 0), (0, 4), (3, 7)], game.cells_with_digit(NonZeroU8::new(4).unwrap()));
+
+        let peers: Vec<_> = game.peers_of(4, 4).collect();
+        assert_eq!(20, peers.len());
+        assert!(peers.contains(&(4, 0)) && peers.contains(&(0, 4)) && peers.contains(&(3, 5)));
+        assert!(!peers.contains(&(4, 4)) && !peers.contains(&(2, 2)));
+    }
+
+    #[test]
+    fn on_change() {
+        use std::sync::{Arc, Mutex};
+
+        let mut game = game();
+        let events = Arc::new(Mutex::new(vec![]));
+        let recorded = Arc::clone(&events);
+        game.on_change(move |event| recorded.lock().unwrap().push(event.clone()));
+        game.add_note(2, 0, 4).unwrap();
+        game.place(2, 0, 4).unwrap();
+        game.undo();
+        game.hint().unwrap();
+        let value = |value| NonZeroU8::new(value).unwrap();
+        let events = events.lock().unwrap();
+        assert_eq!(
+            vec![
+                GameEvent::NoteAdded { x: 2, y: 0, value: value(4) },
+                GameEvent::Placed { x: 2, y: 0, value: value(4) },
+                GameEvent::Erased { x: 2, y: 0 },
+            ],
+            events[..3]
+        );
+        assert!(matches!(events[3], GameEvent::Hint(_)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize() {
+        let mut game = game();
+        game.place(2, 0, 4).unwrap();
+        game.add_note(3, 0, 6).unwrap();
+        game.undo();
+        game.snapshot("saved");
+        let json = serde_json::to_string(&game).unwrap();
+        assert_eq!(game, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,46 @@
+use std::num::NonZeroU8;
+
+use crate::board::{HEIGHT, MAX_VALUE, WIDTH};
+
+/// [Notes] are the candidates a player noted down for each cell, also known as pencil marks.
+/// Unlike the possible values the solver tracks, these are user data and can be wrong.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Notes {
+    // Bit `value - 1` of `masks[x][y]` is set if the value is noted for the cell
+    masks: [[u16; HEIGHT]; WIDTH],
+}
+
+impl Notes {
+    pub fn contains(&self, x: usize, y: usize, value: NonZeroU8) -> bool {
+        self.masks[x][y] & bit(value) != 0
+    }
+
+    /// The noted values of the cell in ascending order
+    pub fn values(&self, x: usize, y: usize) -> impl Iterator<Item = NonZeroU8> + '_ {
+        (1..=MAX_VALUE)
+            .filter_map(NonZeroU8::new)
+            .filter(move |value| self.contains(x, y, *value))
+    }
+
+    pub fn is_empty(&self, x: usize, y: usize) -> bool {
+        self.masks[x][y] == 0
+    }
+
+    /// The number of notes over all cells
+    pub fn count(&self) -> usize {
+        self.masks.iter().flatten().map(|mask| mask.count_ones() as usize).sum()
+    }
+
+    pub(super) fn set(&mut self, x: usize, y: usize, value: NonZeroU8, noted: bool) {
+        if noted {
+            self.masks[x][y] |= bit(value);
+        } else {
+            self.masks[x][y] &= !bit(value);
+        }
+    }
+}
+
+fn bit(value: NonZeroU8) -> u16 {
+    1 << (value.get() - 1)
+}
@@ -0,0 +1,155 @@
+use std::time::Duration;
+use thiserror::Error;
+
+use super::Game;
+use crate::board::Board;
+use crate::generator::{generate_with_difficulty_seeded, Symmetry};
+use crate::rating::Difficulty;
+
+/// A [Race] is a puzzle several players solve at the same time. All players derive the same puzzle from the seed
+/// and difficulty, so only these have to be exchanged before the race.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Race {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+}
+
+impl Race {
+    /// The puzzle of the race, see [generate_with_difficulty_seeded]
+    pub fn puzzle(&self) -> Board {
+        generate_with_difficulty_seeded(self.difficulty, Symmetry::Rotational, self.seed)
+    }
+
+    /// Starts a game for the puzzle of the race. The timer is already running.
+    pub fn start(&self) -> Game {
+        let mut game = Game::new(self.puzzle());
+        game.start();
+        game
+    }
+}
+
+/// What a player reports after finishing a race, see [RaceEntry::new]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RaceEntry {
+    pub player: String,
+
+    /// The board when the player finished, so the other players can check it
+    pub board: Board,
+
+    pub play_time: Duration,
+
+    pub mistakes: usize,
+
+    pub hints: usize,
+}
+
+impl RaceEntry {
+    pub fn new(player: impl Into<String>, game: &Game) -> Self {
+        Self {
+            player: player.into(),
+            board: *game.board(),
+            play_time: game.elapsed(),
+            mistakes: game.mistakes(),
+            hints: game.hints(),
+        }
+    }
+
+    /// Checks that the entry solves the puzzle
+    pub fn validate(&self, puzzle: &Board) -> Result<(), RaceError> {
+        if !puzzle.is_subset_of(&self.board) {
+            Err(RaceError::WrongPuzzle)
+        } else if !self.board.is_filled() || self.board.has_conflicts() {
+            Err(RaceError::NotSolved)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RaceError {
+    #[error("The board doesn't contain the givens of the race's puzzle")]
+    WrongPuzzle,
+
+    #[error("The board isn't solved")]
+    NotSolved,
+}
+
+/// The outcome of a [Race], see [RaceResult::new]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RaceResult {
+    /// The valid entries, best first. Faster players rank higher, ties are broken by fewer mistakes, then fewer hints.
+    pub standings: Vec<RaceEntry>,
+
+    /// The entries that didn't solve the puzzle, in the order they were given
+    pub disqualified: Vec<(RaceEntry, RaceError)>,
+}
+
+impl RaceResult {
+    /// Ranks the entries of all players. `puzzle` is [Race::puzzle], which each entry is validated against.
+    pub fn new(puzzle: &Board, entries: impl IntoIterator<Item = RaceEntry>) -> Self {
+        let mut standings = vec![];
+        let mut disqualified = vec![];
+        for entry in entries {
+            match entry.validate(puzzle) {
+                Ok(()) => standings.push(entry),
+                Err(err) => disqualified.push((entry, err)),
+            }
+        }
+        standings.sort_by_key(|entry| (entry.play_time, entry.mistakes, entry.hints));
+        Self { standings, disqualified }
+    }
+
+    /// The best player, or `None` if nobody solved the puzzle
+    pub fn winner(&self) -> Option<&RaceEntry> {
+        self.standings.first()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn race() {
+        let race = Race { seed: 7, difficulty: Difficulty::Easy };
+        assert_eq!(race.puzzle(), race.puzzle());
+        let puzzle = race.puzzle();
+        let solution = crate::solve(puzzle).unwrap();
+
+        let entry = |player: &str, board: Board, secs: u64, mistakes: usize| RaceEntry {
+            player: player.to_string(),
+            board,
+            play_time: Duration::from_secs(secs),
+            mistakes,
+            hints: 0,
+        };
+        let result = RaceResult::new(
+            &puzzle,
+            [
+                entry("slow", solution, 300, 0),
+                entry("careless", solution, 200, 3),
+                entry("fast", solution, 200, 1),
+                entry("quitter", puzzle, 100, 0),
+                entry("cheater", crate::solve(Race { seed: 8, ..race }.puzzle()).unwrap(), 10, 0),
+            ],
+        );
+        let players: Vec<&str> = result.standings.iter().map(|entry| entry.player.as_str()).collect();
+        assert_eq!(vec!["fast", "careless", "slow"], players);
+        assert_eq!("fast", result.winner().unwrap().player);
+        assert_eq!(RaceError::NotSolved, result.disqualified[0].1);
+        assert_eq!(RaceError::WrongPuzzle, result.disqualified[1].1);
+    }
+
+    #[test]
+    fn entry_from_game() {
+        let race = Race { seed: 7, difficulty: Difficulty::Easy };
+        let game = race.start();
+        let entry = RaceEntry::new("player", &game);
+        assert_eq!(Err(RaceError::NotSolved), entry.validate(&race.puzzle()));
+    }
+}
@@ -0,0 +1,164 @@
+use std::iter::Enumerate;
+use std::num::NonZeroU8;
+use std::str::Lines;
+use std::time::Duration;
+use thiserror::Error;
+
+use super::{Change, Move, Notes};
+use crate::board::Board;
+use crate::formats::{format_line, parse_line, BoardParseError};
+
+const HEADER: &str = "sudoku-replay 1";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    #[error("Not a replay, expected the first line to be '{HEADER}'")]
+    Header,
+
+    #[error("Invalid puzzle: {0}")]
+    Puzzle(BoardParseError),
+
+    #[error("Line {line}: invalid move '{text}'")]
+    InvalidMove { line: usize, text: String },
+
+    #[error("Line {line}: cell r{}c{} is given and can't be changed", .y + 1, .x + 1)]
+    Given { line: usize, x: usize, y: usize },
+}
+
+/// Writes the puzzle and the moves in the replay format. It's made for storing many finished games compactly
+/// and, unlike a serialized [crate::Game], it only contains the moves that weren't undone.
+///
+/// The first line is the header `sudoku-replay 1` and the second line the puzzle in the line format.
+/// Each following line is a move: the play time in milliseconds, followed by its changes separated by spaces.
+/// A change is `r3c5=7` for entering a 7 in row 3, column 5, `r3c5=.` for erasing it,
+/// and `r3c5+7` or `r3c5-7` for adding or removing a note. Changes the game made automatically instead of the player,
+/// like removing notes when a value is placed, end with `*`, e.g. `r3c5-7*`.
+pub fn write_replay(puzzle: &Board, moves: &[Move]) -> String {
+    let mut replay = format!("{HEADER}\n{}\n", format_line(puzzle));
+    for game_move in moves {
+        replay.push_str(&game_move.time.as_millis().to_string());
+        for change in &game_move.changes {
+            let change = match *change {
+                Change::Value { x, y, after, .. } => {
+                    let value = after.map_or(".".to_string(), |value| value.to_string());
+                    format!(" r{}c{}={value}", y + 1, x + 1)
+                }
+                Change::Note { x, y, value, added, derived } => {
+                    let operator = if added { '+' } else { '-' };
+                    format!(" r{}c{}{operator}{value}{}", y + 1, x + 1, if derived { "*" } else { "" })
+                }
+            };
+            replay.push_str(&change);
+        }
+        replay.push('\n');
+    }
+    replay
+}
+
+/// Reads a replay written by [write_replay] move by move, so even long replays don't need to be loaded at once.
+/// The moves are complete, i.e. the changes include the values from before the move.
+pub struct ReplayReader<'a> {
+    puzzle: Board,
+
+    /// The state after the moves read so far, to fill in the values before each change
+    board: Board,
+    notes: Notes,
+
+    lines: Enumerate<Lines<'a>>,
+}
+
+impl<'a> ReplayReader<'a> {
+    /// Reads the header and the puzzle
+    pub fn new(replay: &'a str) -> Result<Self, ReplayError> {
+        let mut lines = replay.lines().enumerate();
+        if lines.next().map(|(_, line)| line.trim()) != Some(HEADER) {
+            return Err(ReplayError::Header);
+        }
+        let (_, puzzle) = lines.next().ok_or(ReplayError::Puzzle(BoardParseError::Empty))?;
+        let puzzle = parse_line(puzzle).map_err(ReplayError::Puzzle)?;
+        Ok(Self {
+            puzzle,
+            board: puzzle,
+            notes: Notes::default(),
+            lines,
+        })
+    }
+
+    pub fn puzzle(&self) -> &Board {
+        &self.puzzle
+    }
+
+    fn parse_move(&mut self, index: usize, line: &str) -> Result<Move, ReplayError> {
+        let invalid = || ReplayError::InvalidMove {
+            line: index + 1,
+            text: line.to_string(),
+        };
+        let mut tokens = line.split_whitespace();
+        let time: u64 = tokens.next().and_then(|time| time.parse().ok()).ok_or_else(invalid)?;
+        let mut changes = vec![];
+        for token in tokens {
+            let (token, derived) = match token.strip_suffix('*') {
+                Some(token) => (token, true),
+                None => (token, false),
+            };
+            let (x, y, operator, value) = parse_change(token).ok_or_else(invalid)?;
+            if derived && operator == '=' {
+                return Err(invalid());
+            }
+            let change = match operator {
+                '=' => {
+                    if !self.puzzle.field(x, y).is_empty() {
+                        return Err(ReplayError::Given { line: index + 1, x, y });
+                    }
+                    let before = self.board.field(x, y).get();
+                    self.board.field_mut(x, y).set(value);
+                    Change::Value {
+                        x,
+                        y,
+                        before,
+                        after: value,
+                    }
+                }
+                _ => {
+                    let value = value.ok_or_else(invalid)?;
+                    let added = operator == '+';
+                    self.notes.set(x, y, value, added);
+                    Change::Note { x, y, value, added, derived }
+                }
+            };
+            changes.push(change);
+        }
+        if changes.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Move {
+            changes,
+            time: Duration::from_millis(time),
+        })
+    }
+}
+
+/// Parses `r3c5=7`, `r3c5=.`, `r3c5+7` or `r3c5-7` into the cell, the operator and the value
+fn parse_change(token: &str) -> Option<(usize, usize, char, Option<NonZeroU8>)> {
+    let [b'r', row, b'c', col, operator, value] = *token.as_bytes() else {
+        return None;
+    };
+    let index = |c: u8| (b'1'..=b'9').contains(&c).then(|| usize::from(c - b'1'));
+    let operator = char::from(operator);
+    let value = match value {
+        b'.' if operator == '=' => None,
+        b'1'..=b'9' => NonZeroU8::new(value - b'0'),
+        _ => return None,
+    };
+    let (x, y) = (index(col)?, index(row)?);
+    matches!(operator, '=' | '+' | '-').then_some((x, y, operator, value))
+}
+
+impl Iterator for ReplayReader<'_> {
+    type Item = Result<Move, ReplayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, line) = self.lines.by_ref().find(|(_, line)| !line.trim().is_empty())?;
+        Some(self.parse_move(index, line))
+    }
+}
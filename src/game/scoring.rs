@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::rating::Difficulty;
+
+/// The score of a game and how it's made up, see [crate::Game::score]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Score {
+    pub difficulty: Difficulty,
+
+    /// Points for solving a puzzle of this difficulty
+    pub base: u64,
+
+    /// Extra points for solving faster than the target time of the difficulty
+    pub time_bonus: u64,
+
+    pub hint_penalty: u64,
+
+    pub mistake_penalty: u64,
+
+    /// The base points plus the bonus minus the penalties, but at least 0
+    pub total: u64,
+}
+
+/// The parameters of the scoring model, see [crate::Game::score_with]. The default is what [crate::Game::score] uses.
+///
+/// With the `serde` feature, this can be loaded from a file. Missing fields are taken from the default.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScoringConfig {
+    /// Base points and target time per difficulty. Difficulties that are missing give no points.
+    pub difficulties: BTreeMap<Difficulty, DifficultyScoring>,
+
+    /// The time bonus for solving a puzzle instantly, as a fraction of the base points.
+    /// It decreases linearly until it's 0 at the target time.
+    pub max_time_bonus: f64,
+
+    /// What each hint costs, as a fraction of the base points
+    pub hint_penalty: f64,
+
+    /// What each mistake costs, as a fraction of the base points
+    pub mistake_penalty: f64,
+}
+
+/// The scoring parameters of one difficulty, see [ScoringConfig]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DifficultyScoring {
+    /// Points for solving a puzzle of this difficulty
+    pub base_points: u64,
+
+    /// Solving faster than this gives a time bonus
+    pub target_seconds: u64,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        let difficulties = [
+            (Difficulty::Easy, 1000, 5),
+            (Difficulty::Medium, 2000, 10),
+            (Difficulty::Hard, 4000, 20),
+            (Difficulty::Expert, 7000, 30),
+            (Difficulty::Extreme, 10000, 45),
+        ];
+        Self {
+            difficulties: difficulties
+                .into_iter()
+                .map(|(difficulty, base_points, target_minutes)| {
+                    let target_seconds = target_minutes * 60;
+                    (difficulty, DifficultyScoring { base_points, target_seconds })
+                })
+                .collect(),
+            max_time_bonus: 0.5,
+            hint_penalty: 0.1,
+            mistake_penalty: 0.05,
+        }
+    }
+}
+
+impl ScoringConfig {
+    pub fn score(&self, difficulty: Difficulty, play_time: Duration, hints: usize, mistakes: usize) -> Score {
+        let parameters = self.difficulties.get(&difficulty).copied().unwrap_or_default();
+        let base = parameters.base_points;
+        let fraction_of_base = |fraction: f64| (base as f64 * fraction) as u64;
+        let target = Duration::from_secs(parameters.target_seconds);
+        let time_bonus = if target.is_zero() {
+            0
+        } else {
+            let remaining = target.saturating_sub(play_time);
+            (u128::from(fraction_of_base(self.max_time_bonus)) * remaining.as_millis() / target.as_millis()) as u64
+        };
+        let hint_penalty = fraction_of_base(self.hint_penalty) * hints as u64;
+        let mistake_penalty = fraction_of_base(self.mistake_penalty) * mistakes as u64;
+        Score {
+            difficulty,
+            base,
+            time_bonus,
+            hint_penalty,
+            mistake_penalty,
+            total: (base + time_bonus).saturating_sub(hint_penalty + mistake_penalty),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_config() {
+        let mut config = ScoringConfig {
+            hint_penalty: 0.5,
+            ..ScoringConfig::default()
+        };
+        config.difficulties.remove(&Difficulty::Extreme);
+        let score = config.score(Difficulty::Easy, Duration::from_secs(150), 1, 0);
+        assert_eq!((1000, 250, 500, 750), (score.base, score.time_bonus, score.hint_penalty, score.total));
+        assert_eq!(0, config.score(Difficulty::Extreme, Duration::ZERO, 0, 0).total);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_partial_config() {
+        let config: ScoringConfig = serde_json::from_str(r#"{"mistake_penalty": 0.25}"#).unwrap();
+        assert_eq!(0.25, config.mistake_penalty);
+        assert_eq!(ScoringConfig::default().difficulties, config.difficulties);
+    }
+}
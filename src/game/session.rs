@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::hints::Technique;
+use crate::rating::Difficulty;
+
+/// The outcome of one game, see [crate::Game::result]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameResult {
+    pub difficulty: Difficulty,
+
+    /// False if the player gave up
+    pub solved: bool,
+
+    pub play_time: Duration,
+
+    pub hints: usize,
+
+    pub mistakes: usize,
+
+    /// The [Score::total](crate::Score::total) of the game
+    pub score: u64,
+
+    /// How often the hint engine needs each technique to solve the puzzle
+    pub techniques: BTreeMap<Technique, usize>,
+}
+
+/// Statistics over the games of one difficulty, see [Session::by_difficulty]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DifficultyStats {
+    pub played: usize,
+
+    pub solved: usize,
+
+    /// The average play time of the solved games
+    pub average_time: Duration,
+
+    /// The average score of the solved games
+    pub average_score: u64,
+}
+
+/// A [Session] collects the results of many games, e.g. for a statistics screen.
+///
+/// With the `serde` feature, a session can be serialized to keep the statistics between app starts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Session {
+    /// All results, oldest first
+    results: Vec<GameResult>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, result: GameResult) {
+        self.results.push(result);
+    }
+
+    pub fn results(&self) -> &[GameResult] {
+        &self.results
+    }
+
+    /// The number of games solved in a row since the last one the player gave up on
+    pub fn current_streak(&self) -> usize {
+        self.results.iter().rev().take_while(|result| result.solved).count()
+    }
+
+    /// The longest run of solved games so far
+    pub fn best_streak(&self) -> usize {
+        self.results
+            .split(|result| !result.solved)
+            .map(|streak| streak.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Statistics for each difficulty that was played at least once
+    pub fn by_difficulty(&self) -> BTreeMap<Difficulty, DifficultyStats> {
+        let mut stats = BTreeMap::<Difficulty, DifficultyStats>::new();
+        let mut total_times = BTreeMap::<Difficulty, Duration>::new();
+        let mut total_scores = BTreeMap::<Difficulty, u64>::new();
+        for result in &self.results {
+            let entry = stats.entry(result.difficulty).or_default();
+            entry.played += 1;
+            if result.solved {
+                entry.solved += 1;
+                *total_times.entry(result.difficulty).or_default() += result.play_time;
+                *total_scores.entry(result.difficulty).or_default() += result.score;
+            }
+        }
+        for (difficulty, entry) in &mut stats {
+            if entry.solved > 0 {
+                entry.average_time = total_times[difficulty] / entry.solved as u32;
+                entry.average_score = total_scores[difficulty] / entry.solved as u64;
+            }
+        }
+        stats
+    }
+
+    /// How often each technique was needed over all solved games, i.e. which techniques the player practiced
+    pub fn technique_exposure(&self) -> BTreeMap<Technique, usize> {
+        let mut exposure = BTreeMap::new();
+        for result in self.results.iter().filter(|result| result.solved) {
+            for (technique, count) in &result.techniques {
+                *exposure.entry(*technique).or_default() += count;
+            }
+        }
+        exposure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(difficulty: Difficulty, solved: bool, minutes: u64, score: u64) -> GameResult {
+        GameResult {
+            difficulty,
+            solved,
+            play_time: Duration::from_secs(minutes * 60),
+            hints: 0,
+            mistakes: 0,
+            score,
+            techniques: [(Technique::HiddenSingle, 40), (Technique::NakedSingle, 5)].into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn statistics() {
+        let mut session = Session::new();
+        assert_eq!(0, session.best_streak());
+        session.record(result(Difficulty::Easy, true, 4, 1000));
+        session.record(result(Difficulty::Easy, true, 6, 1200));
+        session.record(result(Difficulty::Hard, false, 30, 0));
+        session.record(result(Difficulty::Easy, true, 5, 1100));
+        assert_eq!(1, session.current_streak());
+        assert_eq!(2, session.best_streak());
+
+        let stats = session.by_difficulty();
+        assert_eq!(
+            DifficultyStats {
+                played: 3,
+                solved: 3,
+                average_time: Duration::from_secs(300),
+                average_score: 1100,
+            },
+            stats[&Difficulty::Easy]
+        );
+        assert_eq!(1, stats[&Difficulty::Hard].played);
+        assert_eq!(0, stats[&Difficulty::Hard].solved);
+        assert!(!stats.contains_key(&Difficulty::Medium));
+
+        assert_eq!(Some(&120), session.technique_exposure().get(&Technique::HiddenSingle));
+    }
+}
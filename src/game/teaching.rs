@@ -0,0 +1,92 @@
+use std::fmt::{self, Display};
+use std::num::NonZeroU8;
+
+use crate::board::Board;
+use crate::constraints::format_cell;
+use crate::hints::{LogicalSolver, Technique};
+
+/// What the hint engine thinks of a value the player entered in teaching mode, see [Game::set_teaching](super::Game::set_teaching)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Annotation {
+    pub x: usize,
+    pub y: usize,
+    pub value: NonZeroU8,
+    pub justification: Justification,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum Justification {
+    /// The value follows logically from the board before the move
+    Deduced {
+        /// The hardest technique needed to deduce the value
+        technique: Technique,
+
+        /// How many logical steps it took, including the placement itself
+        steps: usize,
+    },
+
+    /// Logic leads to a different value for the cell
+    Wrong,
+
+    /// The hint engine can't deduce the value of the cell, so the player guessed
+    Guessed {
+        /// The easiest deduction that was available anywhere on the board instead, if any
+        available: Option<Technique>,
+    },
+}
+
+impl Annotation {
+    /// Checks the placement against the logical steps that are possible on the board before the move.
+    /// If the cell already has a value, it's checked as if the cell was empty. The board must not have conflicts.
+    pub(super) fn new(board: &Board, x: usize, y: usize, value: NonZeroU8) -> Self {
+        let mut board = *board;
+        board.field_mut(x, y).set(None);
+        let mut solver = LogicalSolver::new(board, vec![]);
+        let available = solver.next_step().map(|step| step.technique);
+        let mut hardest = None;
+        let mut steps = 0;
+        let justification = loop {
+            let Some(step) = solver.next() else {
+                break Justification::Guessed { available };
+            };
+            steps += 1;
+            hardest = hardest.max(Some(step.technique));
+            match step.placement {
+                Some(placement) if (placement.x, placement.y) == (x, y) => {
+                    break if placement.value == value {
+                        Justification::Deduced {
+                            technique: hardest.unwrap_or(step.technique),
+                            steps,
+                        }
+                    } else {
+                        Justification::Wrong
+                    };
+                }
+                _ => {}
+            }
+        };
+        Self { x, y, value, justification }
+    }
+}
+
+impl Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cell = format_cell((self.x, self.y));
+        match self.justification {
+            Justification::Deduced { technique, steps: 1 } => write!(f, "{cell}={}: found with {technique}", self.value),
+            Justification::Deduced { technique, steps } => {
+                write!(f, "{cell}={}: found in {steps} steps, the hardest was {technique}", self.value)
+            }
+            Justification::Wrong => write!(f, "{cell}={}: logic leads to a different value here", self.value),
+            Justification::Guessed { available: Some(technique) } => {
+                write!(f, "{cell}={}: you guessed here, a {technique} was available", self.value)
+            }
+            Justification::Guessed { available: None } => {
+                write!(f, "{cell}={}: you guessed here, there was no logical step available", self.value)
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::time::{Duration, Instant};
+
+/// A [Timer] measures how long a game was played. It can be paused, e.g. while the app is in the background.
+///
+/// When a running timer is serialized and deserialized later, it continues from the time it was serialized,
+/// so the time in between doesn't count as play time.
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "TimerState", into = "TimerState"))]
+pub struct Timer {
+    /// Play time before the timer was last started
+    elapsed_before: Duration,
+
+    /// When the timer was started, or `None` if it is paused
+    running_since: Option<Instant>,
+}
+
+impl Timer {
+    /// Starts or resumes the timer. Does nothing if it's already running.
+    pub fn start(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    /// Pauses the timer. Does nothing if it's already paused.
+    pub fn pause(&mut self) {
+        if let Some(running_since) = self.running_since.take() {
+            self.elapsed_before += running_since.elapsed();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    /// The play time so far, not counting the time while paused
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_before + self.running_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+}
+
+/// Timers are equal if they have the same play time and are both running or both paused.
+/// The play time of two running timers is compared when this is called, so it's only equal if they were started
+/// at the same time.
+impl PartialEq for Timer {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_running() == other.is_running() && self.elapsed_before == other.elapsed_before
+    }
+}
+
+impl Eq for Timer {}
+
+/// How a [Timer] is serialized. [Instant] can't be serialized, so this only stores the play time so far.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TimerState {
+    elapsed: Duration,
+    running: bool,
+}
+
+#[cfg(feature = "serde")]
+impl From<Timer> for TimerState {
+    fn from(timer: Timer) -> Self {
+        Self {
+            elapsed: timer.elapsed(),
+            running: timer.is_running(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<TimerState> for Timer {
+    fn from(state: TimerState) -> Self {
+        Self {
+            elapsed_before: state.elapsed,
+            running_since: state.running.then(Instant::now),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn pause_and_resume() {
+        let mut timer = Timer::default();
+        assert_eq!(Duration::ZERO, timer.elapsed());
+        timer.start();
+        sleep(Duration::from_millis(20));
+        timer.pause();
+        let paused = timer.elapsed();
+        assert!(paused >= Duration::from_millis(20));
+        sleep(Duration::from_millis(20));
+        assert_eq!(paused, timer.elapsed());
+        timer.start();
+        sleep(Duration::from_millis(20));
+        assert!(timer.elapsed() >= paused + Duration::from_millis(20));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialization_skips_the_gap() {
+        let mut timer = Timer::default();
+        timer.start();
+        sleep(Duration::from_millis(20));
+        let json = serde_json::to_string(&timer).unwrap();
+        sleep(Duration::from_millis(50));
+        let restored: Timer = serde_json::from_str(&json).unwrap();
+        assert!(restored.is_running());
+        assert!(restored.elapsed() >= Duration::from_millis(20));
+        assert!(restored.elapsed() < Duration::from_millis(50));
+    }
+}
@@ -1,42 +1,335 @@
-use rand::seq::SliceRandom;
+use rand::{seq::SliceRandom, thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
-use std::sync::{Arc, Mutex};
+use std::fmt::{self, Display};
+use std::num::NonZeroU8;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
-use super::solver::{SolverError, solve, generate_solved};
+use super::solver::{SolverContext, SolverError, ConstraintsError, count_solutions, solve_with_constraints, generate_solved, generate_solved_with_constraints, generate_classic_with_rng};
 use super::board::{Board, HEIGHT, WIDTH};
+use super::constraints::Constraint;
+use super::rating::{rate, Difficulty};
+
+/// Symmetry of the pattern of given fields in a generated sudoku
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Symmetry {
+    #[default]
+    None,
+
+    /// The pattern looks the same after rotating the board by 180 degrees
+    Rotational,
+
+    /// The pattern is mirrored at the vertical center line
+    Horizontal,
+
+    /// The pattern is mirrored at the horizontal center line
+    Vertical,
+
+    /// The pattern is mirrored at the diagonal from the top left to the bottom right
+    Diagonal,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 5] = [
+        Symmetry::None,
+        Symmetry::Rotational,
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::Diagonal,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::None => "none",
+            Symmetry::Rotational => "rotational",
+            Symmetry::Horizontal => "horizontal",
+            Symmetry::Vertical => "vertical",
+            Symmetry::Diagonal => "diagonal",
+        }
+    }
+
+    /// Checks if the pattern of given fields of the board has this symmetry. Every board has [Symmetry::None].
+    pub fn matches(&self, board: &Board) -> bool {
+        (0..HEIGHT).all(|y| {
+            (0..WIDTH).all(|x| {
+                let is_empty = board.field(x, y).is_empty();
+                self.orbit(x, y).into_iter().all(|(x, y)| board.field(x, y).is_empty() == is_empty)
+            })
+        })
+    }
+
+    /// Returns the cells that have to be removed together with the given cell to keep the symmetry
+    fn orbit(&self, x: usize, y: usize) -> Vec<(usize, usize)> {
+        let mirrored = match self {
+            Symmetry::None => (x, y),
+            Symmetry::Rotational => (WIDTH - 1 - x, HEIGHT - 1 - y),
+            Symmetry::Horizontal => (WIDTH - 1 - x, y),
+            Symmetry::Vertical => (x, HEIGHT - 1 - y),
+            Symmetry::Diagonal => (y, x),
+        };
+        if mirrored == (x, y) {
+            vec![(x, y)]
+        } else {
+            vec![(x, y), mirrored]
+        }
+    }
+}
+
+impl Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Symmetry::ALL
+            .into_iter()
+            .find(|symmetry| symmetry.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown symmetry '{s}'"))
+    }
+}
 
 pub fn generate() -> Board {
-    let mut board = generate_solved();
+    _generate(generate_solved(), &[])
+}
+
+/// Like [generate], but generates a sudoku variant that has to fulfill the given constraints in addition to the classic rules.
+/// The generated board has a unique solution under those constraints, but usually not under the classic rules alone.
+/// Returns an error if a constraint is invalid or the constraints can't be fulfilled, see
+/// [check_constraints](crate::check_constraints).
+pub fn generate_with_constraints(constraints: &[Constraint]) -> Result<Board, ConstraintsError> {
+    Ok(_generate(generate_solved_with_constraints(constraints)?, constraints))
+}
+
+fn _generate(solved: Board, constraints: &[Constraint]) -> Board {
+    let board = remove_fields(solved, constraints, Symmetry::None, &mut thread_rng());
+    assert!(solve_with_constraints(board, constraints).is_ok());
+    board
+}
+
+/// Like [generate], but the given fields form a pattern with the given symmetry
+pub fn generate_symmetric(symmetry: Symmetry) -> Board {
+    _generate_symmetric(symmetry, &mut thread_rng())
+}
+
+/// Like [generate_symmetric], but always generates the same board for the same seed
+pub fn generate_symmetric_seeded(symmetry: Symmetry, seed: u64) -> Board {
+    _generate_symmetric(symmetry, &mut seeded_rng(seed))
+}
+
+/// The random number generator behind all seeded generation. Unlike `StdRng`, whose algorithm may change in any
+/// version of `rand`, ChaCha12 is fixed and gives the same numbers on all platforms, see "Reproducibility" in the
+/// crate documentation.
+pub(crate) fn seeded_rng(seed: u64) -> ChaCha12Rng {
+    ChaCha12Rng::seed_from_u64(seed)
+}
+
+fn _generate_symmetric(symmetry: Symmetry, rng: &mut impl Rng) -> Board {
+    let board = remove_fields(generate_classic_with_rng(rng), &[], symmetry, rng);
+    assert!(solve_with_constraints(board, &[]).is_ok());
+    board
+}
+
+/// Generates sudokus until one has the requested difficulty according to [rate](crate::rate).
+/// Easy and extreme sudokus are common, expert ones can take a while.
+pub fn generate_with_difficulty(difficulty: Difficulty, symmetry: Symmetry) -> Board {
+    _generate_with_difficulty(difficulty, symmetry, &mut thread_rng(), &AtomicBool::new(false))
+        .expect("Generation without a cancel flag can't be cancelled")
+}
+
+/// Like [generate_with_difficulty], but always generates the same board for the same seed
+pub fn generate_with_difficulty_seeded(difficulty: Difficulty, symmetry: Symmetry, seed: u64) -> Board {
+    _generate_with_difficulty(difficulty, symmetry, &mut seeded_rng(seed), &AtomicBool::new(false))
+        .expect("Generation without a cancel flag can't be cancelled")
+}
+
+/// Like [generate_with_difficulty_seeded], but checks `cancel` before each generated sudoku and returns `None` once
+/// it's set, e.g. when nobody is waiting for an expert sudoku anymore
+pub fn generate_with_difficulty_cancellable(
+    difficulty: Difficulty,
+    symmetry: Symmetry,
+    seed: u64,
+    cancel: &AtomicBool,
+) -> Option<Board> {
+    _generate_with_difficulty(difficulty, symmetry, &mut seeded_rng(seed), cancel)
+}
+
+fn _generate_with_difficulty(
+    difficulty: Difficulty,
+    symmetry: Symmetry,
+    rng: &mut impl Rng,
+    cancel: &AtomicBool,
+) -> Option<Board> {
+    while !cancel.load(Ordering::Relaxed) {
+        let board = _generate_symmetric(symmetry, rng);
+        if rate(&board).unwrap().difficulty == difficulty {
+            return Some(board);
+        }
+    }
+    None
+}
+
+/// Generates a sudoku with exactly `num_solutions` solutions, checked with [count_solutions](crate::count_solutions),
+/// e.g. to show why a proper sudoku needs a unique one. With 0, the board has no conflicts but can't be solved.
+/// This is meant for small numbers; the larger the number, the more attempts it takes.
+pub fn generate_with_solution_count(num_solutions: usize) -> Board {
+    _generate_with_solution_count(num_solutions, &mut thread_rng())
+}
+
+/// Like [generate_with_solution_count], but always generates the same board for the same seed
+pub fn generate_with_solution_count_seeded(num_solutions: usize, seed: u64) -> Board {
+    _generate_with_solution_count(num_solutions, &mut seeded_rng(seed))
+}
+
+fn _generate_with_solution_count(num_solutions: usize, rng: &mut impl Rng) -> Board {
+    if num_solutions == 0 {
+        return generate_unsolvable(rng);
+    }
+    loop {
+        // Removing fields only adds solutions, so remove each one unless that leads to too many
+        let mut board = generate_classic_with_rng(rng);
+        let mut all_fields: Vec<(usize, usize)> = (0..WIDTH).flat_map(|x| (0..HEIGHT).map(move |y| (x, y))).collect();
+        all_fields.shuffle(rng);
+        for (x, y) in all_fields {
+            let value = board.field(x, y).get();
+            board.field_mut(x, y).set(None);
+            if count_solutions(&board, num_solutions + 1) > num_solutions {
+                board.field_mut(x, y).set(value);
+            }
+        }
+        if count_solutions(&board, num_solutions + 1) == num_solutions {
+            return board;
+        }
+    }
+}
+
+/// A sudoku without conflicts and without solutions. Any solution of a sudoku with a unique solution and an additional
+/// given that differs from that solution would be another solution of the original sudoku, so there can't be one.
+fn generate_unsolvable(rng: &mut impl Rng) -> Board {
+    loop {
+        let mut board = _generate_symmetric(Symmetry::None, rng);
+        let solution = solve_with_constraints(board, &[]).unwrap();
+        let mut empty_fields: Vec<(usize, usize)> = (0..WIDTH)
+            .flat_map(|x| (0..HEIGHT).map(move |y| (x, y)))
+            .filter(|&(x, y)| board.field(x, y).is_empty())
+            .collect();
+        empty_fields.shuffle(rng);
+        for (x, y) in empty_fields {
+            for value in (1..=9).filter_map(NonZeroU8::new) {
+                if Some(value) == solution.field(x, y).get() {
+                    continue;
+                }
+                board.field_mut(x, y).set(Some(value));
+                if !board.has_conflicts() {
+                    return board;
+                }
+                board.field_mut(x, y).set(None);
+            }
+        }
+    }
+}
+
+/// Generates a greater-than sudoku. The returned constraints contain a greater-than sign between every two adjacent
+/// cells of each 3x3 region, derived from a random solution, and the board has as many fields removed as possible
+/// while keeping the solution unique.
+pub fn generate_greater_than() -> (Board, Vec<Constraint>) {
+    let solution = generate_solved();
+    let constraints = greater_than_signs(&solution);
+    let board = remove_fields(solution, &constraints, Symmetry::None, &mut thread_rng());
+    assert!(solve_with_constraints(board, &constraints).is_ok());
+    (board, constraints)
+}
+
+fn greater_than_signs(solution: &Board) -> Vec<Constraint> {
+    let sign = |a: (usize, usize), b: (usize, usize)| {
+        if solution.field(a.0, a.1).get() > solution.field(b.0, b.1).get() {
+            Constraint::GreaterThan { greater: a, smaller: b }
+        } else {
+            Constraint::GreaterThan { greater: b, smaller: a }
+        }
+    };
+    let mut signs = vec![];
+    for x in 0..WIDTH {
+        for y in 0..HEIGHT {
+            if (x + 1) % 3 != 0 {
+                signs.push(sign((x, y), (x + 1, y)));
+            }
+            if (y + 1) % 3 != 0 {
+                signs.push(sign((x, y), (x, y + 1)));
+            }
+        }
+    }
+    signs
+}
+
+pub(crate) fn remove_fields(mut board: Board, constraints: &[Constraint], symmetry: Symmetry, rng: &mut impl Rng) -> Board {
     let mut all_fields: Vec<(u8, u8)> = (0u8..HEIGHT as u8).flat_map(|x| (0u8..WIDTH as u8).map(move |y| (x, y))).collect();
-    all_fields.shuffle(&mut rand::thread_rng());
+    all_fields.shuffle(rng);
+    let mut context = SolverContext::with_constraints(constraints);
     for (x, y) in all_fields {
-        remove_field_if_unambigious(&mut board, x as usize, y as usize);
+        remove_fields_if_unambigious(&mut board, &symmetry.orbit(x as usize, y as usize), &mut context);
     }
-
-    assert!(solve(board).is_ok());
     board
 }
 
+/// Removes all the given fields together, or none of them if that would make the board ambigious
+fn remove_fields_if_unambigious(board: &mut Board, fields: &[(usize, usize)], context: &mut SolverContext) -> bool {
+    let previous = *board;
+    for &(x, y) in fields {
+        board.field_mut(x, y).set(None);
+    }
+    if *board == previous {
+        return false;
+    }
+    if is_ambigious(*board, context) {
+        *board = previous;
+        false
+    } else {
+        true
+    }
+}
+
+/// Generates a sudoku with as many empty fields as possible by trying many orders of removing fields.
+/// With the `parallel` feature, the orders are tried on all cores, otherwise one after the other.
 pub fn generate_max_empty() -> Board {
-    let board = generate_solved();
-    let board = remove_max(board);
-    assert!(solve(board).is_ok());
+    _generate_max_empty(generate_solved(), &[])
+}
+
+/// Like [generate_max_empty], but generates a sudoku variant that has to fulfill the given constraints in addition to the classic rules.
+/// Returns an error like [generate_with_constraints].
+pub fn generate_max_empty_with_constraints(constraints: &[Constraint]) -> Result<Board, ConstraintsError> {
+    Ok(_generate_max_empty(generate_solved_with_constraints(constraints)?, constraints))
+}
+
+fn _generate_max_empty(solved: Board, constraints: &[Constraint]) -> Board {
+    let board = remove_max(solved, constraints);
+    assert!(solve_with_constraints(board, constraints).is_ok());
     board
 }
 
-fn remove_max(board: Board) -> Board {
-    let best_board = Arc::new(Mutex::new((board.num_empty(), board)));
-    _remove_max(board, Arc::clone(&best_board));
-    let best_board = best_board.lock().unwrap();
+fn remove_max(board: Board, constraints: &[Constraint]) -> Board {
+    let best_board = Mutex::new((board.num_empty(), board));
+    _remove_max(board, &mut SolverContext::with_constraints(constraints), &best_board);
+    let best_board = best_board.into_inner().unwrap();
     best_board.1
 }
 
-fn _remove_max(board: Board, best_board: Arc<Mutex<(usize, Board)>>) {
+/// With the `parallel` feature, each rayon job gets its own context with the constraints of the given one,
+/// otherwise the given context is used for the whole search.
+fn _remove_max(board: Board, context: &mut SolverContext, best_board: &Mutex<(usize, Board)>) {
     {
         let num_empty = board.num_empty();
         let mut prev_best = best_board.lock().unwrap();
         if num_empty > prev_best.0 {
-            println!("Found board with {num_empty} empty fields");
+            eprintln!("Found board with {num_empty} empty fields");
             prev_best.0 = num_empty;
             prev_best.1 = board;
         }
@@ -44,23 +337,34 @@ fn _remove_max(board: Board, best_board: Arc<Mutex<(usize, Board)>>) {
     }
 
     let mut all_fields: Vec<(u8, u8)> = (0u8..HEIGHT as u8).flat_map(|x| (0u8..WIDTH as u8).map(move |y| (x, y))).collect();
-    all_fields.shuffle(&mut rand::thread_rng());
-    all_fields.par_iter().for_each(move |(x, y)| {
+    all_fields.shuffle(&mut thread_rng());
+    let remove_and_recurse = |context: &mut SolverContext, &(x, y): &(u8, u8)| {
         let mut board = board;
-        if remove_field_if_unambigious(&mut board, *x as usize, *y as usize) {
-            _remove_max(board, Arc::clone(&best_board));
+        if remove_field_if_unambigious(&mut board, x as usize, y as usize, context) {
+            _remove_max(board, context, best_board);
         }
-    });
+    };
+    #[cfg(feature = "parallel")]
+    {
+        let constraints = context.constraints();
+        all_fields
+            .par_iter()
+            .for_each_init(|| SolverContext::with_constraints(constraints), remove_and_recurse);
+    }
+    #[cfg(not(feature = "parallel"))]
+    for field in &all_fields {
+        remove_and_recurse(context, field);
+    }
 }
 
-fn remove_field_if_unambigious(board: &mut Board, x: usize, y: usize) -> bool {
+fn remove_field_if_unambigious(board: &mut Board, x: usize, y: usize, context: &mut SolverContext) -> bool {
     let mut field = board.field_mut(x, y);
     let value = field.get();
     if value.is_none() {
         return false;
     }
     field.set(None);
-    if is_ambigious(*board) {
+    if is_ambigious(*board, context) {
         board.field_mut(x, y).set(value);
         false
     } else {
@@ -68,10 +372,11 @@ fn remove_field_if_unambigious(board: &mut Board, x: usize, y: usize) -> bool {
     }
 }
 
-fn is_ambigious(board: Board) -> bool {
-    match solve(board) {
+fn is_ambigious(board: Board, context: &mut SolverContext) -> bool {
+    match context.solve(board) {
         Err(SolverError::Conflicting) => panic!("Board is conflicting"),
         Err(SolverError::NotSolvable) => panic!("Board is not solvable"),
+        Err(SolverError::InvalidConstraint(constraint)) => panic!("Constraint '{constraint}' is invalid"),
         Err(SolverError::Ambigious) => true,
         Ok(_) => false,
     }
@@ -80,6 +385,20 @@ fn is_ambigious(board: Board) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::formats::{format_line, parse_line};
+    use crate::solver::solve;
+
+    #[test]
+    fn solution_counts() {
+        for num_solutions in [0, 1, 2, 3, 10] {
+            for seed in 0..3 {
+                let board = generate_with_solution_count_seeded(num_solutions, seed);
+                assert!(!board.has_conflicts());
+                assert_eq!(num_solutions, count_solutions(&board, num_solutions + 1));
+                assert_eq!(board, generate_with_solution_count_seeded(num_solutions, seed));
+            }
+        }
+    }
 
     #[test]
     fn generate_10() {
@@ -90,5 +409,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn generate_symmetric() {
+        for symmetry in Symmetry::ALL {
+            let board = super::generate_symmetric(symmetry);
+            assert!(solve(board).is_ok());
+            for x in 0..WIDTH {
+                for y in 0..HEIGHT {
+                    for (other_x, other_y) in symmetry.orbit(x, y) {
+                        assert_eq!(board.field(x, y).is_empty(), board.field(other_x, other_y).is_empty());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn generate_with_difficulty() {
+        let board = super::generate_with_difficulty(Difficulty::Easy, Symmetry::Rotational);
+        assert_eq!(Difficulty::Easy, rate(&board).unwrap().difficulty);
+    }
+
+    #[test]
+    fn generate_seeded() {
+        assert_eq!(
+            generate_symmetric_seeded(Symmetry::Rotational, 42),
+            generate_symmetric_seeded(Symmetry::Rotational, 42)
+        );
+        assert_ne!(
+            generate_symmetric_seeded(Symmetry::Rotational, 42),
+            generate_symmetric_seeded(Symmetry::Rotational, 43)
+        );
+    }
+
+    #[test]
+    fn seeded_puzzles_are_stable() {
+        // These must only change with a breaking version, see "Reproducibility" in the crate documentation
+        assert_eq!(
+            ".45..8.3......5..13.7..9.....64....99.2...7.41....78.....9..6.28..5......2.1..58.",
+            format_line(&generate_symmetric_seeded(Symmetry::Rotational, 42))
+        );
+        assert_eq!(
+            "7..9.....9.173...24.....7..14.3..2.8....2....8.2..9.57..4.....92...985.3.....6..4",
+            format_line(&generate_with_difficulty_seeded(Difficulty::Medium, Symmetry::Rotational, 1246))
+        );
+    }
+
+    #[test]
+    fn symmetry_matches() {
+        let board = generate_symmetric_seeded(Symmetry::Vertical, 42);
+        assert!(Symmetry::Vertical.matches(&board));
+        assert!(Symmetry::None.matches(&board));
+        assert!(!Symmetry::Horizontal.matches(&board));
+    }
+
+    #[test]
+    fn symmetry_from_str() {
+        for symmetry in Symmetry::ALL {
+            assert_eq!(Ok(symmetry), symmetry.to_string().parse());
+        }
+        assert!("radial".parse::<Symmetry>().is_err());
+    }
+
+    #[test]
+    fn generate_with_constraints() {
+        // Only one board because the uniqueness checks for sparse variant boards are slow in debug builds
+        let constraints = [Constraint::AntiKing];
+        let board = super::generate_with_constraints(&constraints).unwrap();
+        assert!(!board.has_conflicts_with(&constraints));
+        assert!(solve_with_constraints(board, &constraints).is_ok());
+        assert!(board.num_empty() > 0);
+    }
+
+    #[test]
+    fn generate_with_invalid_constraints() {
+        let invalid = Constraint::GreaterThan { greater: (9, 0), smaller: (8, 0) };
+        assert_eq!(Err(ConstraintsError::Invalid(invalid)), super::generate_with_constraints(&[invalid]));
+        assert_eq!(Err(ConstraintsError::Invalid(invalid)), generate_max_empty_with_constraints(&[invalid]));
+    }
+
+    #[test]
+    fn generate_greater_than_10() {
+        for _ in 0..10 {
+            let (board, constraints) = generate_greater_than();
+            assert_eq!(108, constraints.len());
+            assert!(solve_with_constraints(board, &constraints).is_ok());
+            assert!(board.num_empty() > 0);
+        }
+    }
+
+    #[test]
+    fn remove_max_finds_minimal_puzzle() {
+        // 17 given fields, none of which can be removed
+        let puzzle = parse_line(".......1.4.........2...........5.4.7..8...3....1.9....3..4..2...5.1........8.6...").unwrap();
+        let solution = solve(puzzle).unwrap();
+        let mut start = puzzle;
+        for (x, y) in [(0, 0), (4, 1), (8, 2)] {
+            start.field_mut(x, y).set(solution.field(x, y).get());
+        }
+        assert_eq!(puzzle, remove_max(start, &[]));
+    }
+
     // TODO More tests
 }
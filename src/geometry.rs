@@ -0,0 +1,127 @@
+//! Lookup tables for the classic layout of the board, computed at compile time, so hot loops don't have to
+//! recompute which region a field is in or which fields it sees.
+
+use crate::board::{HEIGHT, WIDTH};
+
+/// The number of other fields in the same row, column or region as a field
+pub const NUM_PEERS: usize = 20;
+
+/// For each field, the 3x3 region it's in. Regions are numbered left-to-right, then top-to-bottom.
+const REGIONS: [[u8; HEIGHT]; WIDTH] = {
+    let mut regions = [[0; HEIGHT]; WIDTH];
+    let mut x = 0;
+    while x < WIDTH {
+        let mut y = 0;
+        while y < HEIGHT {
+            regions[x][y] = (3 * (y / 3) + x / 3) as u8;
+            y += 1;
+        }
+        x += 1;
+    }
+    regions
+};
+
+/// For each field, its position in its region, numbered like the regions themselves
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+const POSITIONS_IN_REGION: [[u8; HEIGHT]; WIDTH] = {
+    let mut positions = [[0; HEIGHT]; WIDTH];
+    let mut x = 0;
+    while x < WIDTH {
+        let mut y = 0;
+        while y < HEIGHT {
+            positions[x][y] = (3 * (y % 3) + x % 3) as u8;
+            y += 1;
+        }
+        x += 1;
+    }
+    positions
+};
+
+/// For each field, the other fields in the same row, column or region, column by column
+const PEERS: [[[(u8, u8); NUM_PEERS]; HEIGHT]; WIDTH] = {
+    let mut peers = [[[(0, 0); NUM_PEERS]; HEIGHT]; WIDTH];
+    let mut x = 0;
+    while x < WIDTH {
+        let mut y = 0;
+        while y < HEIGHT {
+            let mut num_peers = 0;
+            let mut peer_x = 0;
+            while peer_x < WIDTH {
+                let mut peer_y = 0;
+                while peer_y < HEIGHT {
+                    let is_self = peer_x == x && peer_y == y;
+                    let same_region = REGIONS[peer_x][peer_y] == REGIONS[x][y];
+                    if !is_self && (peer_x == x || peer_y == y || same_region) {
+                        peers[x][y][num_peers] = (peer_x as u8, peer_y as u8);
+                        num_peers += 1;
+                    }
+                    peer_y += 1;
+                }
+                peer_x += 1;
+            }
+            assert!(num_peers == NUM_PEERS);
+            y += 1;
+        }
+        x += 1;
+    }
+    peers
+};
+
+/// The 3x3 region of the field, numbered left-to-right, then top-to-bottom
+#[inline]
+pub fn region_of(x: usize, y: usize) -> usize {
+    usize::from(REGIONS[x][y])
+}
+
+/// The position of the field in its 3x3 region, numbered left-to-right, then top-to-bottom
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+#[inline]
+pub fn position_in_region(x: usize, y: usize) -> usize {
+    usize::from(POSITIONS_IN_REGION[x][y])
+}
+
+/// The field at the given position of a 3x3 region, the inverse of [region_of] and [position_in_region]
+#[inline]
+pub const fn field_in_region(region: usize, position: usize) -> (usize, usize) {
+    (3 * (region % 3) + position % 3, 3 * (region / 3) + position / 3)
+}
+
+/// The other fields in the same row, column or region as the field, column by column
+#[inline]
+pub fn peers(x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+    PEERS[x][y].iter().map(|&(peer_x, peer_y)| (usize::from(peer_x), usize::from(peer_y)))
+}
+
+/// True if the two fields are different and in the same row, column or region
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+#[inline]
+pub fn are_peers(a: (usize, usize), b: (usize, usize)) -> bool {
+    a != b && (a.0 == b.0 || a.1 == b.1 || region_of(a.0, a.1) == region_of(b.0, b.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions() {
+        assert_eq!(0, region_of(2, 2));
+        assert_eq!(5, region_of(6, 4));
+        assert_eq!(7, position_in_region(7, 5));
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                assert_eq!((x, y), field_in_region(region_of(x, y), position_in_region(x, y)));
+            }
+        }
+    }
+
+    #[test]
+    fn peers_of_field() {
+        let peers: Vec<_> = peers(4, 4).collect();
+        assert_eq!(NUM_PEERS, peers.len());
+        assert!(peers.contains(&(4, 0)) && peers.contains(&(0, 4)) && peers.contains(&(3, 5)));
+        assert!(!peers.contains(&(4, 4)) && !peers.contains(&(2, 2)));
+        assert!(are_peers((4, 4), (3, 5)));
+        assert!(!are_peers((4, 4), (4, 4)));
+    }
+}
@@ -0,0 +1,247 @@
+//! A gRPC service for generating, solving, rating and hints, built with the `grpc` feature. The messages are defined
+//! in proto/sudoku.proto and generated into [proto]. Add [SudokuService] to a tonic server, e.g.
+//! `Server::builder().add_service(SudokuServer::new(SudokuService))`, like the `sudoku grpc` command does.
+//! The computations run on tokio's blocking thread pool, so they don't hold up other requests.
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::board::Board;
+use crate::formats::{format_line, parse_line};
+use crate::generator::{generate_symmetric_seeded, generate_with_difficulty_seeded, Symmetry};
+use crate::hints::hint;
+use crate::rating::{rate, Difficulty};
+use crate::solver::solve;
+
+/// The messages and the server trait generated from proto/sudoku.proto
+pub mod proto {
+    tonic::include_proto!("sudoku");
+}
+
+use proto::batch_solve_response::Result as BatchResult;
+use proto::sudoku_server::Sudoku;
+
+/// Implements the `Sudoku` service of proto/sudoku.proto, see [proto::sudoku_server::SudokuServer]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SudokuService;
+
+#[tonic::async_trait]
+impl Sudoku for SudokuService {
+    async fn generate(
+        &self,
+        request: Request<proto::GenerateRequest>,
+    ) -> Result<Response<proto::GenerateResponse>, Status> {
+        let request = request.into_inner();
+        let difficulty = proto::Difficulty::try_from(request.difficulty)
+            .map_err(|error| Status::invalid_argument(error.to_string()))?;
+        let symmetry = proto::Symmetry::try_from(request.symmetry)
+            .map_err(|error| Status::invalid_argument(error.to_string()))?;
+        let seed = request.seed.unwrap_or_else(rand::random);
+        let puzzle = blocking(move || {
+            let symmetry = symmetry_from_proto(symmetry);
+            Ok(match difficulty_from_proto(difficulty) {
+                Some(difficulty) => generate_with_difficulty_seeded(difficulty, symmetry, seed),
+                None => generate_symmetric_seeded(symmetry, seed),
+            })
+        })
+        .await?;
+        Ok(Response::new(proto::GenerateResponse {
+            puzzle: format_line(&puzzle),
+            seed,
+        }))
+    }
+
+    async fn solve(&self, request: Request<proto::SolveRequest>) -> Result<Response<proto::SolveResponse>, Status> {
+        let puzzle = request.into_inner().puzzle;
+        let solution = blocking(move || solve_puzzle(&puzzle)).await?;
+        Ok(Response::new(proto::SolveResponse { solution }))
+    }
+
+    async fn rate(&self, request: Request<proto::RateRequest>) -> Result<Response<proto::RateResponse>, Status> {
+        let puzzle = request.into_inner().puzzle;
+        let report = blocking(move || {
+            rate(&parse_puzzle(&puzzle)?).map_err(|error| Status::failed_precondition(error.to_string()))
+        })
+        .await?;
+        Ok(Response::new(proto::RateResponse {
+            difficulty: difficulty_to_proto(report.difficulty).into(),
+            requires_guessing: report.requires_guessing,
+            techniques: report
+                .technique_counts()
+                .into_iter()
+                .map(|(technique, count)| proto::TechniqueCount {
+                    technique: technique.name().to_string(),
+                    count: count as u32,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn hint(&self, request: Request<proto::HintRequest>) -> Result<Response<proto::HintResponse>, Status> {
+        let puzzle = request.into_inner().puzzle;
+        let step = blocking(move || {
+            hint(&parse_puzzle(&puzzle)?, &[]).ok_or_else(|| Status::failed_precondition("No logical step found"))
+        })
+        .await?;
+        Ok(Response::new(proto::HintResponse {
+            technique: step.technique.name().to_string(),
+            explanation: step.explanation(),
+            placement: step.placement.map(|placement| proto::Placement {
+                x: placement.x as u32,
+                y: placement.y as u32,
+                value: u32::from(placement.value.get()),
+            }),
+        }))
+    }
+
+    type BatchSolveStream = ReceiverStream<Result<proto::BatchSolveResponse, Status>>;
+
+    async fn batch_solve(
+        &self,
+        request: Request<Streaming<proto::SolveRequest>>,
+    ) -> Result<Response<Self::BatchSolveStream>, Status> {
+        let mut requests = request.into_inner();
+        let (sender, receiver) = mpsc::channel(16);
+        tokio::spawn(async move {
+            let mut index = 0;
+            while let Some(request) = requests.next().await {
+                let response = match request {
+                    Ok(request) => blocking(move || Ok(batch_response(index, &request.puzzle))).await,
+                    Err(status) => Err(status),
+                };
+                let failed = response.is_err();
+                // The client is gone if sending fails, so there's nobody left to solve puzzles for
+                if sender.send(response).await.is_err() || failed {
+                    break;
+                }
+                index += 1;
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+}
+
+/// Runs a computation on the blocking thread pool
+async fn blocking<T>(compute: impl FnOnce() -> Result<T, Status> + Send + 'static) -> Result<T, Status>
+where
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(compute)
+        .await
+        .map_err(|error| Status::internal(error.to_string()))?
+}
+
+fn parse_puzzle(puzzle: &str) -> Result<Board, Status> {
+    parse_line(puzzle).map_err(|error| Status::invalid_argument(error.to_string()))
+}
+
+fn solve_puzzle(puzzle: &str) -> Result<String, Status> {
+    let solution = solve(parse_puzzle(puzzle)?).map_err(|error| Status::failed_precondition(error.to_string()))?;
+    Ok(format_line(&solution))
+}
+
+fn batch_response(index: u64, puzzle: &str) -> proto::BatchSolveResponse {
+    let result = match solve_puzzle(puzzle) {
+        Ok(solution) => BatchResult::Solution(solution),
+        Err(status) => BatchResult::Error(status.message().to_string()),
+    };
+    proto::BatchSolveResponse {
+        index,
+        result: Some(result),
+    }
+}
+
+fn difficulty_from_proto(difficulty: proto::Difficulty) -> Option<Difficulty> {
+    match difficulty {
+        proto::Difficulty::Unspecified => None,
+        proto::Difficulty::Easy => Some(Difficulty::Easy),
+        proto::Difficulty::Medium => Some(Difficulty::Medium),
+        proto::Difficulty::Hard => Some(Difficulty::Hard),
+        proto::Difficulty::Expert => Some(Difficulty::Expert),
+        proto::Difficulty::Extreme => Some(Difficulty::Extreme),
+    }
+}
+
+fn difficulty_to_proto(difficulty: Difficulty) -> proto::Difficulty {
+    match difficulty {
+        Difficulty::Easy => proto::Difficulty::Easy,
+        Difficulty::Medium => proto::Difficulty::Medium,
+        Difficulty::Hard => proto::Difficulty::Hard,
+        Difficulty::Expert => proto::Difficulty::Expert,
+        Difficulty::Extreme => proto::Difficulty::Extreme,
+    }
+}
+
+fn symmetry_from_proto(symmetry: proto::Symmetry) -> Symmetry {
+    match symmetry {
+        proto::Symmetry::None => Symmetry::None,
+        proto::Symmetry::Rotational => Symmetry::Rotational,
+        proto::Symmetry::Horizontal => Symmetry::Horizontal,
+        proto::Symmetry::Vertical => Symmetry::Vertical,
+        proto::Symmetry::Diagonal => Symmetry::Diagonal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    fn puzzle_request<T>(message: impl FnOnce(String) -> T) -> Request<T> {
+        Request::new(message(PUZZLE.to_string()))
+    }
+
+    #[tokio::test]
+    async fn methods() {
+        let service = SudokuService;
+        let solution = service.solve(puzzle_request(|puzzle| proto::SolveRequest { puzzle })).await.unwrap();
+        assert_eq!(81, solution.into_inner().solution.len());
+        let report = service.rate(puzzle_request(|puzzle| proto::RateRequest { puzzle })).await.unwrap().into_inner();
+        assert_eq!(proto::Difficulty::Easy, report.difficulty());
+        let step = service.hint(puzzle_request(|puzzle| proto::HintRequest { puzzle })).await.unwrap();
+        assert!(step.into_inner().placement.is_some());
+
+        let generate = || {
+            service.generate(Request::new(proto::GenerateRequest {
+                difficulty: proto::Difficulty::Easy.into(),
+                symmetry: proto::Symmetry::Rotational.into(),
+                seed: Some(5),
+            }))
+        };
+        let generated = generate().await.unwrap().into_inner();
+        assert_eq!(generated, generate().await.unwrap().into_inner());
+        assert_eq!(
+            format_line(&generate_with_difficulty_seeded(Difficulty::Easy, Symmetry::Rotational, 5)),
+            generated.puzzle
+        );
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let service = SudokuService;
+        let status = service.solve(Request::new(proto::SolveRequest { puzzle: "123".to_string() })).await.unwrap_err();
+        assert_eq!(tonic::Code::InvalidArgument, status.code());
+        let ambigious = proto::SolveRequest { puzzle: ".".repeat(81) };
+        assert_eq!(tonic::Code::FailedPrecondition, service.solve(Request::new(ambigious)).await.unwrap_err().code());
+        let unknown_difficulty = proto::GenerateRequest {
+            difficulty: 42,
+            ..Default::default()
+        };
+        assert_eq!(
+            tonic::Code::InvalidArgument,
+            service.generate(Request::new(unknown_difficulty)).await.unwrap_err().code()
+        );
+    }
+
+    #[test]
+    fn batch_responses() {
+        assert!(matches!(batch_response(0, PUZZLE).result, Some(BatchResult::Solution(_))));
+        assert_eq!(
+            Some(BatchResult::Error("Sudoku has multiple valid solutions".to_string())),
+            batch_response(1, &".".repeat(81)).result
+        );
+    }
+}
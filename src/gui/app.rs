@@ -0,0 +1,353 @@
+use std::num::NonZeroU8;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use sudoku::{
+    generate_with_difficulty, parse_puzzles, render, Board, BoardParseError, Difficulty, Figure, Game, GameError, PuzzleEditor,
+    RenderFormat, Symmetry, HEIGHT, WIDTH,
+};
+
+/// What the board is used for
+pub enum Mode {
+    /// Solving a puzzle, with the givens locked
+    Play(Box<Game>),
+
+    /// Entering a puzzle, e.g. from a newspaper, with live feedback whether it has a unique solution
+    Edit(PuzzleEditor),
+}
+
+/// State of the GUI, independent from how it's drawn
+pub struct App {
+    mode: Mode,
+
+    selected: (usize, usize),
+
+    /// If set, digits toggle notes instead of entering values
+    pub notes_mode: bool,
+
+    /// The difficulty of the next generated puzzle
+    pub difficulty: Difficulty,
+
+    /// Text field for pasting a puzzle in any of the supported formats
+    pub puzzle_text: String,
+
+    export_format: RenderFormat,
+    pub export_path: String,
+
+    message: String,
+
+    /// Set while a puzzle is generated on a background thread
+    generating: Option<Receiver<Board>>,
+}
+
+impl App {
+    pub fn new(puzzle: Board) -> Self {
+        let mut app = Self {
+            mode: Mode::Edit(PuzzleEditor::new()),
+            selected: (0, 0),
+            notes_mode: false,
+            difficulty: Difficulty::Easy,
+            puzzle_text: String::new(),
+            export_format: RenderFormat::Pdf,
+            export_path: "sudoku.pdf".to_string(),
+            message: String::new(),
+            generating: None,
+        };
+        app.play(puzzle);
+        app
+    }
+
+    pub fn mode(&self) -> &Mode {
+        &self.mode
+    }
+
+    /// The board as it's shown, i.e. with the values entered by the player
+    pub fn board(&self) -> &Board {
+        match &self.mode {
+            Mode::Play(game) => game.board(),
+            Mode::Edit(editor) => editor.board(),
+        }
+    }
+
+    /// The puzzle without the values entered by the player, e.g. for exporting it
+    pub fn puzzle(&self) -> &Board {
+        match &self.mode {
+            Mode::Play(game) => game.puzzle(),
+            Mode::Edit(editor) => editor.board(),
+        }
+    }
+
+    pub fn is_given(&self, x: usize, y: usize) -> bool {
+        match &self.mode {
+            Mode::Play(game) => game.is_given(x, y),
+            Mode::Edit(_) => false,
+        }
+    }
+
+    pub fn is_conflicting(&self, x: usize, y: usize) -> bool {
+        match &self.mode {
+            Mode::Play(game) => game.is_conflicting(x, y),
+            Mode::Edit(_) => false,
+        }
+    }
+
+    /// The notes of an empty cell while playing
+    pub fn notes(&self, x: usize, y: usize) -> Vec<NonZeroU8> {
+        match &self.mode {
+            Mode::Play(game) => game.notes().values(x, y).collect(),
+            Mode::Edit(_) => vec![],
+        }
+    }
+
+    pub fn selected(&self) -> (usize, usize) {
+        self.selected
+    }
+
+    pub fn select(&mut self, x: usize, y: usize) {
+        self.selected = (x.min(WIDTH - 1), y.min(HEIGHT - 1));
+    }
+
+    pub fn move_selection(&mut self, dx: isize, dy: isize) {
+        let (x, y) = self.selected;
+        self.select(x.saturating_add_signed(dx), y.saturating_add_signed(dy));
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn is_generating(&self) -> bool {
+        self.generating.is_some()
+    }
+
+    /// Starts playing the given puzzle
+    pub fn play(&mut self, puzzle: Board) {
+        let mut game = Game::new(puzzle);
+        game.set_auto_remove_notes(true);
+        game.start();
+        self.mode = Mode::Play(Box::new(game));
+        self.message = String::new();
+    }
+
+    /// Generates a puzzle with [App::difficulty] on a background thread, see [App::poll_generator]
+    pub fn new_game(&mut self) {
+        let (sender, receiver) = mpsc::channel();
+        let difficulty = self.difficulty;
+        thread::spawn(move || {
+            // The receiver is gone if another puzzle was requested in the meantime
+            let _ = sender.send(generate_with_difficulty(difficulty, Symmetry::Rotational));
+        });
+        self.generating = Some(receiver);
+        self.message = format!("Generating a {difficulty} puzzle...");
+    }
+
+    /// Starts playing the generated puzzle once it's ready. Returns true if it's still being generated.
+    pub fn poll_generator(&mut self) -> bool {
+        let Some(receiver) = &self.generating else {
+            return false;
+        };
+        match receiver.try_recv() {
+            Ok(puzzle) => {
+                self.generating = None;
+                self.play(puzzle);
+                false
+            }
+            Err(mpsc::TryRecvError::Empty) => true,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.generating = None;
+                self.message = "Generating the puzzle failed".to_string();
+                false
+            }
+        }
+    }
+
+    /// Starts entering a puzzle on an empty board
+    pub fn edit(&mut self) {
+        self.mode = Mode::Edit(PuzzleEditor::new());
+        self.message = "Enter the givens of the puzzle".to_string();
+    }
+
+    /// Starts playing the entered puzzle if it has a unique solution
+    pub fn play_edited(&mut self) {
+        let Mode::Edit(editor) = &self.mode else {
+            return;
+        };
+        match editor.status().map(|_| *editor.board()) {
+            Ok(puzzle) => self.play(puzzle),
+            Err(error) => self.message = error.to_string(),
+        }
+    }
+
+    /// Starts playing the first puzzle in [App::puzzle_text]
+    pub fn load_text(&mut self) {
+        let puzzle = parse_puzzles(&self.puzzle_text, None)
+            .and_then(|puzzles| puzzles.first().copied().ok_or(BoardParseError::Empty));
+        match puzzle {
+            Ok(puzzle) => self.play(puzzle),
+            Err(error) => self.message = error.to_string(),
+        }
+    }
+
+    /// Enters a value into the selected cell, or toggles the note in [App::notes_mode]. `None` clears the cell.
+    pub fn enter(&mut self, value: Option<NonZeroU8>) {
+        let (x, y) = self.selected;
+        match &mut self.mode {
+            Mode::Edit(editor) => {
+                editor.set(x, y, value);
+                self.message = match (editor.status(), editor.difficulty()) {
+                    (Ok(_), Some(difficulty)) => format!("Unique solution, {difficulty}"),
+                    (Ok(_), None) => "Unique solution".to_string(),
+                    (Err(error), _) => error.to_string(),
+                };
+            }
+            Mode::Play(game) => {
+                let result = match value {
+                    Some(value) if self.notes_mode && game.board().field(x, y).is_empty() => {
+                        game.toggle_note(x, y, value.get())
+                    }
+                    _ => game.set(x, y, value),
+                };
+                self.message = match result {
+                    Err(GameError::Given { .. }) => "This cell is given and can't be changed".to_string(),
+                    Err(error) => error.to_string(),
+                    Ok(()) if game.is_solved() => {
+                        format!("Solved, congratulations! Score: {}", game.score().total)
+                    }
+                    Ok(()) => String::new(),
+                };
+            }
+        }
+    }
+
+    /// Shows where to look for the next logical step without revealing it
+    pub fn nudge(&mut self) {
+        let Mode::Play(game) = &self.mode else {
+            return;
+        };
+        self.message = if !game.conflicts().is_empty() {
+            "Fix the conflicting values first".to_string()
+        } else {
+            match game.nudge() {
+                Some(nudge) => nudge.to_string(),
+                None => "No logical step found, you have to guess".to_string(),
+            }
+        };
+    }
+
+    /// Explains the next logical step and selects the cell it's about
+    pub fn hint(&mut self) {
+        let Mode::Play(game) = &mut self.mode else {
+            return;
+        };
+        if !game.conflicts().is_empty() {
+            self.message = "Fix the conflicting values first".to_string();
+            return;
+        }
+        self.message = match game.hint() {
+            Some(step) => {
+                if let Some(placement) = step.placement {
+                    self.selected = (placement.x, placement.y);
+                }
+                format!("{}: {}", step.technique, step.explanation())
+            }
+            None if game.board().is_filled() => "Nothing left to do".to_string(),
+            None => "No logical step found, you have to guess".to_string(),
+        };
+    }
+
+    pub fn undo(&mut self) {
+        if let Mode::Play(game) = &mut self.mode {
+            if !game.undo() {
+                self.message = "Nothing to undo".to_string();
+            }
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Mode::Play(game) = &mut self.mode {
+            if !game.redo() {
+                self.message = "Nothing to redo".to_string();
+            }
+        }
+    }
+
+    pub fn export_format(&self) -> RenderFormat {
+        self.export_format
+    }
+
+    /// Changes the format of [App::export], and the extension of the export path to match it
+    pub fn set_export_format(&mut self, format: RenderFormat) {
+        self.export_format = format;
+        let extension = match format {
+            RenderFormat::Latex => "tex",
+            _ => format.name(),
+        };
+        self.export_path = Path::new(&self.export_path).with_extension(extension).display().to_string();
+    }
+
+    /// Writes the puzzle to [App::export_path] in [App::export_format]
+    pub fn export(&mut self) {
+        let figure = Figure {
+            caption: String::new(),
+            board: *self.puzzle(),
+        };
+        let path = Path::new(&self.export_path);
+        self.message = match std::fs::write(path, render(&[figure], self.export_format)) {
+            Ok(()) => format!("Exported to {}", path.display()),
+            Err(error) => format!("Couldn't write {}: {error}", path.display()),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn puzzle() -> Board {
+        sudoku::parse_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79").unwrap()
+    }
+
+    #[test]
+    fn play() {
+        let mut app = App::new(puzzle());
+        app.enter(NonZeroU8::new(4));
+        assert_eq!("This cell is given and can't be changed", app.message());
+        app.select(2, 0);
+        app.enter(NonZeroU8::new(4));
+        assert_eq!(NonZeroU8::new(4), app.board().field(2, 0).get());
+        app.undo();
+        assert!(app.board().field(2, 0).is_empty());
+        app.notes_mode = true;
+        app.enter(NonZeroU8::new(4));
+        assert_eq!(NonZeroU8::new(4).into_iter().collect::<Vec<_>>(), app.notes(2, 0));
+        app.hint();
+        let (x, y) = app.selected();
+        assert!(app.board().field(x, y).is_empty());
+        assert!(!app.message().is_empty());
+    }
+
+    #[test]
+    fn edit() {
+        let mut app = App::new(puzzle());
+        app.edit();
+        app.enter(NonZeroU8::new(5));
+        assert_eq!("Sudoku has multiple valid solutions", app.message());
+        app.play_edited();
+        assert!(matches!(app.mode(), Mode::Edit(_)));
+
+        app.puzzle_text = sudoku::format_line(&puzzle());
+        app.load_text();
+        assert!(matches!(app.mode(), Mode::Play(_)));
+        assert_eq!(&puzzle(), app.puzzle());
+    }
+
+    #[test]
+    fn export_path_follows_format() {
+        let mut app = App::new(puzzle());
+        app.export_path = "puzzles/today.pdf".to_string();
+        app.set_export_format(RenderFormat::Latex);
+        assert_eq!("puzzles/today.tex", app.export_path);
+        app.set_export_format(RenderFormat::Svg);
+        assert_eq!("puzzles/today.svg", app.export_path);
+    }
+}
@@ -0,0 +1,37 @@
+//! A desktop app for playing sudokus, entering puzzles from elsewhere and exporting them for printing.
+//! The puzzle to start with can be given as the first argument, in any of the formats `sudoku solve` reads.
+
+use std::process::ExitCode;
+use sudoku::{generate_with_difficulty, parse_puzzles, Difficulty, Symmetry};
+
+mod app;
+mod ui;
+
+fn main() -> ExitCode {
+    let puzzle = match std::env::args().nth(1) {
+        Some(puzzle) => match parse_puzzles(&puzzle, None) {
+            Ok(puzzles) if !puzzles.is_empty() => puzzles[0],
+            Ok(_) => {
+                eprintln!("Error: No puzzle found");
+                return ExitCode::FAILURE;
+            }
+            Err(error) => {
+                eprintln!("Error: {error}");
+                return ExitCode::FAILURE;
+            }
+        },
+        None => generate_with_difficulty(Difficulty::Easy, Symmetry::Rotational),
+    };
+    let options = eframe::NativeOptions {
+        viewport: eframe::egui::ViewportBuilder::default().with_inner_size([640.0, 760.0]),
+        ..Default::default()
+    };
+    let result = eframe::run_native("Sudoku", options, Box::new(move |_| Ok(Box::new(app::App::new(puzzle)))));
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use eframe::egui::{self, Align2, Color32, Context, Event, FontId, Key, Rect, Sense, Stroke, Ui, Vec2};
+use std::num::NonZeroU8;
+use std::time::Duration;
+use sudoku::{Difficulty, RenderFormat, HEIGHT, WIDTH};
+
+use super::app::{App, Mode};
+
+const GIVEN_COLOR: Color32 = Color32::from_rgb(20, 20, 20);
+const ENTERED_COLOR: Color32 = Color32::from_rgb(30, 80, 200);
+const CONFLICT_COLOR: Color32 = Color32::from_rgb(200, 30, 30);
+const NOTE_COLOR: Color32 = Color32::from_rgb(110, 110, 110);
+const SELECTED_BACKGROUND: Color32 = Color32::from_rgb(190, 215, 250);
+const PEER_BACKGROUND: Color32 = Color32::from_rgb(232, 238, 248);
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
+        if self.poll_generator() {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        if !ctx.wants_keyboard_input() {
+            handle_keys(self, ctx);
+        }
+        egui::TopBottomPanel::top("controls").show(ctx, |ui| controls(self, ui));
+        egui::TopBottomPanel::bottom("message").show(ctx, |ui| {
+            ui.label(self.message());
+        });
+        egui::CentralPanel::default().show(ctx, |ui| board(self, ui));
+        if let Mode::Play(game) = self.mode() {
+            if !game.is_solved() {
+                // Keep the timer in the controls up to date
+                ctx.request_repaint_after(Duration::from_secs(1));
+            }
+        }
+    }
+}
+
+fn handle_keys(app: &mut App, ctx: &Context) {
+    let events = ctx.input(|input| input.events.clone());
+    for event in events {
+        let Event::Key {
+            key, pressed: true, ..
+        } = event
+        else {
+            continue;
+        };
+        match key {
+            Key::ArrowLeft => app.move_selection(-1, 0),
+            Key::ArrowRight => app.move_selection(1, 0),
+            Key::ArrowUp => app.move_selection(0, -1),
+            Key::ArrowDown => app.move_selection(0, 1),
+            Key::Backspace | Key::Delete | Key::Num0 => app.enter(None),
+            Key::N => app.notes_mode = !app.notes_mode,
+            Key::H => app.hint(),
+            Key::U => app.undo(),
+            Key::R => app.redo(),
+            _ => {
+                if let Some(value) = digit(key) {
+                    app.enter(Some(value));
+                }
+            }
+        }
+    }
+}
+
+fn digit(key: Key) -> Option<NonZeroU8> {
+    let digits = [
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+    ];
+    let index = digits.iter().position(|digit| *digit == key)?;
+    NonZeroU8::new(index as u8 + 1)
+}
+
+fn controls(app: &mut App, ui: &mut Ui) {
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_id_salt("difficulty")
+            .selected_text(app.difficulty.name())
+            .show_ui(ui, |ui| {
+                for difficulty in Difficulty::ALL {
+                    ui.selectable_value(&mut app.difficulty, difficulty, difficulty.name());
+                }
+            });
+        if ui.add_enabled(!app.is_generating(), egui::Button::new("New game")).clicked() {
+            app.new_game();
+        }
+        ui.separator();
+        let elapsed = match app.mode() {
+            Mode::Play(game) => Some(game.elapsed()),
+            Mode::Edit(_) => None,
+        };
+        match elapsed {
+            Some(elapsed) => {
+                let seconds = elapsed.as_secs();
+                ui.label(format!("{}:{:02}", seconds / 60, seconds % 60));
+                ui.checkbox(&mut app.notes_mode, "Notes (N)");
+                if ui.button("Nudge").clicked() {
+                    app.nudge();
+                }
+                if ui.button("Hint (H)").clicked() {
+                    app.hint();
+                }
+                if ui.button("Undo (U)").clicked() {
+                    app.undo();
+                }
+                if ui.button("Redo (R)").clicked() {
+                    app.redo();
+                }
+                if ui.button("Enter a puzzle").clicked() {
+                    app.edit();
+                }
+            }
+            None => {
+                if ui.button("Play this puzzle").clicked() {
+                    app.play_edited();
+                }
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut app.puzzle_text).hint_text("Paste a puzzle"));
+        if ui.button("Load").clicked() {
+            app.load_text();
+        }
+        ui.separator();
+        let mut format = app.export_format();
+        egui::ComboBox::from_id_salt("export format")
+            .selected_text(format.name())
+            .show_ui(ui, |ui| {
+                for option in RenderFormat::ALL {
+                    ui.selectable_value(&mut format, option, option.name());
+                }
+            });
+        if format != app.export_format() {
+            app.set_export_format(format);
+        }
+        ui.text_edit_singleline(&mut app.export_path);
+        if ui.button("Export").clicked() {
+            app.export();
+        }
+    });
+}
+
+fn board(app: &mut App, ui: &mut Ui) {
+    let size = ui.available_size().min_elem().max(180.0);
+    let (response, painter) = ui.allocate_painter(Vec2::splat(size), Sense::click());
+    let origin = response.rect.min;
+    let cell_size = size / WIDTH as f32;
+    let cell_rect = |x: usize, y: usize| {
+        Rect::from_min_size(
+            origin + Vec2::new(x as f32 * cell_size, y as f32 * cell_size),
+            Vec2::splat(cell_size),
+        )
+    };
+
+    if let Some(position) = response.interact_pointer_pos() {
+        let x = ((position.x - origin.x) / cell_size) as usize;
+        let y = ((position.y - origin.y) / cell_size) as usize;
+        app.select(x, y);
+    }
+
+    painter.rect_filled(response.rect, 0.0, Color32::WHITE);
+    let (selected_x, selected_y) = app.selected();
+    for x in 0..WIDTH {
+        for y in 0..HEIGHT {
+            let is_peer = x == selected_x || y == selected_y || (x / 3, y / 3) == (selected_x / 3, selected_y / 3);
+            if (x, y) == (selected_x, selected_y) {
+                painter.rect_filled(cell_rect(x, y), 0.0, SELECTED_BACKGROUND);
+            } else if is_peer {
+                painter.rect_filled(cell_rect(x, y), 0.0, PEER_BACKGROUND);
+            }
+        }
+    }
+
+    for x in 0..WIDTH {
+        for y in 0..HEIGHT {
+            let rect = cell_rect(x, y);
+            if let Some(value) = app.board().field(x, y).get() {
+                let color = if app.is_conflicting(x, y) {
+                    CONFLICT_COLOR
+                } else if app.is_given(x, y) || matches!(app.mode(), Mode::Edit(_)) {
+                    GIVEN_COLOR
+                } else {
+                    ENTERED_COLOR
+                };
+                let font = FontId::proportional(cell_size * 0.6);
+                painter.text(rect.center(), Align2::CENTER_CENTER, value.to_string(), font, color);
+            } else {
+                for value in app.notes(x, y) {
+                    let index = usize::from(value.get() - 1);
+                    let offset = Vec2::new((index % 3) as f32 + 0.5, (index / 3) as f32 + 0.5) * (cell_size / 3.0);
+                    let font = FontId::proportional(cell_size * 0.22);
+                    painter.text(rect.min + offset, Align2::CENTER_CENTER, value.to_string(), font, NOTE_COLOR);
+                }
+            }
+        }
+    }
+
+    for line in 0..=WIDTH {
+        let width = if line % 3 == 0 { 2.5 } else { 1.0 };
+        let stroke = Stroke::new(width, GIVEN_COLOR);
+        let offset = line as f32 * cell_size;
+        painter.line_segment([origin + Vec2::new(offset, 0.0), origin + Vec2::new(offset, size)], stroke);
+        painter.line_segment([origin + Vec2::new(0.0, offset), origin + Vec2::new(size, offset)], stroke);
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use thiserror::Error;
+
+use crate::constraints::Constraint;
+
+use super::{HouseId, Technique};
+
+const ENGLISH: &str = include_str!("en.txt");
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CatalogParseError {
+    #[error("Line {line}: Expected 'key = template'")]
+    MissingSeparator { line: usize },
+
+    #[error("Line {line}: Unknown key '{key}'")]
+    UnknownKey { line: usize, key: String },
+}
+
+/// The human readable strings of the hints in one language, i.e. the names of the techniques, the explanations of
+/// [Step](super::Step)s, the [Nudge](super::Nudge)s and the names of houses.
+///
+/// Each message is a template where `{name}` is replaced by an argument, e.g. `{value} can only go into {cell} in
+/// {house}`. See [Catalog::ENGLISH_SOURCE] for all keys and their arguments. Messages that a catalog doesn't have
+/// fall back to English.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    /// The English catalog in the format of [Catalog::parse], a template for translations
+    pub const ENGLISH_SOURCE: &'static str = ENGLISH;
+
+    /// The catalog that is used if no other language is asked for
+    pub fn english() -> Arc<Catalog> {
+        static CATALOG: OnceLock<Arc<Catalog>> = OnceLock::new();
+        CATALOG
+            .get_or_init(|| Arc::new(Catalog::parse_messages(ENGLISH, None).expect("English catalog is valid")))
+            .clone()
+    }
+
+    /// Parses a catalog with one `key = template` per line. Empty lines and lines starting with `#` are ignored.
+    pub fn parse(s: &str) -> Result<Self, CatalogParseError> {
+        Self::parse_messages(s, Some(&Self::english()))
+    }
+
+    /// Like [Catalog::parse], but only accepts the keys of `known` if given
+    fn parse_messages(s: &str, known: Option<&Catalog>) -> Result<Self, CatalogParseError> {
+        let mut messages = HashMap::new();
+        for (index, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, template) = line
+                .split_once('=')
+                .ok_or(CatalogParseError::MissingSeparator { line: index + 1 })?;
+            let key = key.trim();
+            if known.is_some_and(|known| !known.messages.contains_key(key)) {
+                return Err(CatalogParseError::UnknownKey {
+                    line: index + 1,
+                    key: key.to_string(),
+                });
+            }
+            messages.insert(key.to_string(), template.trim().to_string());
+        }
+        Ok(Self { messages })
+    }
+
+    /// Fills in the arguments of the message with the given key
+    pub(super) fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let english;
+        let template = match self.messages.get(key) {
+            Some(template) => template,
+            None => {
+                english = Self::english();
+                english.messages.get(key).expect("English catalog has all keys")
+            }
+        };
+        let mut result = String::with_capacity(template.len());
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let Some(end) = rest.find('}') else {
+                break;
+            };
+            let name = &rest[1..end];
+            match args.iter().find(|(arg, _)| *arg == name) {
+                Some((_, value)) => result.push_str(value),
+                None => result.push_str(&rest[..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+fn registry() -> &'static RwLock<HashMap<String, Arc<Catalog>>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, Arc<Catalog>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::from([("en".to_string(), Catalog::english())])))
+}
+
+/// Makes the catalog available as the given language, e.g. "de", replacing a catalog that was registered for it before
+pub fn register_language(language: &str, catalog: Catalog) {
+    registry()
+        .write()
+        .expect("Catalog registry isn't poisoned")
+        .insert(language.to_string(), Arc::new(catalog));
+}
+
+/// Returns the catalog registered for the language, or [Catalog::english] if there is none
+pub fn catalog(language: &str) -> Arc<Catalog> {
+    registry()
+        .read()
+        .expect("Catalog registry isn't poisoned")
+        .get(language)
+        .cloned()
+        .unwrap_or_else(Catalog::english)
+}
+
+pub(super) fn technique_key(technique: Technique) -> &'static str {
+    match technique {
+        Technique::HiddenSingle => "hidden-single",
+        Technique::NakedSingle => "naked-single",
+        Technique::GreaterThanBounds => "greater-than-bounds",
+        Technique::NonConsecutiveNeighbors => "non-consecutive-neighbors",
+        Technique::KropkiDots => "kropki-dots",
+        Technique::LockedCandidates => "locked-candidates",
+        Technique::VariantPeers => "variant-peers",
+        Technique::NakedPair => "naked-pair",
+        Technique::XWing => "x-wing",
+        Technique::Swordfish => "swordfish",
+    }
+}
+
+pub(super) fn format_house(house: HouseId, catalog: &Catalog) -> String {
+    let (key, index) = match house {
+        HouseId::Row(y) => ("house-row", y),
+        HouseId::Column(x) => ("house-column", x),
+        HouseId::Region(region) => ("house-box", region),
+        HouseId::Diagonal(0) => ("house-main-diagonal", 0),
+        HouseId::Diagonal(_) => ("house-anti-diagonal", 1),
+        HouseId::Window(window) => ("house-window", window),
+    };
+    catalog.format(key, &[("number", &(index + 1).to_string())])
+}
+
+pub(super) fn format_constraint(constraint: Constraint, catalog: &Catalog) -> String {
+    let key = match constraint {
+        Constraint::AntiKnight => "constraint-anti-knight",
+        Constraint::AntiKing => "constraint-anti-king",
+        Constraint::Diagonal => "constraint-diagonal",
+        Constraint::Windows => "constraint-windows",
+        Constraint::NonConsecutive => "constraint-non-consecutive",
+        Constraint::GreaterThan { .. } => "constraint-greater-than",
+        Constraint::Kropki { .. } => "constraint-kropki",
+        Constraint::Killer { .. } => "constraint-killer",
+    };
+    catalog.format(key, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_matches_names() {
+        let english = Catalog::english();
+        for technique in Technique::ALL {
+            assert_eq!(
+                technique.name(),
+                english.format(&format!("technique-{}", technique_key(technique)), &[])
+            );
+        }
+        assert_eq!("box 3", format_house(HouseId::Region(2), &english));
+        assert_eq!("the anti-diagonal", format_house(HouseId::Diagonal(1), &english));
+    }
+
+    #[test]
+    fn parse() {
+        let catalog = Catalog::parse("# Deutsch\n\nnudge = Schau dir {house} an\nhouse-box = Block {number}").unwrap();
+        let house = format_house(HouseId::Region(3), &catalog);
+        assert_eq!("Schau dir Block 4 an", catalog.format("nudge", &[("house", &house)]));
+        assert_eq!("row 1", format_house(HouseId::Row(0), &catalog));
+        assert_eq!(Err(CatalogParseError::MissingSeparator { line: 1 }), Catalog::parse("nudge"));
+        assert_eq!(
+            Err(CatalogParseError::UnknownKey {
+                line: 2,
+                key: "nudges".to_string()
+            }),
+            Catalog::parse("\nnudges = {house}")
+        );
+    }
+
+    #[test]
+    fn register() {
+        assert_eq!(Catalog::english(), catalog("fr"));
+        register_language("fr", Catalog::parse("house-row = ligne {number}").unwrap());
+        assert_eq!("ligne 5", format_house(HouseId::Row(4), &catalog("fr")));
+    }
+}
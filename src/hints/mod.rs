@@ -0,0 +1,578 @@
+use std::fmt::{self, Display};
+use std::num::NonZeroU8;
+
+use crate::board::{Board, HEIGHT, WIDTH};
+use crate::candidates::Candidates;
+use crate::constraints::{format_cell, Constraint, House};
+use crate::geometry::{field_in_region, region_of};
+use crate::solver::first_invalid;
+use crate::solver::possible_values::PossibleValues;
+
+mod locale;
+mod techniques;
+
+pub use locale::{catalog, register_language, Catalog, CatalogParseError};
+
+/// A [Technique] is a logical deduction rule a human solver can apply, ordered from easiest to hardest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Technique {
+    /// A value can only go into one cell of a house
+    HiddenSingle,
+
+    /// A cell has only one possible value left
+    NakedSingle,
+
+    /// The possible values of two cells connected by a greater-than sign limit each other
+    GreaterThanBounds,
+
+    /// All possible values of a cell are consecutive to a value, so its orthogonal neighbors can't contain that value
+    NonConsecutiveNeighbors,
+
+    /// A possible value of a cell next to a kropki dot has no matching possible value in the cell on the other side
+    KropkiDots,
+
+    /// All cells of a house that can contain a value also lie in a second house,
+    /// so the value can be removed from the other cells of the second house
+    LockedCandidates,
+
+    /// A cell sees, through a variant constraint like anti-knight, all the cells of a house that can contain a value
+    VariantPeers,
+
+    /// Two cells of a house can only contain the same two values, so these values can be removed from the other cells of the house
+    NakedPair,
+
+    /// A value can only go into two columns in two rows, so it can be removed from the other cells of these columns,
+    /// or the same with rows and columns swapped
+    XWing,
+
+    /// Like [Technique::XWing], but with three rows and three columns
+    Swordfish,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 10] = [
+        Technique::HiddenSingle,
+        Technique::NakedSingle,
+        Technique::GreaterThanBounds,
+        Technique::NonConsecutiveNeighbors,
+        Technique::KropkiDots,
+        Technique::LockedCandidates,
+        Technique::VariantPeers,
+        Technique::NakedPair,
+        Technique::XWing,
+        Technique::Swordfish,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::NakedSingle => "Naked Single",
+            Technique::GreaterThanBounds => "Greater-Than Bounds",
+            Technique::NonConsecutiveNeighbors => "Non-Consecutive Neighbors",
+            Technique::KropkiDots => "Kropki Dots",
+            Technique::LockedCandidates => "Locked Candidates",
+            Technique::VariantPeers => "Variant Peers",
+            Technique::NakedPair => "Naked Pair",
+            Technique::XWing => "X-Wing",
+            Technique::Swordfish => "Swordfish",
+        }
+    }
+
+    /// Like [Technique::name], but in the language of the catalog
+    pub fn localized_name(&self, catalog: &Catalog) -> String {
+        catalog.format(&format!("technique-{}", locale::technique_key(*self)), &[])
+    }
+}
+
+impl Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Identifies a house, i.e. a set of 9 cells that must contain each value exactly once.
+/// Besides rows, columns and 3x3 regions, this includes houses added by variant constraints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HouseId {
+    Row(usize),
+    Column(usize),
+    /// Regions are numbered left-to-right, then top-to-bottom
+    Region(usize),
+    Diagonal(usize),
+    Window(usize),
+}
+
+impl HouseId {
+    pub fn cells(&self) -> House {
+        let mut cells = [(0, 0); 9];
+        for (i, cell) in cells.iter_mut().enumerate() {
+            *cell = match *self {
+                HouseId::Row(y) => (i, y),
+                HouseId::Column(x) => (x, i),
+                HouseId::Region(region) => field_in_region(region, i),
+                HouseId::Diagonal(index) => Constraint::Diagonal.houses()[index][i],
+                HouseId::Window(index) => Constraint::Windows.houses()[index][i],
+            };
+        }
+        cells
+    }
+
+    /// Returns all houses of a board with the given constraints
+    pub fn all(constraints: &[Constraint]) -> Vec<HouseId> {
+        let mut houses: Vec<HouseId> = (0..HEIGHT).map(HouseId::Row).collect();
+        houses.extend((0..WIDTH).map(HouseId::Column));
+        houses.extend((0..9).map(HouseId::Region));
+        for constraint in constraints {
+            match constraint {
+                Constraint::Diagonal => houses.extend((0..2).map(HouseId::Diagonal)),
+                Constraint::Windows => houses.extend((0..4).map(HouseId::Window)),
+                Constraint::AntiKnight
+                | Constraint::AntiKing
+                | Constraint::GreaterThan { .. }
+                | Constraint::NonConsecutive
+                | Constraint::Kropki { .. }
+                | Constraint::Killer { .. } => {}
+            }
+        }
+        houses
+    }
+}
+
+impl HouseId {
+    /// Like the [Display] implementation, but in the language of the catalog
+    pub fn localized(&self, catalog: &Catalog) -> String {
+        locale::format_house(*self, catalog)
+    }
+}
+
+impl Display for HouseId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.localized(&Catalog::english()))
+    }
+}
+
+/// A value in a cell, either placed or eliminated by a [Step]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Candidate {
+    pub x: usize,
+    pub y: usize,
+    pub value: NonZeroU8,
+}
+
+impl Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", format_cell((self.x, self.y)), self.value)
+    }
+}
+
+/// A [Step] is one logical deduction, i.e. either a placement of a value or the elimination of some possible values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+
+    /// The house the deduction is about, if any
+    pub house: Option<HouseId>,
+
+    /// The cells whose possible values justify the deduction, e.g. the two cells of a naked pair
+    pub cells: Vec<(usize, usize)>,
+
+    /// The constraint the deduction is based on, for variant techniques
+    pub constraint: Option<Constraint>,
+
+    pub placement: Option<Candidate>,
+    pub eliminations: Vec<Candidate>,
+}
+
+impl Step {
+    /// A human readable explanation of the step. A single without a placement is only explained by its name.
+    pub fn explanation(&self) -> String {
+        self.localized_explanation(&Catalog::english())
+    }
+
+    /// Like [Step::explanation], but in the language of the catalog
+    pub fn localized_explanation(&self, catalog: &Catalog) -> String {
+        let cells = self.cells.iter().map(|cell| format_cell(*cell)).collect::<Vec<_>>().join(", ");
+        let eliminations = self.eliminations.iter().map(|candidate| candidate.to_string()).collect::<Vec<_>>().join(", ");
+        let house = self.house.map(|house| house.localized(catalog)).unwrap_or_default();
+        let key = format!("explanation-{}", locale::technique_key(self.technique));
+        match (self.technique, self.placement) {
+            (Technique::HiddenSingle | Technique::NakedSingle, Some(placement)) => catalog.format(
+                &key,
+                &[
+                    ("value", &placement.value.to_string()),
+                    ("cell", &format_cell((placement.x, placement.y))),
+                    ("house", &house),
+                ],
+            ),
+            // Steps can be built by hand, and a single without a placement has nothing to explain beyond its name
+            (Technique::HiddenSingle | Technique::NakedSingle, None) => self.technique.localized_name(catalog),
+            _ => {
+                let constraint = self
+                    .constraint
+                    .map(|constraint| locale::format_constraint(constraint, catalog))
+                    .unwrap_or_default();
+                catalog.format(
+                    &key,
+                    &[
+                        ("cells", &cells),
+                        ("house", &house),
+                        ("eliminations", &eliminations),
+                        ("constraint", &constraint),
+                    ],
+                )
+            }
+        }
+    }
+}
+
+/// A [Nudge] points the player to where the next logical step is, without giving the step away, see [Step::nudge]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Nudge {
+    /// The house worth examining
+    pub house: HouseId,
+
+    /// The value worth examining in the house, if the step is about a single value
+    pub value: Option<NonZeroU8>,
+}
+
+impl Nudge {
+    /// Like the [Display] implementation, but in the language of the catalog
+    pub fn localized(&self, catalog: &Catalog) -> String {
+        let house = self.house.localized(catalog);
+        match self.value {
+            Some(value) => catalog.format("nudge-value", &[("value", &value.to_string()), ("house", &house)]),
+            None => catalog.format("nudge", &[("house", &house)]),
+        }
+    }
+}
+
+impl Display for Nudge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.localized(&Catalog::english()))
+    }
+}
+
+impl Step {
+    /// A less revealing version of this step that only names the house and possibly the value to look at.
+    /// Steps that aren't about a house, like naked singles, point to the box of the cell.
+    pub fn nudge(&self) -> Nudge {
+        let cell = self
+            .placement
+            .map(|placement| (placement.x, placement.y))
+            .or_else(|| self.cells.first().copied())
+            .or_else(|| self.eliminations.first().map(|candidate| (candidate.x, candidate.y)))
+            .unwrap_or((0, 0));
+        let house = self.house.unwrap_or(HouseId::Region(region_of(cell.0, cell.1)));
+        let value = match self.technique {
+            Technique::HiddenSingle => self.placement.map(|placement| placement.value),
+            Technique::LockedCandidates | Technique::VariantPeers | Technique::XWing | Technique::Swordfish => {
+                self.eliminations.first().map(|candidate| candidate.value)
+            }
+            Technique::NakedSingle
+            | Technique::GreaterThanBounds
+            | Technique::NonConsecutiveNeighbors
+            | Technique::KropkiDots
+            | Technique::NakedPair => None,
+        };
+        Nudge { house, value }
+    }
+}
+
+/// A [LogicalSolver] solves a sudoku step by step like a human would, only using the logical techniques in [Technique].
+/// It keeps track of the possible values of each cell, so eliminations from earlier steps are taken into account.
+pub struct LogicalSolver {
+    board: Board,
+    possible_values: PossibleValues,
+    constraints: Vec<Constraint>,
+}
+
+impl LogicalSolver {
+    pub fn new(board: Board, constraints: Vec<Constraint>) -> Self {
+        let possible_values = PossibleValues::from_board(&board, &constraints);
+        Self {
+            board,
+            possible_values,
+            constraints,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// Returns the possible values for the field at (x, y), taking into account all eliminations of previously applied steps
+    pub fn possible_values_for_field(&self, x: usize, y: usize) -> impl Iterator<Item = NonZeroU8> + '_ {
+        let is_empty = self.board.field(x, y).is_empty();
+        self.possible_values
+            .possible_values_for_field(x, y)
+            .filter(move |_| is_empty)
+    }
+
+    /// The candidates of all cells, taking into account all eliminations of previously applied steps
+    pub fn candidates(&self) -> Candidates {
+        Candidates::from_possible_values(self.possible_values.clone())
+    }
+
+    /// Finds the easiest step that can be applied to the current board without applying it.
+    /// Returns `None` if the board is solved or none of the techniques make progress.
+    pub fn next_step(&self) -> Option<Step> {
+        techniques::find_step(&self.board, &self.possible_values, &self.constraints)
+    }
+
+    pub fn apply(&mut self, step: &Step) {
+        if let Some(placement) = step.placement {
+            self.board
+                .field_mut(placement.x, placement.y)
+                .set(Some(placement.value));
+            self.possible_values.remove_conflicting(
+                &self.board,
+                placement.x,
+                placement.y,
+                placement.value,
+                &self.constraints,
+            );
+        }
+        for elimination in &step.eliminations {
+            self.possible_values
+                .remove_if_set(elimination.x, elimination.y, elimination.value);
+        }
+    }
+}
+
+/// Iterating over a [LogicalSolver] finds and applies one step after the other until the board is solved or the solver is stuck.
+impl Iterator for LogicalSolver {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        let step = self.next_step()?;
+        self.apply(&step);
+        Some(step)
+    }
+}
+
+/// Returns the easiest logical step for the given board, or `None` if there is none or a constraint is invalid
+pub fn hint(board: &Board, constraints: &[Constraint]) -> Option<Step> {
+    if first_invalid(constraints).is_some() {
+        return None;
+    }
+    LogicalSolver::new(*board, constraints.to_vec()).next_step()
+}
+
+/// Like [hint], but only returns where to look, see [Step::nudge]
+pub fn nudge(board: &Board, constraints: &[Constraint]) -> Option<Nudge> {
+    hint(board, constraints).map(|step| step.nudge())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(value: u8) -> NonZeroU8 {
+        NonZeroU8::new(value).unwrap()
+    }
+
+    #[test]
+    fn house_cells() {
+        assert_eq!((4, 2), HouseId::Row(2).cells()[4]);
+        assert_eq!((2, 4), HouseId::Column(2).cells()[4]);
+        assert_eq!((3, 0), HouseId::Region(1).cells()[0]);
+        assert_eq!((5, 5), HouseId::Region(4).cells()[8]);
+        assert_eq!((4, 4), HouseId::Diagonal(1).cells()[4]);
+    }
+
+    #[test]
+    fn solve_easy() {
+        let board = Board::from_str(
+            "
+            53_ _7_ ___
+            6__ 195 ___
+            _98 ___ _6_
+
+            8__ _6_ __3
+            4__ 8_3 __1
+            7__ _2_ __6
+
+            _6_ ___ 28_
+            ___ 419 __5
+            ___ _8_ _79
+        ",
+        );
+        let mut solver = LogicalSolver::new(board, vec![]);
+        let steps: Vec<Step> = solver.by_ref().collect();
+        assert_eq!(board.num_empty(), steps.iter().filter(|step| step.placement.is_some()).count());
+        assert!(solver.board().is_filled());
+        assert_eq!(Ok(*solver.board()), crate::solve(board));
+    }
+
+    #[test]
+    fn hidden_single_explanation() {
+        let board = Board::from_str(
+            "
+            53_ _7_ ___
+            6__ 195 ___
+            _98 ___ _6_
+
+            8__ _6_ __3
+            4__ 8_3 __1
+            7__ _2_ __6
+
+            _6_ ___ 28_
+            ___ 419 __5
+            ___ _8_ _79
+        ",
+        );
+        let step = hint(&board, &[]).unwrap();
+        assert_eq!(Technique::HiddenSingle, step.technique);
+        let placement = step.placement.unwrap();
+        assert!(step.explanation().starts_with(&format!("{} can only go into", placement.value)));
+        assert_eq!(
+            Some(placement.value),
+            crate::solve(board).unwrap().field(placement.x, placement.y).get()
+        );
+    }
+
+    #[test]
+    fn explain_single_without_placement() {
+        let step = Step {
+            technique: Technique::HiddenSingle,
+            house: Some(HouseId::Row(0)),
+            cells: vec![],
+            constraint: None,
+            placement: None,
+            eliminations: vec![],
+        };
+        assert_eq!("Hidden Single", step.explanation());
+    }
+
+    #[test]
+    fn nudge_doesnt_reveal_the_cell() {
+        let board = Board::from_str(
+            "
+            53_ _7_ ___
+            6__ 195 ___
+            _98 ___ _6_
+
+            8__ _6_ __3
+            4__ 8_3 __1
+            7__ _2_ __6
+
+            _6_ ___ 28_
+            ___ 419 __5
+            ___ _8_ _79
+        ",
+        );
+        let step = hint(&board, &[]).unwrap();
+        let nudge = nudge(&board, &[]).unwrap();
+        assert_eq!(step.house, Some(nudge.house));
+        assert_eq!(step.placement.map(|placement| placement.value), nudge.value);
+        let text = nudge.to_string();
+        assert!(text.starts_with("Look at where"));
+        assert!(!text.contains(&format_cell((step.placement.unwrap().x, step.placement.unwrap().y))));
+
+        let naked_single = Step {
+            technique: Technique::NakedSingle,
+            house: None,
+            cells: vec![],
+            constraint: None,
+            placement: Some(Candidate { x: 4, y: 7, value: value(3) }),
+            eliminations: vec![],
+        };
+        assert_eq!("Look at box 8", naked_single.nudge().to_string());
+    }
+
+    #[test]
+    fn greater_than_bounds() {
+        let board = Board::new_empty();
+        let constraints = vec![
+            Constraint::GreaterThan {
+                greater: (0, 0),
+                smaller: (1, 0),
+            },
+            Constraint::GreaterThan {
+                greater: (1, 0),
+                smaller: (2, 0),
+            },
+        ];
+        let step = hint(&board, &constraints).unwrap();
+        assert_eq!(Technique::GreaterThanBounds, step.technique);
+        assert!(step.eliminations.contains(&Candidate {
+            x: 0,
+            y: 0,
+            value: value(1)
+        }));
+        assert!(step.eliminations.contains(&Candidate {
+            x: 1,
+            y: 0,
+            value: value(9)
+        }));
+    }
+
+    #[test]
+    fn non_consecutive_neighbors() {
+        let mut solver = LogicalSolver::new(Board::new_empty(), vec![Constraint::NonConsecutive]);
+        // Leave only 4 and 6 for r1c1
+        let eliminations = [1, 2, 3, 5, 7, 8, 9]
+            .into_iter()
+            .map(|v| Candidate {
+                x: 0,
+                y: 0,
+                value: value(v),
+            })
+            .collect();
+        solver.apply(&Step {
+            technique: Technique::NakedPair,
+            house: None,
+            cells: vec![],
+            constraint: None,
+            placement: None,
+            eliminations,
+        });
+        let step = solver.next_step().unwrap();
+        assert_eq!(Technique::NonConsecutiveNeighbors, step.technique);
+        assert_eq!(vec![(0, 0)], step.cells);
+        assert!(step.eliminations.contains(&Candidate {
+            x: 1,
+            y: 0,
+            value: value(5)
+        }));
+        assert!(step.eliminations.contains(&Candidate {
+            x: 0,
+            y: 1,
+            value: value(5)
+        }));
+    }
+
+    #[test]
+    fn x_wing() {
+        let mut solver = LogicalSolver::new(Board::new_empty(), vec![]);
+        // Leave only columns 3 and 7 for the 5 in rows 2 and 5
+        let eliminations = [1, 4]
+            .into_iter()
+            .flat_map(|y| [0, 1, 3, 4, 5, 7, 8].into_iter().map(move |x| Candidate { x, y, value: value(5) }))
+            .collect();
+        solver.apply(&Step {
+            technique: Technique::NakedPair,
+            house: None,
+            cells: vec![],
+            constraint: None,
+            placement: None,
+            eliminations,
+        });
+        let step = solver.next_step().unwrap();
+        assert_eq!(Technique::XWing, step.technique);
+        assert_eq!(vec![(2, 1), (6, 1), (2, 4), (6, 4)], step.cells);
+        assert_eq!(14, step.eliminations.len());
+        assert!(step.eliminations.iter().all(|candidate| candidate.value == value(5)
+            && [2, 6].contains(&candidate.x)
+            && ![1, 4].contains(&candidate.y)));
+        assert_eq!("Look at where 5 can go in row 2", step.nudge().to_string());
+    }
+}
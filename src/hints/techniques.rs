@@ -0,0 +1,394 @@
+use std::num::NonZeroU8;
+
+use itertools::Itertools;
+
+use super::{Candidate, HouseId, Step, Technique};
+use crate::bitboard::{column_bitboard, columns_of_bitboard, fields_of_bitboard, row_bitboard, rows_of_bitboard};
+use crate::board::{Board, HEIGHT, MAX_VALUE, WIDTH};
+use crate::constraints::Constraint;
+use crate::geometry::are_peers;
+use crate::solver::possible_values::PossibleValues;
+
+/// [find_step] tries all techniques from easiest to hardest and returns the first step it finds
+pub fn find_step(
+    board: &Board,
+    possible_values: &PossibleValues,
+    constraints: &[Constraint],
+) -> Option<Step> {
+    let candidates = Candidates::new(board, possible_values);
+    let houses = HouseId::all(constraints);
+    find_hidden_single(&candidates, &houses)
+        .or_else(|| find_naked_single(&candidates))
+        .or_else(|| find_greater_than_bounds(&candidates, constraints))
+        .or_else(|| find_non_consecutive_neighbors(&candidates, constraints))
+        .or_else(|| find_kropki_dots(&candidates, constraints))
+        .or_else(|| find_locked_candidates(&candidates, &houses))
+        .or_else(|| find_variant_peers(&candidates, &houses, constraints))
+        .or_else(|| find_naked_pair(&candidates, &houses))
+        .or_else(|| find_fish(possible_values, 2, Technique::XWing))
+        .or_else(|| find_fish(possible_values, 3, Technique::Swordfish))
+}
+
+/// Possible values of each cell as a bitmask where bit `value - 1` is set if the value is possible.
+/// Filled cells don't have any possible values.
+struct Candidates<'a> {
+    board: &'a Board,
+    masks: [[u16; HEIGHT]; WIDTH],
+}
+
+impl<'a> Candidates<'a> {
+    fn new(board: &'a Board, possible_values: &PossibleValues) -> Self {
+        let mut masks = [[0; HEIGHT]; WIDTH];
+        for (x, column) in masks.iter_mut().enumerate() {
+            for (y, mask) in column.iter_mut().enumerate() {
+                if board.field(x, y).is_empty() {
+                    for value in possible_values.possible_values_for_field(x, y) {
+                        *mask |= bit(value);
+                    }
+                }
+            }
+        }
+        Self { board, masks }
+    }
+
+    fn mask(&self, (x, y): (usize, usize)) -> u16 {
+        self.masks[x][y]
+    }
+
+    fn is_possible(&self, cell: (usize, usize), value: NonZeroU8) -> bool {
+        self.mask(cell) & bit(value) != 0
+    }
+
+    /// Returns the cells of the house that can contain the value, or `None` if the value is already placed in the house
+    fn positions(&self, house: HouseId, value: NonZeroU8) -> Option<Vec<(usize, usize)>> {
+        let cells = house.cells();
+        if cells
+            .iter()
+            .any(|&(x, y)| self.board.field(x, y).get() == Some(value))
+        {
+            return None;
+        }
+        Some(
+            cells
+                .into_iter()
+                .filter(|&cell| self.is_possible(cell, value))
+                .collect(),
+        )
+    }
+}
+
+fn bit(value: NonZeroU8) -> u16 {
+    1 << (value.get() - 1)
+}
+
+fn values() -> impl Iterator<Item = NonZeroU8> {
+    (1..=MAX_VALUE).map(|value| NonZeroU8::new(value).unwrap())
+}
+
+fn values_in_mask(mask: u16) -> impl Iterator<Item = NonZeroU8> {
+    values().filter(move |value| mask & bit(*value) != 0)
+}
+
+fn all_cells() -> impl Iterator<Item = (usize, usize)> {
+    (0..HEIGHT).flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+}
+
+fn candidate((x, y): (usize, usize), value: NonZeroU8) -> Candidate {
+    Candidate { x, y, value }
+}
+
+fn step(technique: Technique) -> Step {
+    Step {
+        technique,
+        house: None,
+        cells: vec![],
+        constraint: None,
+        placement: None,
+        eliminations: vec![],
+    }
+}
+
+fn find_hidden_single(candidates: &Candidates, houses: &[HouseId]) -> Option<Step> {
+    for &house in houses {
+        for value in values() {
+            if let Some(positions) = candidates.positions(house, value) {
+                if let [cell] = positions[..] {
+                    return Some(Step {
+                        house: Some(house),
+                        cells: vec![cell],
+                        placement: Some(candidate(cell, value)),
+                        ..step(Technique::HiddenSingle)
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+fn find_naked_single(candidates: &Candidates) -> Option<Step> {
+    all_cells().find_map(|cell| {
+        let mask = candidates.mask(cell);
+        (mask.count_ones() == 1).then(|| Step {
+            cells: vec![cell],
+            placement: Some(candidate(cell, values_in_mask(mask).next().unwrap())),
+            ..step(Technique::NakedSingle)
+        })
+    })
+}
+
+fn find_greater_than_bounds(candidates: &Candidates, constraints: &[Constraint]) -> Option<Step> {
+    for constraint in constraints {
+        let Constraint::GreaterThan { greater, smaller } = *constraint else {
+            continue;
+        };
+        let greater_mask = candidates.mask(greater);
+        let smaller_mask = candidates.mask(smaller);
+        if greater_mask == 0 || smaller_mask == 0 {
+            continue;
+        }
+        let max_greater = values_in_mask(greater_mask).last().unwrap();
+        let min_smaller = values_in_mask(smaller_mask).next().unwrap();
+        let mut eliminations: Vec<Candidate> = values_in_mask(greater_mask)
+            .filter(|value| *value <= min_smaller)
+            .map(|value| candidate(greater, value))
+            .collect();
+        eliminations.extend(
+            values_in_mask(smaller_mask)
+                .filter(|value| *value >= max_greater)
+                .map(|value| candidate(smaller, value)),
+        );
+        if !eliminations.is_empty() {
+            return Some(Step {
+                cells: vec![greater, smaller],
+                constraint: Some(*constraint),
+                eliminations,
+                ..step(Technique::GreaterThanBounds)
+            });
+        }
+    }
+    None
+}
+
+fn find_non_consecutive_neighbors(
+    candidates: &Candidates,
+    constraints: &[Constraint],
+) -> Option<Step> {
+    if !constraints.contains(&Constraint::NonConsecutive) {
+        return None;
+    }
+    for cell in all_cells() {
+        let mask = candidates.mask(cell);
+        if mask == 0 {
+            continue;
+        }
+        let mut eliminations = vec![];
+        for neighbor in Constraint::orthogonal_neighbors(cell.0, cell.1) {
+            for value in values_in_mask(candidates.mask(neighbor)) {
+                // Bits of the values consecutive to `value`
+                let consecutive_mask = (bit(value) << 1 | bit(value) >> 1) & 0x1FF;
+                if mask & !consecutive_mask == 0 {
+                    eliminations.push(candidate(neighbor, value));
+                }
+            }
+        }
+        if !eliminations.is_empty() {
+            return Some(Step {
+                cells: vec![cell],
+                constraint: Some(Constraint::NonConsecutive),
+                eliminations,
+                ..step(Technique::NonConsecutiveNeighbors)
+            });
+        }
+    }
+    None
+}
+
+fn find_kropki_dots(candidates: &Candidates, constraints: &[Constraint]) -> Option<Step> {
+    for constraint in constraints {
+        let Constraint::Kropki { dot, cells } = *constraint else {
+            continue;
+        };
+        let value_of = |cell: (usize, usize)| candidates.board.field(cell.0, cell.1).get();
+        // The values the other cell can have, whether it's filled or not
+        let options = |cell: (usize, usize)| value_of(cell).map_or(candidates.mask(cell), bit);
+        let mut eliminations = vec![];
+        for (cell, other) in [(cells[0], cells[1]), (cells[1], cells[0])] {
+            let other_options = options(other);
+            eliminations.extend(
+                values_in_mask(candidates.mask(cell))
+                    .filter(|value| !values_in_mask(other_options).any(|other| dot.allows(value.get(), other.get())))
+                    .map(|value| candidate(cell, value)),
+            );
+        }
+        if !eliminations.is_empty() {
+            return Some(Step {
+                cells: cells.to_vec(),
+                constraint: Some(*constraint),
+                eliminations,
+                ..step(Technique::KropkiDots)
+            });
+        }
+    }
+    None
+}
+
+fn find_locked_candidates(candidates: &Candidates, houses: &[HouseId]) -> Option<Step> {
+    for &house in houses {
+        for value in values() {
+            let Some(positions) = candidates.positions(house, value) else {
+                continue;
+            };
+            if positions.len() < 2 {
+                continue;
+            }
+            for &other_house in houses {
+                if other_house == house {
+                    continue;
+                }
+                let other_cells = other_house.cells();
+                if !positions.iter().all(|cell| other_cells.contains(cell)) {
+                    continue;
+                }
+                let eliminations: Vec<Candidate> = other_cells
+                    .into_iter()
+                    .filter(|cell| !positions.contains(cell) && candidates.is_possible(*cell, value))
+                    .map(|cell| candidate(cell, value))
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        house: Some(house),
+                        cells: positions,
+                        eliminations,
+                        ..step(Technique::LockedCandidates)
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+fn find_variant_peers(
+    candidates: &Candidates,
+    houses: &[HouseId],
+    constraints: &[Constraint],
+) -> Option<Step> {
+    let peer_constraints: Vec<Constraint> = constraints
+        .iter()
+        .copied()
+        .filter(|constraint| matches!(constraint, Constraint::AntiKnight | Constraint::AntiKing))
+        .collect();
+    if peer_constraints.is_empty() {
+        return None;
+    }
+    for &house in houses {
+        for value in values() {
+            let Some(positions) = candidates.positions(house, value) else {
+                continue;
+            };
+            if positions.is_empty() {
+                continue;
+            }
+            for constraint in &peer_constraints {
+                let sees = |cell: (usize, usize), position: (usize, usize)| {
+                    are_peers(cell, position)
+                        || constraint.peers(cell.0, cell.1).any(|peer| peer == position)
+                };
+                let eliminations: Vec<Candidate> = all_cells()
+                    .filter(|cell| !positions.contains(cell) && candidates.is_possible(*cell, value))
+                    .filter(|&cell| positions.iter().all(|&position| sees(cell, position)))
+                    // Cells that see all positions through the classic rules are found by [find_locked_candidates]
+                    .filter(|&cell| !positions.iter().all(|&position| are_peers(cell, position)))
+                    .map(|cell| candidate(cell, value))
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        house: Some(house),
+                        cells: positions,
+                        constraint: Some(*constraint),
+                        eliminations,
+                        ..step(Technique::VariantPeers)
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+fn find_naked_pair(candidates: &Candidates, houses: &[HouseId]) -> Option<Step> {
+    for &house in houses {
+        let cells = house.cells();
+        for (i, &first) in cells.iter().enumerate() {
+            let mask = candidates.mask(first);
+            if mask.count_ones() != 2 {
+                continue;
+            }
+            for &second in &cells[i + 1..] {
+                if candidates.mask(second) != mask {
+                    continue;
+                }
+                let eliminations: Vec<Candidate> = cells
+                    .iter()
+                    .filter(|&&cell| cell != first && cell != second)
+                    .flat_map(|&cell| {
+                        values_in_mask(candidates.mask(cell) & mask).map(move |value| candidate(cell, value))
+                    })
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        house: Some(house),
+                        cells: vec![first, second],
+                        eliminations,
+                        ..step(Technique::NakedPair)
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Finds `size` rows in which a value can only go into the same `size` columns, so it can be removed from the other
+/// cells of these columns, or the same with rows and columns swapped. Each row and column is a bitboard,
+/// so this only needs a few masks per value instead of looking at the cells.
+fn find_fish(possible_values: &PossibleValues, size: usize, technique: Technique) -> Option<Step> {
+    type Lines = (fn(usize) -> u128, fn(u128) -> u16, fn(usize) -> HouseId);
+    let orientations: [(Lines, Lines); 2] = [
+        ((row_bitboard, rows_of_bitboard, HouseId::Row), (column_bitboard, columns_of_bitboard, HouseId::Column)),
+        ((column_bitboard, columns_of_bitboard, HouseId::Column), (row_bitboard, rows_of_bitboard, HouseId::Row)),
+    ];
+    for value in values() {
+        let fields = possible_values.fields_with_value(value);
+        for ((base, _, base_house), (cover, cover_lines, _)) in orientations {
+            let base_lines: Vec<usize> = (0..9)
+                .filter(|&line| (2..=size).contains(&(cover_lines(fields & base(line)).count_ones() as usize)))
+                .collect();
+            for lines in base_lines.into_iter().combinations(size) {
+                let base_fields = lines.iter().fold(0, |bitboard, &line| bitboard | base(line));
+                let covered = cover_lines(fields & base_fields);
+                if covered.count_ones() as usize != size {
+                    continue;
+                }
+                let cover_fields = (0..9)
+                    .filter(|line| covered & (1 << line) != 0)
+                    .fold(0, |bitboard, line| bitboard | cover(line));
+                let eliminations = fields & cover_fields & !base_fields;
+                if eliminations != 0 {
+                    let mut cells: Vec<(usize, usize)> = fields_of_bitboard(fields & base_fields).collect();
+                    cells.sort_by_key(|&(x, y)| (y, x));
+                    return Some(Step {
+                        house: Some(base_house(lines[0])),
+                        cells,
+                        eliminations: fields_of_bitboard(eliminations)
+                            .map(|cell| candidate(cell, value))
+                            .collect(),
+                        ..step(technique)
+                    });
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,135 @@
+//! Conversions between [Board] and the plain representations other sudoku crates use, e.g. the 81 bytes of the
+//! `sudoku` crate's `Sudoku::from_bytes` and `Sudoku::to_bytes`, or a 9x9 array. This makes it possible to migrate
+//! incrementally or to cross-check solutions, without this crate depending on them.
+//! In all of them, values are stored row by row and 0 is an empty field.
+//!
+//! This module is limited to those representations. There are no `From`/`TryFrom` impls for the types of specific
+//! crates, like the `sudoku` crate's `Sudoku`, and no features that pull those crates in as dependencies, so nothing
+//! here is behind a feature. Converting through the bytes is a one-liner on the caller's side, e.g. for the `sudoku`
+//! crate:
+//! ```ignore
+//! let theirs = Sudoku::from_bytes(<[u8; 81]>::from(&board))?;
+//! let ours = Board::try_from(theirs.to_bytes())?;
+//! ```
+
+use core::num::NonZeroU8;
+use thiserror::Error;
+
+use crate::board::{Board, HEIGHT, MAX_VALUE, NUM_FIELDS, WIDTH};
+
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BytesError {
+    #[error("Expected {NUM_FIELDS} values but found {0}")]
+    WrongLength(usize),
+
+    #[error("Invalid value {value} at r{}c{}, values must be between 0 and {MAX_VALUE}", .y + 1, .x + 1)]
+    InvalidValue { x: usize, y: usize, value: u8 },
+}
+
+impl TryFrom<&[u8]> for Board {
+    type Error = BytesError;
+
+    fn try_from(values: &[u8]) -> Result<Self, Self::Error> {
+        if values.len() != NUM_FIELDS {
+            return Err(BytesError::WrongLength(values.len()));
+        }
+        let mut board = Board::new_empty();
+        for (index, &value) in values.iter().enumerate() {
+            let (x, y) = (index % WIDTH, index / WIDTH);
+            if value > MAX_VALUE {
+                return Err(BytesError::InvalidValue { x, y, value });
+            }
+            board.field_mut(x, y).set(NonZeroU8::new(value));
+        }
+        Ok(board)
+    }
+}
+
+impl TryFrom<[u8; NUM_FIELDS]> for Board {
+    type Error = BytesError;
+
+    fn try_from(values: [u8; NUM_FIELDS]) -> Result<Self, Self::Error> {
+        Board::try_from(&values[..])
+    }
+}
+
+impl TryFrom<[[u8; WIDTH]; HEIGHT]> for Board {
+    type Error = BytesError;
+
+    fn try_from(rows: [[u8; WIDTH]; HEIGHT]) -> Result<Self, Self::Error> {
+        Board::try_from(rows.as_flattened())
+    }
+}
+
+impl From<&Board> for [u8; NUM_FIELDS] {
+    fn from(board: &Board) -> Self {
+        let mut values = [0; NUM_FIELDS];
+        for (index, value) in values.iter_mut().enumerate() {
+            *value = board.field(index % WIDTH, index / WIDTH).get().map_or(0, NonZeroU8::get);
+        }
+        values
+    }
+}
+
+impl From<Board> for [u8; NUM_FIELDS] {
+    fn from(board: Board) -> Self {
+        Self::from(&board)
+    }
+}
+
+impl From<&Board> for [[u8; WIDTH]; HEIGHT] {
+    fn from(board: &Board) -> Self {
+        let mut rows = [[0; WIDTH]; HEIGHT];
+        for (y, row) in rows.iter_mut().enumerate() {
+            for (x, value) in row.iter_mut().enumerate() {
+                *value = board.field(x, y).get().map_or(0, NonZeroU8::get);
+            }
+        }
+        rows
+    }
+}
+
+impl From<Board> for [[u8; WIDTH]; HEIGHT] {
+    fn from(board: Board) -> Self {
+        Self::from(&board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "
+        53_ _7_ ___
+        6__ 195 ___
+        _98 ___ _6_
+
+        8__ _6_ __3
+        4__ 8_3 __1
+        7__ _2_ __6
+
+        _6_ ___ 28_
+        ___ 419 __5
+        ___ _8_ _79
+    ";
+
+    #[test]
+    fn bytes() {
+        let board = Board::from_str(PUZZLE);
+        let bytes: [u8; NUM_FIELDS] = board.into();
+        assert_eq!([5, 3, 0, 0, 7, 0, 0, 0, 0], bytes[..9]);
+        assert_eq!(Ok(board), Board::try_from(bytes));
+
+        let rows: [[u8; WIDTH]; HEIGHT] = (&board).into();
+        assert_eq!([0, 0, 0, 0, 8, 0, 0, 7, 9], rows[8]);
+        assert_eq!(Ok(board), Board::try_from(rows));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(BytesError::WrongLength(80)), Board::try_from(&[0; 80][..]));
+        let mut bytes = [0; NUM_FIELDS];
+        bytes[10] = 10;
+        assert_eq!(Err(BytesError::InvalidValue { x: 1, y: 1, value: 10 }), Board::try_from(bytes));
+    }
+}
@@ -1,8 +1,141 @@
+//! Without the default `std` feature, the library only needs `alloc`. This leaves the board, the candidates,
+//! the solver and the puzzle formats, e.g. to solve sudokus on embedded devices.
+//! Everything else, like generating and rating sudokus, needs `std`.
+//! Generating sudokus also needs the default `rand` feature.
+//! Without the `alloc` feature, only the board and [solve_bounded] are left.
+//!
+//! # Reproducibility
+//!
+//! The seeded generators, like [generate_with_difficulty_seeded], generate the same puzzle for the same seed on all
+//! platforms, including WASM. [daily_puzzle], races and [regenerate] rely on this. The mapping from seeds to puzzles
+//! only changes in a breaking release of this crate, i.e. a new minor version before 1.0, and never in a patch
+//! release. A [Manifest] records the crate version, so older batches can still be reproduced with that version.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod bitboard;
 mod board;
+mod geometry;
+mod constraints;
+#[cfg(feature = "alloc")]
 mod solver;
 mod utils;
+mod bounded;
+mod interop;
+#[cfg(all(feature = "std", feature = "rand"))]
 mod generator;
+#[cfg(feature = "std")]
+mod hints;
+#[cfg(feature = "std")]
+mod validation;
+#[cfg(feature = "std")]
+mod rating;
+#[cfg(feature = "alloc")]
+mod formats;
+#[cfg(feature = "std")]
+mod canonical;
+#[cfg(feature = "std")]
+mod db;
+#[cfg(feature = "pack")]
+mod pack;
+#[cfg(feature = "std")]
+mod render;
+#[cfg(all(feature = "std", feature = "rand"))]
+mod pool;
+#[cfg(all(feature = "std", feature = "rand"))]
+mod manifest;
+#[cfg(feature = "std")]
+mod game;
+#[cfg(all(feature = "std", feature = "rand"))]
+mod daily;
+#[cfg(feature = "std")]
+mod editor;
+#[cfg(feature = "alloc")]
+mod candidates;
+#[cfg(feature = "corpus")]
+pub mod corpus;
+#[cfg(feature = "std")]
+pub mod checkpoint;
+#[cfg(all(feature = "std", feature = "rand"))]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod tutorials;
+#[cfg(feature = "std")]
+pub mod verify;
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub mod fuzz;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 
-pub use board::Board;
-pub use solver::{generate_solved, solve};
-pub use generator::{generate, generate_max_empty};
\ No newline at end of file
+pub use board::{Board, HEIGHT, NUM_FIELDS, WIDTH};
+pub use constraints::{Cage, Constraint, House, KropkiDot};
+#[cfg(feature = "alloc")]
+pub use constraints::{format_constraints, parse_constraints, ConstraintParseError};
+pub use bounded::{solve_bounded, BoundedSolverError};
+pub use interop::BytesError;
+#[cfg(feature = "alloc")]
+pub use solver::{
+    check_constraints, count_solutions, solutions, solutions_with_constraints, solve, solve_with_backend,
+    solve_with_cancel, solve_with_config, solve_with_constraints, Backend, Branching, ConstraintsError, Generator,
+    GuessInOrder, Guesser, PossibleValues, SearchConfig, SolveStats, Solutions, Solver, SolverContext, SolverError,
+    ValueOrder,
+};
+#[cfg(all(feature = "std", feature = "parallel"))]
+pub use solver::{count_solutions_parallel, solutions_parallel, ParallelSolutions};
+#[cfg(all(feature = "std", feature = "rand"))]
+pub use solver::{generate_solved, generate_solved_with_constraints};
+#[cfg(feature = "rand")]
+pub use solver::{generate_solved_with_rng, GuessRandomPossibleValue};
+#[cfg(all(feature = "std", feature = "rand"))]
+pub use generator::{
+    generate, generate_greater_than, generate_max_empty, generate_max_empty_with_constraints,
+    generate_symmetric, generate_symmetric_seeded, generate_with_constraints, generate_with_difficulty,
+    generate_with_difficulty_cancellable, generate_with_difficulty_seeded, generate_with_solution_count,
+    generate_with_solution_count_seeded, Symmetry,
+};
+#[cfg(feature = "std")]
+pub use hints::{
+    catalog, hint, nudge, register_language, Candidate, Catalog, CatalogParseError, HouseId, LogicalSolver, Nudge, Step,
+    Technique,
+};
+#[cfg(feature = "std")]
+pub use validation::{validate_with, Rule, ValidationReport, Violation};
+#[cfg(feature = "std")]
+pub use rating::{
+    analyze_cells, analyze_cells_with_constraints, baseline_set, calibrate, calibrate_with, parse_calibration_set, rate,
+    rate_batch, rate_batch_with, rate_with_cancel, rate_with_constraints, regression_check, CalibrationParseError, CalibrationPuzzle,
+    CalibrationReport, CellAnalysis, Complexity, Difficulty, Mismatch, RatingRegression, TechniqueReport,
+};
+#[cfg(feature = "alloc")]
+pub use formats::{format_grid, format_line, parse_grid, parse_line, parse_puzzles, BoardParseError, PuzzleFormat};
+#[cfg(feature = "std")]
+pub use canonical::{canonical_form, is_equivalent, Fingerprint};
+#[cfg(feature = "std")]
+pub use db::{PuzzleDb, Query};
+#[cfg(feature = "pack")]
+pub use pack::{PackError, PuzzlePack};
+#[cfg(feature = "std")]
+pub use render::{render, Figure, RenderFormat};
+#[cfg(all(feature = "std", feature = "rand"))]
+pub use pool::{PuzzlePool, PuzzleStore};
+#[cfg(all(feature = "std", feature = "rand"))]
+pub use manifest::{regenerate, GeneratorConfig, Manifest, ManifestEntry, RegenerateError};
+#[cfg(feature = "std")]
+pub use game::{
+    write_replay, Analytics, Annotation, CellTime, Change, DifficultyScoring, DifficultyStats, ErrorCheck, Game,
+    GameError, GameEvent, GameResult, HouseStats, Justification, Legality, Move, Notes, Progress, ReplayError,
+    ReplayReader, Score, ScoringConfig, Session, Timer,
+};
+#[cfg(all(feature = "std", feature = "rand"))]
+pub use game::{Race, RaceEntry, RaceError, RaceResult};
+#[cfg(all(feature = "std", feature = "rand"))]
+pub use daily::{daily_puzzle, daily_seed, Date};
+#[cfg(feature = "std")]
+pub use editor::PuzzleEditor;
+#[cfg(feature = "alloc")]
+pub use candidates::Candidates;
@@ -1,7 +1,26 @@
-use sudoku::{generate, generate_max_empty};
+use clap::Parser;
+use std::process::ExitCode;
 
-fn main() {
-    let board = generate_max_empty();
-    println!("{:?}", board);
-    println!("Number of gaps: {}", board.num_empty());
+mod cli;
+
+fn main() -> ExitCode {
+    let args = match cli::Cli::try_parse() {
+        Ok(args) => args,
+        Err(err) => {
+            // Prints help and version to stdout, usage errors to stderr
+            let _ = err.print();
+            return if err.use_stderr() {
+                ExitCode::from(cli::USAGE_EXIT_CODE)
+            } else {
+                ExitCode::SUCCESS
+            };
+        }
+    };
+    match cli::run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::from(err.exit_code())
+        }
+    }
 }
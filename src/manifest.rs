@@ -0,0 +1,191 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::board::Board;
+use crate::generator::{generate_symmetric_seeded, generate_with_difficulty_seeded, Symmetry};
+use crate::rating::Difficulty;
+use crate::utils::SplitMix64;
+
+/// The settings that, together with a seed, determine a generated puzzle
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneratorConfig {
+    /// Only generate puzzles of this difficulty
+    pub difficulty: Option<Difficulty>,
+
+    pub symmetry: Symmetry,
+}
+
+impl GeneratorConfig {
+    /// Generates a puzzle. The same seed always gives the same puzzle with the same version of this crate.
+    pub fn generate(&self, seed: u64) -> Board {
+        match self.difficulty {
+            Some(difficulty) => generate_with_difficulty_seeded(difficulty, self.symmetry, seed),
+            None => generate_symmetric_seeded(self.symmetry, seed),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ManifestEntry {
+    pub seed: u64,
+    pub puzzle: Board,
+}
+
+/// Records how a batch of puzzles was generated, so that the batch can be reproduced with [regenerate], e.g. to prove
+/// where a published puzzle came from. With the `serde` feature, it can be stored next to the puzzles, e.g. as JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Manifest {
+    /// The version of this crate that generated the puzzles. Other versions may generate different puzzles.
+    pub crate_version: String,
+
+    pub config: GeneratorConfig,
+
+    /// The seed of the batch. The seed of the puzzle at index `i` is a hash of `seed` and `i`, so batches with nearby
+    /// seeds, e.g. 1240 and 1241, don't share puzzles. Each [ManifestEntry] records the seed of its puzzle.
+    pub seed: u64,
+
+    /// Seconds since the Unix epoch when the generation started
+    pub started_at: u64,
+
+    /// Seconds since the Unix epoch when the generation finished
+    pub finished_at: u64,
+
+    pub puzzles: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// Generates `count` puzzles and records how. Each puzzle gets its own seed, so the puzzles don't depend on how
+    /// the work is distributed across threads.
+    pub fn generate(config: GeneratorConfig, seed: u64, count: usize) -> Self {
+        Self::generate_range(config, seed, 0..count)
+    }
+
+    /// Like [Manifest::generate], but only generates the puzzles at the given indices of the batch, e.g. to continue
+    /// a batch whose first puzzles were taken from somewhere else
+    pub fn generate_range(config: GeneratorConfig, seed: u64, indices: Range<usize>) -> Self {
+        let started_at = unix_time();
+        let puzzles = generate_entries(config, indices.map(|index| puzzle_seed(seed, index as u64)).collect());
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            config,
+            seed,
+            started_at,
+            finished_at: unix_time(),
+            puzzles,
+        }
+    }
+
+    pub fn boards(&self) -> impl Iterator<Item = Board> + '_ {
+        self.puzzles.iter().map(|entry| entry.puzzle)
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum RegenerateError {
+    #[error(
+        "Puzzle {index} doesn't match what seed {seed} generates. The manifest was written by version {crate_version} of the crate, this is version {}.",
+        env!("CARGO_PKG_VERSION")
+    )]
+    Mismatch {
+        index: usize,
+        seed: u64,
+        crate_version: String,
+    },
+}
+
+/// Generates the puzzles of the manifest again from their seeds and checks that they match the recorded puzzles
+pub fn regenerate(manifest: &Manifest) -> Result<Vec<Board>, RegenerateError> {
+    let seeds = manifest.puzzles.iter().map(|entry| entry.seed).collect();
+    let entries = generate_entries(manifest.config, seeds);
+    for (index, (recorded, regenerated)) in manifest.puzzles.iter().zip(&entries).enumerate() {
+        if recorded != regenerated {
+            return Err(RegenerateError::Mismatch {
+                index,
+                seed: recorded.seed,
+                crate_version: manifest.crate_version.clone(),
+            });
+        }
+    }
+    Ok(entries.into_iter().map(|entry| entry.puzzle).collect())
+}
+
+fn generate_entries(config: GeneratorConfig, seeds: Vec<u64>) -> Vec<ManifestEntry> {
+    #[cfg(feature = "parallel")]
+    let seeds = seeds.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let seeds = seeds.into_iter();
+    seeds
+        .map(|seed| ManifestEntry {
+            seed,
+            puzzle: config.generate(seed),
+        })
+        .collect()
+}
+
+/// Hashes the seed of the batch and the index of the puzzle with SplitMix64. This must never change, otherwise
+/// [Manifest::generate] generates different puzzles for the same seed.
+fn puzzle_seed(seed: u64, index: u64) -> u64 {
+    SplitMix64(SplitMix64(seed).next() ^ index).next()
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regenerate_manifest() {
+        let config = GeneratorConfig {
+            difficulty: None,
+            symmetry: Symmetry::Rotational,
+        };
+        let mut manifest = Manifest::generate(config, 1240, 3);
+        let seeds: Vec<u64> = (0..3).map(|index| puzzle_seed(1240, index)).collect();
+        assert_eq!(seeds, manifest.puzzles.iter().map(|entry| entry.seed).collect::<Vec<_>>());
+        assert_eq!(generate_symmetric_seeded(Symmetry::Rotational, seeds[1]), manifest.puzzles[1].puzzle);
+        assert_eq!(Ok(manifest.boards().collect()), regenerate(&manifest));
+        assert_eq!(manifest.puzzles[1..], Manifest::generate_range(config, 1240, 1..3).puzzles);
+
+        manifest.puzzles[2].puzzle = Board::new_empty();
+        assert_eq!(
+            Err(RegenerateError::Mismatch {
+                index: 2,
+                seed: seeds[2],
+                crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            }),
+            regenerate(&manifest)
+        );
+    }
+
+    #[test]
+    fn puzzle_seeds_are_stable() {
+        assert_eq!([0xe721_c214_70d7_6163, 0x8d49_b111_27a6_2a5d], [puzzle_seed(1240, 0), puzzle_seed(1240, 1)]);
+    }
+
+    #[test]
+    fn adjacent_batches_share_no_puzzles() {
+        let config = GeneratorConfig {
+            difficulty: None,
+            symmetry: Symmetry::None,
+        };
+        let batches: Vec<Manifest> = [1240, 1241].map(|seed| Manifest::generate(config, seed, 4)).into();
+        let [first, second] = &batches[..] else { unreachable!() };
+        for entry in &first.puzzles {
+            assert!(!second.puzzles.iter().any(|other| other.seed == entry.seed || other.puzzle == entry.puzzle));
+        }
+
+        // Only the seeds matter, so check far more of them than there are puzzles above
+        let seeds = |batch: u64| (0..10_000).map(move |index| puzzle_seed(batch, index));
+        let first: std::collections::HashSet<u64> = seeds(1240).collect();
+        assert!(seeds(1241).chain(seeds(1239)).all(|seed| !first.contains(&seed)));
+    }
+}
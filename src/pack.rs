@@ -0,0 +1,295 @@
+use memmap2::Mmap;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::board::{Board, MAX_VALUE, NUM_FIELDS};
+use crate::constraints::{format_constraints, parse_constraints, Constraint, ConstraintParseError};
+
+const MAGIC: &[u8; 8] = b"SUDOKUPK";
+const VERSION: u32 = 2;
+const HEADER_SIZE: usize = 16;
+const RECORD_SIZE: usize = NUM_FIELDS.div_ceil(2);
+
+#[derive(Error, Debug)]
+pub enum PackError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error("Not a puzzle pack")]
+    InvalidHeader,
+
+    #[error("Unsupported puzzle pack version {0}, expected {VERSION}")]
+    UnsupportedVersion(u32),
+
+    #[error("Expected at least {expected} bytes for the puzzles in the header but found {actual}")]
+    WrongLength { expected: usize, actual: usize },
+
+    #[error("Puzzle {index} has a value above {MAX_VALUE}")]
+    InvalidValue { index: usize },
+
+    #[error("The constraints of puzzle {index} are out of bounds")]
+    InvalidOffset { index: usize },
+
+    #[error("Puzzle {index}: {error}")]
+    InvalidConstraints { index: usize, error: ConstraintParseError },
+}
+
+/// A compact binary file for large puzzle libraries, e.g. shipped with an offline app. The file is memory mapped,
+/// so opening it is cheap and puzzles are only decoded when they are read.
+/// [PuzzleDb::open_pack](crate::PuzzleDb::open_pack) queries a pack.
+///
+/// A pack starts with a 16 byte header: the magic bytes `SUDOKUPK`, the format version and the number of puzzles,
+/// both as little endian `u32`. Each puzzle follows as 41 bytes with one nibble per field, row by row, the low
+/// nibble first. 0 is an empty field.
+///
+/// The constraints of variant puzzles follow the puzzles, so classic puzzles can be read without looking at them:
+/// one little endian `u32` offset per puzzle plus one for the end, and then the constraints of each puzzle in the
+/// text format of [parse_constraints](crate::parse_constraints), from its offset to the next one. Classic puzzles
+/// have no constraints, i.e. an empty text.
+pub struct PuzzlePack {
+    mmap: Mmap,
+    len: usize,
+}
+
+impl PuzzlePack {
+    /// Opens the pack and checks that all of its puzzles are valid, without decoding them
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PackError> {
+        let file = File::open(path)?;
+        // Safety: the map is read-only. Like any memory mapped file, the pack must not be modified while it is open.
+        let mmap = unsafe { Mmap::map(&file)? };
+        let Some((header, records)) = mmap.split_first_chunk::<HEADER_SIZE>() else {
+            return Err(PackError::InvalidHeader);
+        };
+        if &header[..8] != MAGIC {
+            return Err(PackError::InvalidHeader);
+        }
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if version != VERSION {
+            return Err(PackError::UnsupportedVersion(version));
+        }
+        let len = u32::from_le_bytes(header[12..16].try_into().unwrap()) as usize;
+        let expected = len * RECORD_SIZE + (len + 1) * 4;
+        if records.len() < expected {
+            return Err(PackError::WrongLength {
+                expected,
+                actual: records.len(),
+            });
+        }
+        let pack = Self { mmap, len };
+        for index in 0..len {
+            if !is_valid(pack.record(index).unwrap()) {
+                return Err(PackError::InvalidValue { index });
+            }
+            let constraints = pack.constraints_text(index).ok_or(PackError::InvalidOffset { index })?;
+            parse_constraints(constraints).map_err(|error| PackError::InvalidConstraints { index, error })?;
+        }
+        Ok(pack)
+    }
+
+    /// Writes the puzzles to a new pack, replacing the file if it exists
+    pub fn write(path: impl AsRef<Path>, puzzles: &[Board]) -> io::Result<()> {
+        Self::write_variants(path, puzzles.iter().map(|&puzzle| (puzzle, &[][..])))
+    }
+
+    /// Like [PuzzlePack::write], but for variant puzzles with their constraints
+    pub fn write_variants<'a>(
+        path: impl AsRef<Path>,
+        puzzles: impl IntoIterator<Item = (Board, &'a [Constraint])>,
+    ) -> io::Result<()> {
+        let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "Too many puzzles for a pack");
+        let mut records = vec![];
+        let mut offsets = vec![0];
+        let mut constraints = String::new();
+        for (puzzle, puzzle_constraints) in puzzles {
+            records.extend(encode(&puzzle));
+            constraints.push_str(&format_constraints(puzzle_constraints));
+            offsets.push(u32::try_from(constraints.len()).map_err(|_| too_large())?);
+        }
+        let len = u32::try_from(offsets.len() - 1).map_err(|_| too_large())?;
+        let mut content = Vec::with_capacity(HEADER_SIZE + records.len() + offsets.len() * 4 + constraints.len());
+        content.extend_from_slice(MAGIC);
+        content.extend_from_slice(&VERSION.to_le_bytes());
+        content.extend_from_slice(&len.to_le_bytes());
+        content.extend(records);
+        content.extend(offsets.into_iter().flat_map(u32::to_le_bytes));
+        content.extend_from_slice(constraints.as_bytes());
+        fs::write(path, content)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<Board> {
+        self.record(index).map(decode)
+    }
+
+    /// The constraints of a variant puzzle, or an empty list for a classic one
+    pub fn constraints(&self, index: usize) -> Option<Vec<Constraint>> {
+        let text = self.constraints_text(index)?;
+        Some(parse_constraints(text).expect("Constraints are validated when the pack is opened"))
+    }
+
+    /// The number of given fields of a puzzle, without decoding it
+    pub fn num_filled(&self, index: usize) -> Option<usize> {
+        self.record(index).map(|record| values(record).filter(|&value| value != 0).count())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Board> + '_ {
+        (0..self.len).map(|index| self.get(index).unwrap())
+    }
+
+    fn record(&self, index: usize) -> Option<&[u8]> {
+        if index >= self.len {
+            return None;
+        }
+        let start = HEADER_SIZE + index * RECORD_SIZE;
+        Some(&self.mmap[start..start + RECORD_SIZE])
+    }
+
+    fn constraints_text(&self, index: usize) -> Option<&str> {
+        if index >= self.len {
+            return None;
+        }
+        let offsets = HEADER_SIZE + self.len * RECORD_SIZE;
+        let offset = |index: usize| {
+            let start = offsets + index * 4;
+            u32::from_le_bytes(self.mmap[start..start + 4].try_into().unwrap()) as usize
+        };
+        let text = &self.mmap[offsets + (self.len + 1) * 4..];
+        let bytes = text.get(offset(index)..offset(index + 1))?;
+        core::str::from_utf8(bytes).ok()
+    }
+}
+
+/// The values of the fields of a record, row by row
+fn values(record: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    record.iter().flat_map(|byte| [byte & 0xf, byte >> 4]).take(NUM_FIELDS)
+}
+
+fn is_valid(record: &[u8]) -> bool {
+    values(record).all(|value| value <= MAX_VALUE) && record[RECORD_SIZE - 1] >> 4 == 0
+}
+
+fn encode(puzzle: &Board) -> [u8; RECORD_SIZE] {
+    let values: [u8; NUM_FIELDS] = puzzle.into();
+    let mut record = [0; RECORD_SIZE];
+    for (index, value) in values.into_iter().enumerate() {
+        record[index / 2] |= value << (4 * (index % 2));
+    }
+    record
+}
+
+fn decode(record: &[u8]) -> Board {
+    let values: Vec<u8> = values(record).collect();
+    Board::try_from(&values[..]).expect("Records are validated when the pack is opened")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraints::{Cage, KropkiDot};
+    use crate::formats::parse_line;
+
+    const PUZZLES: [&str; 3] = [
+        ".3.1..2..9..8.74........9.6...56.....123....4........1..4....5.79......2.2..45..9",
+        "62.4......1.7..9..3..5....1.......3....2.174...6.3..5....98.5..........8.75......",
+        ".1.......4..8.2.1.......849....2.69.1.9.743.......6.....6...7..7.4.8....8.......5",
+    ];
+
+    fn puzzles() -> Vec<Board> {
+        PUZZLES.iter().map(|puzzle| parse_line(puzzle).unwrap()).collect()
+    }
+
+    fn temporary_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sudoku-pack-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn roundtrip() {
+        let puzzles = puzzles();
+        let path = temporary_path("roundtrip");
+        PuzzlePack::write(&path, &puzzles).unwrap();
+        let pack = PuzzlePack::open(&path).unwrap();
+        assert_eq!(puzzles.len(), pack.len());
+        assert_eq!(puzzles, pack.iter().collect::<Vec<_>>());
+        assert_eq!(Some(puzzles[1].num_filled()), pack.num_filled(1));
+        assert_eq!(Some(vec![]), pack.constraints(1));
+        assert_eq!(None, pack.get(puzzles.len()));
+        drop(pack);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn variants() {
+        let anti_knight = [Constraint::AntiKnight];
+        let kropki = [
+            Constraint::Diagonal,
+            Constraint::Kropki {
+                dot: KropkiDot::Black,
+                cells: [(0, 0), (1, 0)],
+            },
+            Constraint::Killer {
+                cage: Cage::new([(4, 4), (4, 5), (5, 5)]).unwrap(),
+                sum: 12,
+            },
+        ];
+        let puzzles = [
+            (Board::new_empty(), &anti_knight[..]),
+            (puzzles()[0], &[][..]),
+            (Board::new_empty(), &kropki[..]),
+        ];
+        let path = temporary_path("variants");
+        PuzzlePack::write_variants(&path, puzzles).unwrap();
+        let pack = PuzzlePack::open(&path).unwrap();
+        for (index, (puzzle, constraints)) in puzzles.into_iter().enumerate() {
+            assert_eq!(Some(puzzle), pack.get(index));
+            assert_eq!(Some(constraints.to_vec()), pack.constraints(index));
+        }
+        drop(pack);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid() {
+        let path = temporary_path("invalid");
+        PuzzlePack::write(&path, &[Board::new_empty()]).unwrap();
+        let mut content = fs::read(&path).unwrap();
+
+        fs::write(&path, &content[..content.len() - 1]).unwrap();
+        assert!(matches!(
+            PuzzlePack::open(&path),
+            Err(PackError::WrongLength {
+                expected: 49,
+                actual: 48
+            })
+        ));
+
+        let mut with_constraints = content.clone();
+        with_constraints.extend_from_slice(b"anti-queen\n");
+        with_constraints[HEADER_SIZE + RECORD_SIZE + 4] = 11;
+        fs::write(&path, &with_constraints).unwrap();
+        assert!(matches!(PuzzlePack::open(&path), Err(PackError::InvalidConstraints { index: 0, .. })));
+        with_constraints[HEADER_SIZE + RECORD_SIZE + 4] = 12;
+        fs::write(&path, &with_constraints).unwrap();
+        assert!(matches!(PuzzlePack::open(&path), Err(PackError::InvalidOffset { index: 0 })));
+
+        content[HEADER_SIZE + 3] = 0xa0;
+        fs::write(&path, &content).unwrap();
+        assert!(matches!(PuzzlePack::open(&path), Err(PackError::InvalidValue { index: 0 })));
+
+        content[8] = 1;
+        fs::write(&path, &content).unwrap();
+        assert!(matches!(PuzzlePack::open(&path), Err(PackError::UnsupportedVersion(1))));
+
+        fs::write(&path, b"not a pack").unwrap();
+        assert!(matches!(PuzzlePack::open(&path), Err(PackError::InvalidHeader)));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,303 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::board::Board;
+use crate::formats::{format_line, parse_line};
+use crate::generator::{generate_with_difficulty, Symmetry};
+use crate::rating::Difficulty;
+
+/// A [PuzzleStore] keeps pre-generated puzzles on disk, so that a puzzle of a given difficulty can be handed out
+/// instantly instead of generating it on demand. Hard puzzles can take a long time to generate.
+///
+/// Each puzzle is stored in its own file in a subdirectory per difficulty, e.g. `hard/1700000000-42-7.txt`.
+/// Adding and taking puzzles only use atomic renames, so several processes can share a store,
+/// e.g. a daemon refilling it and a CLI taking puzzles from it.
+pub struct PuzzleStore {
+    directory: PathBuf,
+}
+
+static NEXT_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+impl PuzzleStore {
+    /// Opens the store in the given directory, creating it if it doesn't exist
+    pub fn open(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let store = Self {
+            directory: directory.into(),
+        };
+        for difficulty in Difficulty::ALL {
+            fs::create_dir_all(store.difficulty_directory(difficulty))?;
+        }
+        Ok(store)
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn difficulty_directory(&self, difficulty: Difficulty) -> PathBuf {
+        self.directory.join(difficulty.name())
+    }
+
+    /// Returns the puzzle files of the difficulty, skipping temporary files of other processes
+    fn puzzle_files(&self, difficulty: Difficulty) -> io::Result<Vec<PathBuf>> {
+        let mut files = vec![];
+        for entry in fs::read_dir(self.difficulty_directory(difficulty))? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "txt") {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    pub fn count(&self, difficulty: Difficulty) -> io::Result<usize> {
+        Ok(self.puzzle_files(difficulty)?.len())
+    }
+
+    pub fn add(&self, difficulty: Difficulty, puzzle: &Board) -> io::Result<()> {
+        let name = format!(
+            "{}-{}-{}",
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos(),
+            process::id(),
+            NEXT_FILE_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        let directory = self.difficulty_directory(difficulty);
+        let temporary = directory.join(format!("{name}.tmp"));
+        fs::write(&temporary, format_line(puzzle))?;
+        // Renaming is atomic, so other processes never see a partially written puzzle
+        fs::rename(temporary, directory.join(format!("{name}.txt")))
+    }
+
+    /// Removes the oldest puzzle of the difficulty from the store and returns it, or `None` if there is none
+    pub fn take(&self, difficulty: Difficulty) -> io::Result<Option<Board>> {
+        for path in self.puzzle_files(difficulty)? {
+            // Claim the file by renaming it. If another process was faster, try the next one.
+            let claimed = path.with_extension(format!("{}.taken", process::id()));
+            if fs::rename(&path, &claimed).is_err() {
+                continue;
+            }
+            let content = fs::read_to_string(&claimed);
+            fs::remove_file(&claimed)?;
+            let puzzle = parse_line(&content?).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            return Ok(Some(puzzle));
+        }
+        Ok(None)
+    }
+
+    /// Generates puzzles until the store has `target` puzzles of the difficulty. Returns how many were generated.
+    pub fn refill(&self, difficulty: Difficulty, target: usize) -> io::Result<usize> {
+        let mut generated = 0;
+        while self.count(difficulty)? < target {
+            self.add(difficulty, &generate_with_difficulty(difficulty, Symmetry::Rotational))?;
+            generated += 1;
+        }
+        Ok(generated)
+    }
+
+    /// Keeps the store filled up to the targets until `stop` is set, checking for taken puzzles every `interval`.
+    /// This blocks, so it's meant to run on its own thread or in a daemon process.
+    pub fn refill_until(&self, targets: &[(Difficulty, usize)], interval: Duration, stop: &AtomicBool) -> io::Result<()> {
+        while !stop.load(Ordering::Relaxed) {
+            for &(difficulty, target) in targets {
+                self.refill(difficulty, target)?;
+            }
+            thread::sleep(interval);
+        }
+        Ok(())
+    }
+}
+
+/// A [PuzzlePool] keeps a configurable number of puzzles per difficulty ready in a [PuzzleStore] and refills it on a
+/// background thread, so apps can hand out puzzles instantly. The puzzles persist in the store between runs.
+///
+/// Several pools, e.g. in different processes, can share a store. The background thread refills right after a puzzle
+/// was taken from this pool, and also checks every `interval` for puzzles that were taken by others.
+pub struct PuzzlePool {
+    store: Arc<PuzzleStore>,
+    signal: Arc<RefillSignal>,
+    refiller: Option<JoinHandle<io::Result<()>>>,
+}
+
+#[derive(Default)]
+struct RefillSignal {
+    state: Mutex<RefillState>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct RefillState {
+    /// Set when the pool is dropped or stopped
+    stopped: bool,
+
+    /// Set when a puzzle was taken since the background thread last checked
+    woken: bool,
+}
+
+impl RefillSignal {
+    fn is_stopped(&self) -> bool {
+        self.state.lock().unwrap().stopped
+    }
+
+    /// Waits until [RefillSignal::wake] is called or the timeout passes. Returns true if the pool was stopped.
+    fn wait(&self, timeout: Duration) -> bool {
+        let state = self.state.lock().unwrap();
+        let (mut state, _) = self
+            .condvar
+            .wait_timeout_while(state, timeout, |state| !state.woken && !state.stopped)
+            .unwrap();
+        state.woken = false;
+        state.stopped
+    }
+
+    fn wake(&self) {
+        self.state.lock().unwrap().woken = true;
+        self.condvar.notify_all();
+    }
+
+    fn stop(&self) {
+        self.state.lock().unwrap().stopped = true;
+        self.condvar.notify_all();
+    }
+}
+
+impl PuzzlePool {
+    /// Starts refilling the store up to the number of puzzles given for each difficulty
+    pub fn start(store: PuzzleStore, targets: Vec<(Difficulty, usize)>, interval: Duration) -> Self {
+        let store = Arc::new(store);
+        let signal = Arc::new(RefillSignal::default());
+        let refiller = {
+            let store = Arc::clone(&store);
+            let signal = Arc::clone(&signal);
+            thread::spawn(move || refill_loop(&store, &targets, interval, &signal))
+        };
+        Self {
+            store,
+            signal,
+            refiller: Some(refiller),
+        }
+    }
+
+    pub fn store(&self) -> &PuzzleStore {
+        &self.store
+    }
+
+    /// Takes a ready puzzle, or returns `None` if there is none of the difficulty right now
+    pub fn try_take(&self, difficulty: Difficulty) -> io::Result<Option<Board>> {
+        let puzzle = self.store.take(difficulty)?;
+        self.signal.wake();
+        Ok(puzzle)
+    }
+
+    /// Takes a ready puzzle, or generates one if there is none of the difficulty right now
+    pub fn take(&self, difficulty: Difficulty) -> io::Result<Board> {
+        Ok(self
+            .try_take(difficulty)?
+            .unwrap_or_else(|| generate_with_difficulty(difficulty, Symmetry::Rotational)))
+    }
+
+    /// Blocks until the background thread stops, which only happens if it fails, e.g. because the disk is full
+    pub fn join(mut self) -> io::Result<()> {
+        self.refiller.take().expect("Pool is running").join().expect("Refill thread doesn't panic")
+    }
+
+    /// Stops the background thread after the puzzle it is generating, and returns the error it failed with, if any
+    pub fn stop(mut self) -> io::Result<()> {
+        self.signal.stop();
+        self.refiller.take().expect("Pool is running").join().expect("Refill thread doesn't panic")
+    }
+}
+
+/// Dropping the pool stops the background thread after the puzzle it is generating, without waiting for it
+impl Drop for PuzzlePool {
+    fn drop(&mut self) {
+        self.signal.stop();
+    }
+}
+
+fn refill_loop(
+    store: &PuzzleStore,
+    targets: &[(Difficulty, usize)],
+    interval: Duration,
+    signal: &RefillSignal,
+) -> io::Result<()> {
+    loop {
+        for &(difficulty, target) in targets {
+            while store.count(difficulty)? < target {
+                if signal.is_stopped() {
+                    return Ok(());
+                }
+                store.add(difficulty, &generate_with_difficulty(difficulty, Symmetry::Rotational))?;
+            }
+        }
+        if signal.wait(interval) {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temporary_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("sudoku-pool-test-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn add_and_take() {
+        let directory = temporary_directory("add-and-take");
+        let store = PuzzleStore::open(&directory).unwrap();
+        let first = parse_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79").unwrap();
+        let second = Board::new_empty();
+        store.add(Difficulty::Easy, &first).unwrap();
+        store.add(Difficulty::Easy, &second).unwrap();
+        assert_eq!(2, store.count(Difficulty::Easy).unwrap());
+        assert_eq!(0, store.count(Difficulty::Hard).unwrap());
+        assert_eq!(None, store.take(Difficulty::Hard).unwrap());
+        assert_eq!(Some(first), store.take(Difficulty::Easy).unwrap());
+        assert_eq!(Some(second), store.take(Difficulty::Easy).unwrap());
+        assert_eq!(None, store.take(Difficulty::Easy).unwrap());
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn refill() {
+        let directory = temporary_directory("refill");
+        let store = PuzzleStore::open(&directory).unwrap();
+        assert_eq!(1, store.refill(Difficulty::Easy, 1).unwrap());
+        assert_eq!(0, store.refill(Difficulty::Easy, 1).unwrap());
+        let puzzle = store.take(Difficulty::Easy).unwrap().unwrap();
+        assert_eq!(Difficulty::Easy, crate::rating::rate(&puzzle).unwrap().difficulty);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn pool_refills_in_background() {
+        let directory = temporary_directory("pool");
+        let targets = vec![(Difficulty::Easy, 2)];
+        let pool = PuzzlePool::start(PuzzleStore::open(&directory).unwrap(), targets, Duration::from_secs(60));
+        let puzzle = pool.take(Difficulty::Easy).unwrap();
+        assert_eq!(Difficulty::Easy, crate::rating::rate(&puzzle).unwrap().difficulty);
+        while pool.store().count(Difficulty::Easy).unwrap() < 2 {
+            thread::sleep(Duration::from_millis(10));
+        }
+        pool.stop().unwrap();
+
+        // The puzzles persist for the next pool
+        let pool = PuzzlePool::start(PuzzleStore::open(&directory).unwrap(), vec![], Duration::from_secs(60));
+        assert!(pool.try_take(Difficulty::Easy).unwrap().is_some());
+        assert!(pool.try_take(Difficulty::Easy).unwrap().is_some());
+        assert_eq!(None, pool.try_take(Difficulty::Easy).unwrap());
+        drop(pool);
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::board::Board;
+use crate::solver::{SolverContext, SolverError};
+
+use super::{rate_unique, TechniqueReport};
+
+/// Rates all puzzles like [rate](super::rate), using all cores with the `parallel` feature.
+/// The reports are in the order of the puzzles.
+pub fn rate_batch(puzzles: impl IntoIterator<Item = Board>) -> Vec<Result<TechniqueReport, SolverError>> {
+    rate_batch_with(puzzles, |_| {}, &AtomicBool::new(false)).expect("The batch can't be cancelled")
+}
+
+/// Like [rate_batch], but calls `progress` with the number of puzzles rated so far after each puzzle, e.g. to show a
+/// progress bar. `progress` is called from the threads that rate the puzzles.
+///
+/// Once `cancel` is set, the puzzles being rated stop, no more are started and this returns `None`.
+pub fn rate_batch_with(
+    puzzles: impl IntoIterator<Item = Board>,
+    progress: impl Fn(usize) + Sync,
+    cancel: &AtomicBool,
+) -> Option<Vec<Result<TechniqueReport, SolverError>>> {
+    let puzzles: Vec<Board> = puzzles.into_iter().collect();
+    let num_rated = AtomicUsize::new(0);
+    let rate = |context: &mut SolverContext, puzzle: &Board| {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        // Checking uniqueness is the expensive part, so each thread keeps the allocations of its solver
+        let report = context.solve_with_cancel(*puzzle, cancel)?.map(|_| rate_unique(puzzle, &[]));
+        progress(num_rated.fetch_add(1, Ordering::Relaxed) + 1);
+        Some(report)
+    };
+    #[cfg(feature = "parallel")]
+    let reports = puzzles.par_iter().map_init(SolverContext::new, rate).collect();
+    #[cfg(not(feature = "parallel"))]
+    let reports = {
+        let mut context = SolverContext::new();
+        puzzles.iter().map(|puzzle| rate(&mut context, puzzle)).collect()
+    };
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::formats::parse_line;
+    use crate::rating::rate;
+
+    const PUZZLES: [&str; 3] = [
+        "28.3.7.....3..18........2....21.8......49.....79..6.1.5..9..4...3......29.46...83",
+        "62.4......1.7..9..3..5....1.......3....2.174...6.3..5....98.5..........8.75......",
+        "5..2.......36....7.6.4.............5.3..8..127..9......2....5461........48...79..",
+    ];
+
+    fn puzzles() -> Vec<Board> {
+        PUZZLES.iter().map(|puzzle| parse_line(puzzle).unwrap()).collect()
+    }
+
+    #[test]
+    fn same_as_rate() {
+        let puzzles: Vec<Board> = puzzles().into_iter().chain([Board::new_empty()]).collect();
+        let reports = rate_batch(puzzles.iter().copied());
+        assert_eq!(puzzles.iter().map(rate).collect::<Vec<_>>(), reports);
+    }
+
+    #[test]
+    fn progress_and_cancel() {
+        let puzzles = puzzles();
+        let reported = Mutex::new(vec![]);
+        let reports = rate_batch_with(
+            puzzles.iter().copied(),
+            |num_rated| reported.lock().unwrap().push(num_rated),
+            &AtomicBool::new(false),
+        );
+        assert_eq!(Some(3), reports.map(|reports| reports.len()));
+        let mut reported = reported.into_inner().unwrap();
+        reported.sort();
+        assert_eq!(vec![1, 2, 3], reported);
+
+        let reports = rate_batch_with(puzzles.iter().copied(), |_| panic!("Nothing to rate"), &AtomicBool::new(true));
+        assert_eq!(None, reports);
+    }
+}
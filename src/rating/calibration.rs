@@ -0,0 +1,248 @@
+//! Measures [rate] against puzzles with known difficulties.
+//!
+//! [calibrate] compares the ratings with reference difficulties from another source, e.g. the grades of a published
+//! puzzle collection or of another rater, read with [parse_calibration_set]. That tells how well the difficulties of
+//! this crate match what others call easy or hard. No such set is embedded yet, since the reference difficulties
+//! have to come from outside this crate.
+//!
+//! [regression_check] compares the ratings with the [baseline_set] instead, which was generated with
+//! `sudoku generate -n 20 --difficulty <difficulty> --seed 1232` for each difficulty and labelled with the ratings at
+//! that time. It agrees with the rater by construction, so it doesn't calibrate anything, but it notices when changes
+//! to the hint engine shift what a difficulty means. Its puzzles are never removed or relabeled. If a change to the
+//! techniques is meant to rate some of them differently, that is a decision to make explicitly, not by accident.
+
+use thiserror::Error;
+
+use super::{rate, Difficulty};
+use crate::board::Board;
+use crate::formats::{parse_line, BoardParseError};
+
+const BASELINE_SET: &str = include_str!("baseline.csv");
+
+/// A puzzle with a reference difficulty to compare the rating with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CalibrationPuzzle {
+    pub puzzle: Board,
+    pub reference: Difficulty,
+}
+
+/// A puzzle that a rater puts into another difficulty than its reference
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub puzzle: Board,
+    pub reference: Difficulty,
+    pub rated: Difficulty,
+}
+
+/// How well a rater agrees with the reference difficulties of a set of puzzles
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CalibrationReport {
+    /// `confusion[reference][rated]` is the number of puzzles with the reference difficulty that got the rated
+    /// difficulty, indexed like [Difficulty::ALL]
+    pub confusion: [[usize; Difficulty::ALL.len()]; Difficulty::ALL.len()],
+
+    pub mismatches: Vec<Mismatch>,
+
+    /// Puzzles the rater couldn't rate, e.g. because they don't have a unique solution. They aren't part of
+    /// [CalibrationReport::confusion].
+    pub unrated: Vec<Board>,
+}
+
+impl CalibrationReport {
+    /// The number of rated puzzles
+    pub fn num_puzzles(&self) -> usize {
+        self.confusion.iter().flatten().sum()
+    }
+
+    /// The fraction of rated puzzles that got their reference difficulty
+    pub fn agreement(&self) -> f64 {
+        (self.num_puzzles() - self.mismatches.len()) as f64 / self.num_puzzles() as f64
+    }
+
+    /// The average number of difficulty levels between the rating and the reference, with a positive value if the
+    /// rater rates puzzles harder than the reference
+    pub fn bias(&self) -> f64 {
+        let shift: isize = self
+            .mismatches
+            .iter()
+            .map(|mismatch| difficulty_index(mismatch.rated) as isize - difficulty_index(mismatch.reference) as isize)
+            .sum();
+        shift as f64 / self.num_puzzles() as f64
+    }
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[error("{} of {num_puzzles} baseline puzzles got another difficulty than their reference", .mismatches.len())]
+pub struct RatingRegression {
+    pub num_puzzles: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CalibrationParseError {
+    #[error("Line {line}: Expected a puzzle and a difficulty separated by a comma")]
+    MissingDifficulty { line: usize },
+
+    #[error("Line {line}: {error}")]
+    InvalidPuzzle { line: usize, error: BoardParseError },
+
+    #[error("Line {line}: {error}")]
+    InvalidDifficulty { line: usize, error: String },
+}
+
+/// Reads puzzles with reference difficulties, one `puzzle,difficulty` pair per line with the puzzle in the line
+/// format of [parse_line] and the difficulty as in [Difficulty::name], e.g. `..3.2.6..,hard`.
+/// A first line of `puzzle,difficulty` is skipped, as are empty lines.
+pub fn parse_calibration_set(csv: &str) -> Result<Vec<CalibrationPuzzle>, CalibrationParseError> {
+    csv.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|&(line, content)| !content.is_empty() && (line, content) != (1, "puzzle,difficulty"))
+        .map(|(line, content)| {
+            let (puzzle, reference) =
+                content.split_once(',').ok_or(CalibrationParseError::MissingDifficulty { line })?;
+            Ok(CalibrationPuzzle {
+                puzzle: parse_line(puzzle.trim())
+                    .map_err(|error| CalibrationParseError::InvalidPuzzle { line, error })?,
+                reference: reference
+                    .trim()
+                    .parse()
+                    .map_err(|error| CalibrationParseError::InvalidDifficulty { line, error })?,
+            })
+        })
+        .collect()
+}
+
+/// The puzzles of the regression baseline, in the order of [Difficulty::ALL]. Their reference difficulties are
+/// earlier ratings of [rate], so the baseline catches changes of the rating, not disagreements with other raters.
+pub fn baseline_set() -> Vec<CalibrationPuzzle> {
+    parse_calibration_set(BASELINE_SET).expect("The baseline is valid")
+}
+
+/// Scores [rate] against the reference difficulties of the given puzzles
+pub fn calibrate(reference: &[CalibrationPuzzle]) -> CalibrationReport {
+    calibrate_with(reference, |puzzle| rate(puzzle).ok().map(|report| report.difficulty))
+}
+
+/// Like [calibrate], but for another rater, e.g. to compare a new rating model with the current one.
+/// The rater returns `None` for puzzles it can't rate.
+pub fn calibrate_with(
+    reference: &[CalibrationPuzzle],
+    mut rater: impl FnMut(&Board) -> Option<Difficulty>,
+) -> CalibrationReport {
+    let mut confusion = [[0; Difficulty::ALL.len()]; Difficulty::ALL.len()];
+    let mut mismatches = vec![];
+    let mut unrated = vec![];
+    for &CalibrationPuzzle { puzzle, reference } in reference {
+        let Some(rated) = rater(&puzzle) else {
+            unrated.push(puzzle);
+            continue;
+        };
+        confusion[difficulty_index(reference)][difficulty_index(rated)] += 1;
+        if rated != reference {
+            mismatches.push(Mismatch {
+                puzzle,
+                reference,
+                rated,
+            });
+        }
+    }
+    CalibrationReport {
+        confusion,
+        mismatches,
+        unrated,
+    }
+}
+
+/// Fails if [rate] puts any puzzle of the [baseline_set] into another difficulty than its reference.
+/// This runs as a test, so changes to the techniques can't shift the difficulties unnoticed.
+pub fn regression_check() -> Result<(), RatingRegression> {
+    let report = calibrate(&baseline_set());
+    if report.mismatches.is_empty() && report.unrated.is_empty() {
+        Ok(())
+    } else {
+        Err(RatingRegression {
+            num_puzzles: report.num_puzzles() + report.unrated.len(),
+            mismatches: report.mismatches,
+        })
+    }
+}
+
+fn difficulty_index(difficulty: Difficulty) -> usize {
+    Difficulty::ALL.iter().position(|d| *d == difficulty).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_rating_matches_baseline() {
+        if let Err(regression) = regression_check() {
+            let details: Vec<String> = regression
+                .mismatches
+                .iter()
+                .map(|mismatch| format!("{mismatch:?}"))
+                .collect();
+            panic!("{regression}:\n{}", details.join("\n"));
+        }
+    }
+
+    #[test]
+    fn report() {
+        let set = baseline_set();
+        assert_eq!(100, set.len());
+        for difficulty in Difficulty::ALL {
+            assert_eq!(20, set.iter().filter(|puzzle| puzzle.reference == difficulty).count());
+        }
+
+        let report = calibrate_with(&set, |_| Some(Difficulty::Hard));
+        assert_eq!(100, report.num_puzzles());
+        assert_eq!(0.2, report.agreement());
+        assert_eq!(20, report.confusion[0][2]);
+        assert_eq!(0, report.confusion[0][0]);
+        // Easy and medium are rated 2 and 1 levels too hard, expert and extreme 1 and 2 levels too easy
+        assert_eq!(0.0, report.bias());
+
+        // A rater that doesn't know the expert techniques and rates expert and extreme puzzles hard
+        let report = calibrate_with(&set, |puzzle| Some(rate(puzzle).unwrap().difficulty.min(Difficulty::Hard)));
+        assert_eq!(0.6, report.agreement());
+        assert_eq!(-0.6, report.bias());
+        assert_eq!(40, report.mismatches.len());
+    }
+
+    #[test]
+    fn calibrate_reference_set() {
+        let set = parse_calibration_set(
+            "puzzle,difficulty
+            .3.1..2..9..8.74........9.6...56.....123....4........1..4....5.79......2.2..45..9,medium
+
+            ...6.........746.1......9....5....7.9.....1....1532.....9..52.381......9.7..1....,Hard
+            .................................................................................,easy",
+        )
+        .unwrap();
+        assert_eq!(3, set.len());
+        let report = calibrate(&set);
+        assert_eq!(2, report.num_puzzles());
+        assert_eq!(vec![Board::new_empty()], report.unrated);
+        assert_eq!(0.5, report.agreement());
+        assert_eq!(-0.5, report.bias());
+        assert_eq!(Difficulty::Easy, report.mismatches[0].rated);
+    }
+
+    #[test]
+    fn invalid_calibration_sets() {
+        assert_eq!(
+            Err(CalibrationParseError::MissingDifficulty { line: 2 }),
+            parse_calibration_set("puzzle,difficulty\n...")
+        );
+        assert!(matches!(
+            parse_calibration_set("...,easy"),
+            Err(CalibrationParseError::InvalidPuzzle { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_calibration_set(&format!("{},trivial", ".".repeat(81))),
+            Err(CalibrationParseError::InvalidDifficulty { line: 1, .. })
+        ));
+    }
+}
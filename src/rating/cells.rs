@@ -0,0 +1,88 @@
+use crate::board::{Board, HEIGHT, WIDTH};
+use crate::constraints::Constraint;
+use crate::hints::Technique;
+use crate::solver::SolverError;
+
+use super::{rate_with_constraints, Difficulty};
+
+/// When and how the hint engine solves an empty cell of a puzzle, see [analyze_cells]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CellAnalysis {
+    pub x: usize,
+    pub y: usize,
+
+    /// The index of the step in [TechniqueReport::steps](super::TechniqueReport::steps) that places the value, or
+    /// `None` if the hint engine gets stuck before
+    pub step: Option<usize>,
+
+    /// The hardest technique of the steps since the previous placement, i.e. what it took to unlock this cell
+    pub technique: Option<Technique>,
+
+    /// The difficulty of [CellAnalysis::technique], or [Difficulty::Extreme] if the cell needs guessing
+    pub difficulty: Difficulty,
+}
+
+/// Analyzes each empty cell of the puzzle, row by row, e.g. to render a heatmap of where the crux of the puzzle is.
+/// The puzzle must have a unique solution, otherwise the corresponding [SolverError] is returned.
+pub fn analyze_cells(board: &Board) -> Result<Vec<CellAnalysis>, SolverError> {
+    analyze_cells_with_constraints(board, &[])
+}
+
+/// Like [analyze_cells], but for a sudoku variant that has to fulfill the given constraints in addition to the classic
+/// rules
+pub fn analyze_cells_with_constraints(
+    board: &Board,
+    constraints: &[Constraint],
+) -> Result<Vec<CellAnalysis>, SolverError> {
+    let report = rate_with_constraints(board, constraints)?;
+    let mut solved = [[None; WIDTH]; HEIGHT];
+    let mut hardest_since_placement = None;
+    for (index, step) in report.steps.iter().enumerate() {
+        hardest_since_placement = hardest_since_placement.max(Some(step.technique));
+        if let Some(placement) = step.placement {
+            solved[placement.y][placement.x] = Some((index, hardest_since_placement.take()));
+        }
+    }
+    let empty_cells = (0..HEIGHT).flat_map(|y| (0..WIDTH).map(move |x| (x, y)));
+    Ok(empty_cells
+        .filter(|&(x, y)| board.field(x, y).is_empty())
+        .map(|(x, y)| {
+            let (step, technique) = match solved[y][x] {
+                Some((step, technique)) => (Some(step), technique),
+                None => (None, None),
+            };
+            CellAnalysis {
+                x,
+                y,
+                step,
+                technique,
+                difficulty: technique.map_or(Difficulty::Extreme, |technique| technique.difficulty()),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::parse_line;
+
+    /// Needs techniques up to [Difficulty::Hard]
+    const HARD: &str = "...8..2.6....37..8.8..16......3.....6...7..39.24..1...2.....6.4.......1..1...8.5.";
+
+    #[test]
+    fn analyze_hard_puzzle() {
+        let puzzle = parse_line(HARD).unwrap();
+        let cells = analyze_cells(&puzzle).unwrap();
+        assert_eq!(puzzle.num_empty(), cells.len());
+        assert!(cells
+            .iter()
+            .all(|cell| puzzle.field(cell.x, cell.y).is_empty() && cell.step.is_some()));
+        assert_eq!(Some(Difficulty::Hard), cells.iter().map(|cell| cell.difficulty).max());
+        assert!(cells.iter().any(|cell| cell.technique == Some(Technique::HiddenSingle)));
+        assert!(cells
+            .windows(2)
+            .all(|pair| (pair[0].y, pair[0].x) < (pair[1].y, pair[1].x)));
+    }
+}
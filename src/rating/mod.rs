@@ -0,0 +1,272 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+
+use crate::board::Board;
+use crate::constraints::Constraint;
+use crate::hints::{LogicalSolver, Step, Technique};
+use crate::solver::{solve_with_cancel, solve_with_constraints, SolverError};
+
+mod batch;
+mod calibration;
+mod cells;
+
+pub use calibration::{
+    baseline_set, calibrate, calibrate_with, parse_calibration_set, regression_check, CalibrationParseError,
+    CalibrationPuzzle, CalibrationReport, Mismatch, RatingRegression,
+};
+pub use batch::{rate_batch, rate_batch_with};
+pub use cells::{analyze_cells, analyze_cells_with_constraints, CellAnalysis};
+
+/// How hard a sudoku is for a human, based on the hardest technique needed to solve it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    /// The techniques of the hint engine aren't enough, solving it requires guessing
+    Extreme,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+        Difficulty::Extreme,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+            Difficulty::Extreme => "extreme",
+        }
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown difficulty '{s}'"))
+    }
+}
+
+impl Technique {
+    /// The difficulty of a sudoku whose hardest required technique is this one
+    pub fn difficulty(&self) -> Difficulty {
+        match self {
+            Technique::HiddenSingle => Difficulty::Easy,
+            Technique::NakedSingle => Difficulty::Medium,
+            Technique::GreaterThanBounds
+            | Technique::NonConsecutiveNeighbors
+            | Technique::KropkiDots
+            | Technique::LockedCandidates
+            | Technique::VariantPeers => Difficulty::Hard,
+            Technique::NakedPair | Technique::XWing | Technique::Swordfish => Difficulty::Expert,
+        }
+    }
+}
+
+/// How much work solving a sudoku takes in total, as opposed to [TechniqueReport::difficulty] which only depends on
+/// the hardest step. See [TechniqueReport::complexity].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Complexity {
+    /// One point per step, per cell a step is based on and per eliminated candidate
+    pub score: usize,
+
+    /// The number of steps
+    pub steps: usize,
+
+    /// The number of cells the steps are based on, summed over all steps, e.g. four for an X-Wing
+    pub pattern_cells: usize,
+
+    /// The number of cells of the largest pattern a single step is based on
+    pub longest_pattern: usize,
+
+    /// The number of candidates eliminated by all steps
+    pub eliminations: usize,
+}
+
+/// The result of rating a sudoku: its difficulty and the logical steps that solve it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TechniqueReport {
+    pub difficulty: Difficulty,
+
+    /// The steps the hint engine applied, in order. If [TechniqueReport::requires_guessing] is set, these don't solve the whole board.
+    pub steps: Vec<Step>,
+
+    /// True if the hint engine got stuck before the board was solved
+    pub requires_guessing: bool,
+}
+
+impl TechniqueReport {
+    pub fn hardest_technique(&self) -> Option<Technique> {
+        self.steps.iter().map(|step| step.technique).max()
+    }
+
+    /// Returns how often each technique was used, ordered from easiest to hardest technique
+    pub fn technique_counts(&self) -> Vec<(Technique, usize)> {
+        Technique::ALL
+            .into_iter()
+            .map(|technique| {
+                let count = self
+                    .steps
+                    .iter()
+                    .filter(|step| step.technique == technique)
+                    .count();
+                (technique, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// Measures the total effort of the steps, e.g. to tell apart two sudokus of the same difficulty where one needs
+    /// its hardest technique once and the other one over and over
+    pub fn complexity(&self) -> Complexity {
+        let steps = self.steps.len();
+        let pattern_cells = self.steps.iter().map(|step| step.cells.len()).sum();
+        let eliminations = self.steps.iter().map(|step| step.eliminations.len()).sum();
+        Complexity {
+            score: steps + pattern_cells + eliminations,
+            steps,
+            pattern_cells,
+            longest_pattern: self.steps.iter().map(|step| step.cells.len()).max().unwrap_or(0),
+            eliminations,
+        }
+    }
+}
+
+/// [rate] rates how hard the sudoku is for a human by solving it with the hint engine.
+/// The sudoku must have a unique solution, otherwise the corresponding [SolverError] is returned.
+pub fn rate(board: &Board) -> Result<TechniqueReport, SolverError> {
+    rate_with_constraints(board, &[])
+}
+
+/// Like [rate], but for a sudoku variant that has to fulfill the given constraints in addition to the classic rules.
+pub fn rate_with_constraints(
+    board: &Board,
+    constraints: &[Constraint],
+) -> Result<TechniqueReport, SolverError> {
+    solve_with_constraints(*board, constraints)?;
+    Ok(rate_unique(board, constraints))
+}
+
+/// Like [rate], but gives up and returns `None` once `cancel` is set, see [solve_with_cancel](crate::solve_with_cancel).
+/// Checking that the solution is unique is the part that can take a while.
+pub fn rate_with_cancel(board: &Board, cancel: &AtomicBool) -> Option<Result<TechniqueReport, SolverError>> {
+    Some(solve_with_cancel(*board, &[], cancel)?.map(|_| rate_unique(board, &[])))
+}
+
+/// Like [rate_with_constraints], but for a sudoku that's already known to have a unique solution
+pub(crate) fn rate_unique(board: &Board, constraints: &[Constraint]) -> TechniqueReport {
+    let mut solver = LogicalSolver::new(*board, constraints.to_vec());
+    let steps: Vec<Step> = solver.by_ref().collect();
+    let requires_guessing = !solver.board().is_filled();
+    let difficulty = if requires_guessing {
+        Difficulty::Extreme
+    } else {
+        steps
+            .iter()
+            .map(|step| step.technique.difficulty())
+            .max()
+            .unwrap_or(Difficulty::Easy)
+    };
+    TechniqueReport {
+        difficulty,
+        steps,
+        requires_guessing,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::parse_line;
+
+    #[test]
+    fn rate_easy() {
+        let board = Board::from_str(
+            "
+            53_ _7_ ___
+            6__ 195 ___
+            _98 ___ _6_
+
+            8__ _6_ __3
+            4__ 8_3 __1
+            7__ _2_ __6
+
+            _6_ ___ 28_
+            ___ 419 __5
+            ___ _8_ _79
+        ",
+        );
+        let report = rate(&board).unwrap();
+        assert!(!report.requires_guessing);
+        assert_eq!(board.num_empty(), report.steps.len());
+        assert!(report.difficulty <= Difficulty::Medium);
+        let complexity = report.complexity();
+        assert_eq!(board.num_empty(), complexity.steps);
+        assert_eq!(0, complexity.eliminations);
+        assert_eq!(1, complexity.longest_pattern);
+        assert_eq!(2 * board.num_empty(), complexity.score);
+    }
+
+    #[test]
+    fn rate_with_invalid_constraint() {
+        let invalid = Constraint::GreaterThan { greater: (9, 0), smaller: (8, 0) };
+        assert_eq!(
+            Err(SolverError::InvalidConstraint(invalid)),
+            rate_with_constraints(&Board::new_empty(), &[invalid]).map(|report| report.difficulty)
+        );
+    }
+
+    #[test]
+    fn complexity_of_same_difficulty() {
+        let hard = [
+            ".1.......4..8.2.1.......849....2.69.1.9.743.......6.....6...7..7.4.8....8.......5",
+            "5..2.......36....7.6.4.............5.3..8..127..9......2....5461........48...79..",
+            "...8..2.6....37..8.8..16......3.....6...7..39.24..1...2.....6.4.......1..1...8.5.",
+        ];
+        let mut complexities: Vec<Complexity> = hard
+            .into_iter()
+            .map(|puzzle| rate(&parse_line(puzzle).unwrap()).unwrap())
+            .inspect(|report| assert_eq!(Difficulty::Hard, report.difficulty))
+            .map(|report| report.complexity())
+            .collect();
+        complexities.sort();
+        assert!(complexities[0].score < complexities[complexities.len() - 1].score);
+        assert!(complexities.iter().all(|complexity| complexity.eliminations > 0));
+    }
+
+    #[test]
+    fn rate_ambigious() {
+        assert_eq!(Err(SolverError::Ambigious), rate(&Board::new_empty()));
+    }
+
+    #[test]
+    fn difficulty_from_str() {
+        for difficulty in Difficulty::ALL {
+            assert_eq!(Ok(difficulty), difficulty.to_string().parse());
+        }
+        assert_eq!(Ok(Difficulty::Hard), "HARD".parse());
+        assert!("impossible".parse::<Difficulty>().is_err());
+    }
+}
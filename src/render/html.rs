@@ -0,0 +1,34 @@
+use super::svg::{escape, render_grid};
+use super::Figure;
+
+pub fn render(figures: &[Figure]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Sudoku</title>\n<style>\n\
+         figure { display: inline-block; margin: 1em; break-inside: avoid; }\n\
+         figcaption { font-family: sans-serif; font-size: 1.2em; margin-bottom: 0.5em; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+    for figure in figures {
+        html += &format!("<figure>\n<figcaption>{}</figcaption>\n", escape(&figure.caption));
+        html += "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"364\" height=\"364\" viewBox=\"-2 -2 364 364\">\n";
+        html += &render_grid(&figure.board, 0, 0);
+        html += "</svg>\n</figure>\n";
+    }
+    html += "</body>\n</html>\n";
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    fn render_figures() {
+        let figures = [Figure { caption: "A & B".to_string(), board: Board::new_empty() }];
+        let html = render(&figures);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<figcaption>A &amp; B</figcaption>"));
+        assert_eq!(1, html.matches("<figure>").count());
+    }
+}
@@ -0,0 +1,47 @@
+use super::{for_each_value, Figure};
+
+pub fn render(figures: &[Figure]) -> String {
+    let mut latex = String::from(
+        "\\documentclass{article}\n\\usepackage[a4paper,margin=2cm]{geometry}\n\\usepackage{tikz}\n\\begin{document}\n",
+    );
+    for figure in figures {
+        latex += "\\begin{center}\n\\begin{minipage}{9cm}\n";
+        latex += &format!("\\textbf{{{}}}\\\\[0.5em]\n", escape(&figure.caption));
+        latex += "\\begin{tikzpicture}[scale=0.9]\n";
+        latex += "\\draw[step=1,thin] (0,0) grid (9,9);\n";
+        latex += "\\draw[step=3,very thick] (0,0) grid (9,9);\n";
+        for_each_value(&figure.board, |x, y, value| {
+            latex += &format!("\\node at ({}.5,{}.5) {{\\Large {value}}};\n", x, 8 - y);
+        });
+        latex += "\\end{tikzpicture}\n\\end{minipage}\n\\end{center}\n";
+    }
+    latex += "\\end{document}\n";
+    latex
+}
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' => "\\textbackslash{}".to_string(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{c}"),
+            '~' => "\\textasciitilde{}".to_string(),
+            '^' => "\\textasciicircum{}".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::parse_line;
+
+    #[test]
+    fn render_figures() {
+        let board = parse_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79").unwrap();
+        let latex = render(&[Figure { caption: "Puzzle #1".to_string(), board }]);
+        assert!(latex.contains("\\textbf{Puzzle \\#1}"));
+        assert!(latex.contains("\\node at (0.5,8.5) {\\Large 5};"));
+        assert_eq!(81 - board.num_empty(), latex.matches("\\node").count());
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::board::{Board, HEIGHT, WIDTH};
+
+mod html;
+mod latex;
+mod pdf;
+mod svg;
+
+/// A board with a caption, e.g. "Puzzle 12" or "Solution 12", as laid out by the renderers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Figure {
+    pub caption: String,
+    pub board: Board,
+}
+
+/// The graphical formats the renderers can write
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RenderFormat {
+    /// One SVG image with all figures below each other
+    Svg,
+
+    /// A PDF document with 6 figures per A4 page
+    Pdf,
+
+    /// A LaTeX document drawing the figures with TikZ
+    Latex,
+
+    /// A HTML page with an inline SVG image per figure
+    Html,
+}
+
+impl RenderFormat {
+    pub const ALL: [RenderFormat; 4] = [
+        RenderFormat::Svg,
+        RenderFormat::Pdf,
+        RenderFormat::Latex,
+        RenderFormat::Html,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderFormat::Svg => "svg",
+            RenderFormat::Pdf => "pdf",
+            RenderFormat::Latex => "latex",
+            RenderFormat::Html => "html",
+        }
+    }
+}
+
+impl Display for RenderFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for RenderFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RenderFormat::ALL
+            .into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown render format '{s}'"))
+    }
+}
+
+/// Renders the figures into a document of the given format. Only PDF documents are binary, the other formats are UTF-8 text.
+pub fn render(figures: &[Figure], format: RenderFormat) -> Vec<u8> {
+    match format {
+        RenderFormat::Svg => svg::render(figures).into_bytes(),
+        RenderFormat::Pdf => pdf::render(figures),
+        RenderFormat::Latex => latex::render(figures).into_bytes(),
+        RenderFormat::Html => html::render(figures).into_bytes(),
+    }
+}
+
+/// Calls `f(x, y, value)` for each filled cell
+fn for_each_value(board: &Board, mut f: impl FnMut(usize, usize, u8)) {
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            if let Some(value) = board.field(x, y).get() {
+                f(x, y, value.get());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_from_str() {
+        for format in RenderFormat::ALL {
+            assert_eq!(Ok(format), format.to_string().parse());
+        }
+        assert!("png".parse::<RenderFormat>().is_err());
+    }
+}
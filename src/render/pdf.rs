@@ -0,0 +1,142 @@
+use super::{for_each_value, Figure};
+use crate::board::Board;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const CELL_SIZE: f32 = 24.0;
+const GRID_SIZE: f32 = 9.0 * CELL_SIZE;
+const CAPTION_HEIGHT: f32 = 20.0;
+const SLOT_HEIGHT: f32 = CAPTION_HEIGHT + GRID_SIZE + 20.0;
+const TOP_MARGIN: f32 = 40.0;
+const COLUMNS: [f32; 2] = [55.0, 324.0];
+const ROWS: usize = 3;
+const FIGURES_PER_PAGE: usize = COLUMNS.len() * ROWS;
+const CAPTION_FONT_SIZE: f32 = 12.0;
+const DIGIT_FONT_SIZE: f32 = 16.0;
+/// Width of a digit in Helvetica, relative to the font size
+const DIGIT_WIDTH: f32 = 0.556;
+
+/// Writes a PDF document with [FIGURES_PER_PAGE] figures per A4 page.
+/// This only needs lines and the standard Helvetica font, so we write the few PDF objects by hand.
+pub fn render(figures: &[Figure]) -> Vec<u8> {
+    let pages: Vec<String> = if figures.is_empty() {
+        vec![String::new()]
+    } else {
+        figures.chunks(FIGURES_PER_PAGE).map(page_content).collect()
+    };
+
+    // Object 1 is the catalog, 2 the page tree, 3 the font, and then each page is followed by its content stream
+    let page_id = |index: usize| 4 + 2 * index;
+    let kids: Vec<String> = (0..pages.len()).map(|index| format!("{} 0 R", page_id(index))).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+    for (index, content) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            page_id(index) + 1
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{content}\nendstream", content.len()));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = vec![];
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf += &format!("{} 0 obj\n{object}\nendobj\n", index + 1);
+    }
+    let xref_offset = pdf.len();
+    pdf += &format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        pdf += &format!("{offset:010} 00000 n \n");
+    }
+    pdf += &format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+        objects.len() + 1
+    );
+    pdf.into_bytes()
+}
+
+fn page_content(figures: &[Figure]) -> String {
+    let mut content = String::new();
+    for (index, figure) in figures.iter().enumerate() {
+        let left = COLUMNS[index % COLUMNS.len()];
+        let slot_top = PAGE_HEIGHT - TOP_MARGIN - (index / COLUMNS.len()) as f32 * SLOT_HEIGHT;
+        content += &text(left, slot_top - CAPTION_FONT_SIZE, CAPTION_FONT_SIZE, &figure.caption);
+        content += &grid(&figure.board, left, slot_top - CAPTION_HEIGHT);
+    }
+    content
+}
+
+fn grid(board: &Board, left: f32, top: f32) -> String {
+    let mut content = String::new();
+    for line in 0..=9 {
+        let width = if line % 3 == 0 { 2.0 } else { 0.5 };
+        let offset = line as f32 * CELL_SIZE;
+        content += &format!(
+            "{width} w {} {top} m {} {} l S\n",
+            left + offset,
+            left + offset,
+            top - GRID_SIZE
+        );
+        content += &format!(
+            "{width} w {left} {} m {} {} l S\n",
+            top - offset,
+            left + GRID_SIZE,
+            top - offset
+        );
+    }
+    for_each_value(board, |x, y, value| {
+        let center_x = left + (x as f32 + 0.5) * CELL_SIZE;
+        let center_y = top - (y as f32 + 0.5) * CELL_SIZE;
+        content += &text(
+            center_x - DIGIT_WIDTH * DIGIT_FONT_SIZE / 2.0,
+            // Digits are about 0.7em high, so this centers them vertically
+            center_y - 0.35 * DIGIT_FONT_SIZE,
+            DIGIT_FONT_SIZE,
+            &value.to_string(),
+        );
+    });
+    content
+}
+
+fn text(x: f32, y: f32, size: f32, text: &str) -> String {
+    let escaped: String = text
+        .chars()
+        // The standard fonts only cover Latin-1, replace anything else
+        .map(|c| if c.is_ascii() { c } else { '?' })
+        .flat_map(|c| match c {
+            '(' | ')' | '\\' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect();
+    format!("BT /F1 {size} Tf {x} {y} Td ({escaped}) Tj ET\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_pages() {
+        let figures: Vec<Figure> = (1..=7)
+            .map(|number| Figure {
+                caption: format!("Puzzle ({number})"),
+                board: Board::new_empty(),
+            })
+            .collect();
+        let pdf = String::from_utf8(render(&figures)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/Count 2"));
+        assert!(pdf.contains("(Puzzle \\(7\\)) Tj"));
+
+        // Check that the cross-reference table points to the objects
+        let xref_offset: usize = pdf.lines().rev().nth(1).unwrap().parse().unwrap();
+        assert!(pdf[xref_offset..].starts_with("xref"));
+        let first_offset: usize = pdf[xref_offset..].lines().nth(3).unwrap()[..10].parse().unwrap();
+        assert!(pdf[first_offset..].starts_with("1 0 obj"));
+    }
+}
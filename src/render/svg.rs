@@ -0,0 +1,78 @@
+use super::{for_each_value, Figure};
+use crate::board::Board;
+
+const CELL_SIZE: usize = 40;
+const GRID_SIZE: usize = 9 * CELL_SIZE;
+const MARGIN: usize = 10;
+const CAPTION_HEIGHT: usize = 30;
+const FIGURE_HEIGHT: usize = CAPTION_HEIGHT + GRID_SIZE + MARGIN;
+
+pub fn render(figures: &[Figure]) -> String {
+    let width = GRID_SIZE + 2 * MARGIN;
+    let height = figures.len() * FIGURE_HEIGHT + MARGIN;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+    );
+    for (index, figure) in figures.iter().enumerate() {
+        let top = MARGIN + index * FIGURE_HEIGHT;
+        svg += &format!(
+            "<text x=\"{MARGIN}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"18\">{}</text>\n",
+            top + CAPTION_HEIGHT - 10,
+            escape(&figure.caption)
+        );
+        svg += &render_grid(&figure.board, MARGIN, top + CAPTION_HEIGHT);
+    }
+    svg += "</svg>\n";
+    svg
+}
+
+/// Renders a single grid as SVG elements with its top left corner at the given position
+pub fn render_grid(board: &Board, left: usize, top: usize) -> String {
+    let mut svg = format!("<g transform=\"translate({left},{top})\">\n");
+    for line in 0..=9 {
+        let position = line * CELL_SIZE;
+        let width = if line % 3 == 0 { 3 } else { 1 };
+        svg += &format!(
+            "<line x1=\"{position}\" y1=\"0\" x2=\"{position}\" y2=\"{GRID_SIZE}\" stroke=\"black\" stroke-width=\"{width}\" stroke-linecap=\"square\"/>\n"
+        );
+        svg += &format!(
+            "<line x1=\"0\" y1=\"{position}\" x2=\"{GRID_SIZE}\" y2=\"{position}\" stroke=\"black\" stroke-width=\"{width}\" stroke-linecap=\"square\"/>\n"
+        );
+    }
+    for_each_value(board, |x, y, value| {
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" font-family=\"sans-serif\" font-size=\"24\">{value}</text>\n",
+            x * CELL_SIZE + CELL_SIZE / 2,
+            y * CELL_SIZE + CELL_SIZE / 2
+        );
+    });
+    svg += "</g>\n";
+    svg
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::parse_line;
+
+    #[test]
+    fn render_figures() {
+        let board = parse_line("53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79").unwrap();
+        let figures = [
+            Figure { caption: "Puzzle <1>".to_string(), board },
+            Figure { caption: "Puzzle 2".to_string(), board },
+        ];
+        let svg = render(&figures);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Puzzle &lt;1&gt;"));
+        assert_eq!(2 * (81 - board.num_empty()) + 2, svg.matches("<text").count());
+        assert_eq!(2 * 20, svg.matches("<line").count());
+    }
+}
@@ -0,0 +1,123 @@
+use alloc::format;
+use alloc::string::String;
+use core::fmt::{self, Display};
+use core::str::FromStr;
+
+/// How the backtracking solver picks the field to guess a value for, see [SearchConfig]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Branching {
+    /// [Branching::FirstEmpty] for classic sudokus, [Branching::FewestCandidates] for sudoku variants.
+    /// For classic sudokus, the simple strategies leave few enough choices that looking for the best field doesn't pay off.
+    #[default]
+    Auto,
+
+    /// The first empty field, column by column
+    FirstEmpty,
+
+    /// The empty field with the fewest possible values
+    FewestCandidates,
+}
+
+impl Branching {
+    pub const ALL: [Branching; 3] = [Branching::Auto, Branching::FirstEmpty, Branching::FewestCandidates];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Branching::Auto => "auto",
+            Branching::FirstEmpty => "first-empty",
+            Branching::FewestCandidates => "fewest-candidates",
+        }
+    }
+}
+
+impl Display for Branching {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Branching {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Branching::ALL
+            .into_iter()
+            .find(|branching| branching.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown branching heuristic '{s}'"))
+    }
+}
+
+/// In which order the backtracking solver tries the possible values of a field, see [SearchConfig]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ValueOrder {
+    #[default]
+    Ascending,
+
+    Descending,
+
+    /// A random order, seeded with [SearchConfig::seed]
+    Random,
+}
+
+impl ValueOrder {
+    pub const ALL: [ValueOrder; 3] = [ValueOrder::Ascending, ValueOrder::Descending, ValueOrder::Random];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueOrder::Ascending => "ascending",
+            ValueOrder::Descending => "descending",
+            ValueOrder::Random => "random",
+        }
+    }
+}
+
+impl Display for ValueOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for ValueOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ValueOrder::ALL
+            .into_iter()
+            .find(|order| order.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown value order '{s}'"))
+    }
+}
+
+/// Heuristics of the backtracking solver, e.g. to compare them on a corpus with `sudoku bench`.
+/// They only change how fast a solution is found, not which solutions exist.
+/// The default is what [solve](crate::solve) uses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SearchConfig {
+    pub branching: Branching,
+
+    pub value_order: ValueOrder,
+
+    /// How often the simple strategies are applied after each guess, as long as they find something.
+    /// With 0, a guess only removes the value from the possible values of its peers.
+    pub propagation_passes: usize,
+
+    /// If set, the search starts over after this many guesses, with twice as many allowed each time.
+    /// This only makes sense with [ValueOrder::Random], which tries a different order after each restart.
+    /// 0 is treated like 1, since the search couldn't make any progress otherwise.
+    pub restart_after: Option<u64>,
+
+    /// Seed for [ValueOrder::Random]
+    pub seed: u64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            branching: Branching::Auto,
+            value_order: ValueOrder::Ascending,
+            propagation_passes: 1,
+            restart_after: None,
+            seed: 0,
+        }
+    }
+}
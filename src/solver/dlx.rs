@@ -0,0 +1,203 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroU8;
+
+use crate::board::{Board, HEIGHT, MAX_VALUE, NUM_FIELDS, WIDTH};
+use crate::geometry::region_of;
+
+/// A solver for classic sudokus using Knuth's Algorithm X with dancing links.
+/// The sudoku is an exact cover problem: each candidate (cell and value) is a row that covers four columns,
+/// the cell, the value in the row, the value in the column and the value in the 3x3 region.
+/// Variant constraints can't be expressed as exact cover, use the backtracking solver for those.
+///
+/// The nodes are stored in vectors and linked by index. Index 0 is the root, followed by one header per column.
+pub struct DlxSolver {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The column header of each node
+    column: Vec<usize>,
+    /// The candidate of each node, encoded as `cell * 9 + value - 1` with the cells numbered row by row
+    candidate: Vec<usize>,
+    /// The number of nodes in each column, indexed by the column header
+    size: Vec<usize>,
+    nodes: u64,
+}
+
+const NUM_COLUMNS: usize = 4 * NUM_FIELDS;
+const ROOT: usize = 0;
+
+impl DlxSolver {
+    /// The board must not have conflicts
+    pub fn new(board: &Board) -> Self {
+        let mut solver = Self {
+            left: (0..=NUM_COLUMNS).map(|index| index.checked_sub(1).unwrap_or(NUM_COLUMNS)).collect(),
+            right: (0..=NUM_COLUMNS).map(|index| (index + 1) % (NUM_COLUMNS + 1)).collect(),
+            up: (0..=NUM_COLUMNS).collect(),
+            down: (0..=NUM_COLUMNS).collect(),
+            column: (0..=NUM_COLUMNS).collect(),
+            candidate: vec![usize::MAX; NUM_COLUMNS + 1],
+            size: vec![0; NUM_COLUMNS + 1],
+            nodes: 0,
+        };
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                match board.field(x, y).get() {
+                    // Given cells only get the row of their value, so the search has to pick it
+                    Some(value) => solver.add_row(x, y, usize::from(value.get()) - 1),
+                    None => (0..usize::from(MAX_VALUE)).for_each(|value| solver.add_row(x, y, value)),
+                }
+            }
+        }
+        solver
+    }
+
+    fn add_row(&mut self, x: usize, y: usize, value: usize) {
+        let region = region_of(x, y);
+        let columns = [
+            y * WIDTH + x,
+            NUM_FIELDS + y * 9 + value,
+            2 * NUM_FIELDS + x * 9 + value,
+            3 * NUM_FIELDS + region * 9 + value,
+        ];
+        let first = self.left.len();
+        for (index, column) in columns.into_iter().enumerate() {
+            // Column headers start at 1 because 0 is the root
+            let header = column + 1;
+            let node = first + index;
+            self.left.push(if index == 0 { first + 3 } else { node - 1 });
+            self.right.push(if index == 3 { first } else { node + 1 });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.column.push(header);
+            self.candidate.push((y * WIDTH + x) * 9 + value);
+            self.size[header] += 1;
+        }
+    }
+
+    fn cover(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+        let mut row = self.down[header];
+        while row != header {
+            let mut node = self.right[row];
+            while node != row {
+                self.down[self.up[node]] = self.down[node];
+                self.up[self.down[node]] = self.up[node];
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut row = self.up[header];
+        while row != header {
+            let mut node = self.left[row];
+            while node != row {
+                self.size[self.column[node]] += 1;
+                self.down[self.up[node]] = node;
+                self.up[self.down[node]] = node;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+        self.right[self.left[header]] = header;
+        self.left[self.right[header]] = header;
+    }
+
+    /// Searches for up to `limit` solutions and returns them
+    pub fn solutions(&mut self, limit: usize) -> Vec<Board> {
+        let mut partial = vec![];
+        let mut solutions = vec![];
+        self.search(&mut partial, &mut solutions, limit);
+        solutions
+            .into_iter()
+            .map(|candidates| {
+                let mut board = Board::new_empty();
+                for candidate in candidates {
+                    let cell = candidate / 9;
+                    let value = NonZeroU8::new((candidate % 9) as u8 + 1);
+                    board.field_mut(cell % WIDTH, cell / WIDTH).set(value);
+                }
+                board
+            })
+            .collect()
+    }
+
+    /// The number of search nodes visited so far
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    fn search(&mut self, partial: &mut Vec<usize>, solutions: &mut Vec<Vec<usize>>, limit: usize) {
+        self.nodes += 1;
+        if self.right[ROOT] == ROOT {
+            solutions.push(partial.clone());
+            return;
+        }
+        // Branch on the column with the fewest remaining rows to keep the search tree small
+        let mut header = self.right[ROOT];
+        let mut best = header;
+        while header != ROOT {
+            if self.size[header] < self.size[best] {
+                best = header;
+            }
+            header = self.right[header];
+        }
+        if self.size[best] == 0 {
+            return;
+        }
+        self.cover(best);
+        let mut row = self.down[best];
+        while row != best && solutions.len() < limit {
+            partial.push(self.candidate[row]);
+            let mut node = self.right[row];
+            while node != row {
+                self.cover(self.column[node]);
+                node = self.right[node];
+            }
+            self.search(partial, solutions, limit);
+            let mut node = self.left[row];
+            while node != row {
+                self.uncover(self.column[node]);
+                node = self.left[node];
+            }
+            partial.pop();
+            row = self.down[row];
+        }
+        self.uncover(best);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve() {
+        let board = Board::from_str(
+            "
+            __4 68_ _19
+            __3 __9 2_5
+            _6_ ___ __4
+
+            6__ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        let solutions = DlxSolver::new(&board).solutions(2);
+        assert_eq!(vec![crate::solver::solve(board).unwrap()], solutions);
+        assert_eq!(2, DlxSolver::new(&Board::new_empty()).solutions(2).len());
+    }
+}
@@ -1,14 +1,37 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::str::FromStr;
+use core::sync::atomic::{AtomicBool, Ordering};
 use thiserror::Error;
 
 use super::board::Board;
+use super::constraints::Constraint;
 
-mod possible_values;
+pub(crate) mod possible_values;
 
+mod config;
+mod dlx;
+#[cfg(all(feature = "std", feature = "parallel"))]
+mod parallel;
+#[allow(clippy::module_inception)]
 mod solver;
 mod strategies;
-use solver::{Generator, Solver};
+#[cfg(feature = "std")]
+pub(crate) use strategies::find_singles;
+pub use config::{Branching, SearchConfig, ValueOrder};
+use dlx::DlxSolver;
+#[cfg(all(feature = "std", feature = "parallel"))]
+pub use parallel::{count_solutions_parallel, solutions_parallel, ParallelSolutions};
+pub use possible_values::PossibleValues;
+pub use solver::{Generator, GuessInOrder, Guesser, Solver};
+#[cfg(feature = "rand")]
+pub use solver::GuessRandomPossibleValue;
+use solver::SolverBuffers;
 
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SolverError {
     #[error("Sudoku is not solvable")]
     NotSolvable,
@@ -18,37 +41,366 @@ pub enum SolverError {
 
     #[error("Sudoku has conflicting entries")]
     Conflicting,
+
+    #[error("Constraint '{0}' is invalid")]
+    InvalidConstraint(Constraint),
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ConstraintsError {
+    #[error("Constraint '{0}' is invalid")]
+    Invalid(Constraint),
+
+    #[error("The combination of constraints doesn't allow any solved sudoku")]
+    Unsatisfiable,
+}
+
+/// [check_constraints] checks up front whether an arbitrary combination of constraints is well-formed and whether
+/// there is at least one solved 9x9 sudoku that fulfills all of them, e.g. before trying to generate a puzzle for it.
+pub fn check_constraints(constraints: &[Constraint]) -> Result<(), ConstraintsError> {
+    if let Some(invalid) = first_invalid(constraints) {
+        return Err(ConstraintsError::Invalid(invalid));
+    }
+    match Solver::new(Board::new_empty(), constraints.to_vec()).next_solution() {
+        Some(_) => Ok(()),
+        None => Err(ConstraintsError::Unsatisfiable),
+    }
+}
+
+/// The first constraint that isn't well-formed, e.g. refers to a cell outside of the board, see [Constraint::is_valid].
+/// The constraint variants have public fields, so everything that takes constraints from a caller checks this before
+/// looking at the cells of a constraint.
+pub(crate) fn first_invalid(constraints: &[Constraint]) -> Option<Constraint> {
+    constraints.iter().find(|constraint| !constraint.is_valid()).copied()
 }
 
 pub fn solve(board: Board) -> Result<Board, SolverError> {
-    if board.has_conflicts() {
-        return Err(SolverError::Conflicting);
-    }
-    let mut solver = Solver::new(board);
-    match solver.next_solution() {
-        None => Err(SolverError::NotSolvable),
-        Some(solution) => {
-            assert!(board.is_subset_of(&solution));
-            if let Some(solution2) = solver.next_solution() {
+    solve_with_constraints(board, &[])
+}
+
+/// Like [solve], but for a sudoku variant that has to fulfill the given constraints in addition to the classic rules.
+pub fn solve_with_constraints(board: Board, constraints: &[Constraint]) -> Result<Board, SolverError> {
+    SolverContext::with_constraints(constraints).solve(board)
+}
+
+/// Like [solve_with_constraints], but gives up and returns `None` once `cancel` is set, e.g. when nobody is waiting
+/// for the result anymore. The flag is checked every thousand guesses.
+pub fn solve_with_cancel(
+    board: Board,
+    constraints: &[Constraint],
+    cancel: &AtomicBool,
+) -> Option<Result<Board, SolverError>> {
+    SolverContext::with_constraints(constraints).search(board, Some(cancel))
+}
+
+/// How many guesses [solve_with_cancel] makes between two checks of its cancel flag
+const CANCEL_CHECK_INTERVAL: u64 = 1000;
+
+/// Why [next_solution_until] stopped before the search was done
+enum Interrupted {
+    NodeLimit,
+    Cancelled,
+}
+
+/// Like [Solver::next_solution], but stops after `max_nodes` guesses in total or once `cancel` is set
+fn next_solution_until(
+    solver: &mut Solver,
+    max_nodes: Option<u64>,
+    cancel: Option<&AtomicBool>,
+) -> Result<Option<Board>, Interrupted> {
+    loop {
+        let pause = cancel.map(|_| solver.nodes() + CANCEL_CHECK_INTERVAL);
+        solver.set_max_nodes(match (pause, max_nodes) {
+            (Some(pause), Some(max_nodes)) => Some(pause.min(max_nodes)),
+            (pause, max_nodes) => pause.or(max_nodes),
+        });
+        let solution = solver.next_solution();
+        if !solver.exceeded_node_limit() {
+            return Ok(solution);
+        }
+        if max_nodes.is_some_and(|max_nodes| solver.nodes() >= max_nodes) {
+            return Err(Interrupted::NodeLimit);
+        }
+        if cancel.is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+            return Err(Interrupted::Cancelled);
+        }
+    }
+}
+
+/// Like [solve_with_constraints], but with the given heuristics, and also returns statistics about the search,
+/// e.g. to compare heuristics on a set of sudokus.
+pub fn solve_with_config(
+    board: Board,
+    constraints: &[Constraint],
+    config: SearchConfig,
+) -> (Result<Board, SolverError>, SolveStats) {
+    let mut context = SolverContext::with_config(constraints, config);
+    let result = context.solve(board);
+    (result, context.stats())
+}
+
+/// A [SolverContext] solves one sudoku after the other and keeps the allocations of the solver between them,
+/// e.g. to check many boards for uniqueness while generating a sudoku.
+pub struct SolverContext {
+    buffers: SolverBuffers,
+    constraints: Vec<Constraint>,
+    config: SearchConfig,
+    stats: SolveStats,
+}
+
+impl SolverContext {
+    pub fn new() -> Self {
+        Self::with_constraints(&[])
+    }
+
+    /// A context for solving sudoku variants that have to fulfill the given constraints in addition to the classic rules
+    pub fn with_constraints(constraints: &[Constraint]) -> Self {
+        Self::with_config(constraints, SearchConfig::default())
+    }
+
+    /// Like [SolverContext::with_constraints], but the solver uses the given heuristics
+    pub fn with_config(constraints: &[Constraint], config: SearchConfig) -> Self {
+        Self {
+            buffers: SolverBuffers::default(),
+            constraints: constraints.to_vec(),
+            config,
+            stats: SolveStats::default(),
+        }
+    }
+
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    pub fn config(&self) -> &SearchConfig {
+        &self.config
+    }
+
+    /// Like [solve_with_constraints] with the constraints of the context
+    pub fn solve(&mut self, board: Board) -> Result<Board, SolverError> {
+        self.search(board, None).expect("A search without a cancel flag can't be cancelled")
+    }
+
+    /// Like [solve_with_cancel] with the constraints of the context
+    pub fn solve_with_cancel(&mut self, board: Board, cancel: &AtomicBool) -> Option<Result<Board, SolverError>> {
+        self.search(board, Some(cancel))
+    }
+
+    fn search(&mut self, board: Board, cancel: Option<&AtomicBool>) -> Option<Result<Board, SolverError>> {
+        if let Some(invalid) = first_invalid(&self.constraints) {
+            return Some(Err(SolverError::InvalidConstraint(invalid)));
+        }
+        if board.has_conflicts_with(&self.constraints) {
+            return Some(Err(SolverError::Conflicting));
+        }
+        let mut config = self.config;
+        let (solution, solution2) = loop {
+            let constraints = core::mem::take(&mut self.constraints);
+            let mut solver = Solver::with_config(board, constraints, config, core::mem::take(&mut self.buffers));
+            // A limit of 0 would give up before the first guess, on every restart
+            let max_nodes = config.restart_after.map(|max_nodes| max_nodes.max(1));
+            let solutions = match next_solution_until(&mut solver, max_nodes, cancel) {
+                Ok(Some(solution)) => {
+                    next_solution_until(&mut solver, max_nodes, cancel).map(|solution2| (Some(solution), solution2))
+                }
+                Ok(None) => Ok((None, None)),
+                Err(interrupted) => Err(interrupted),
+            };
+            self.stats.nodes += solver.nodes();
+            (self.buffers, self.constraints) = solver.into_buffers();
+            match solutions {
+                Ok(solutions) => break solutions,
+                Err(Interrupted::Cancelled) => return None,
+                Err(Interrupted::NodeLimit) => {}
+            }
+            // Start over with a larger budget and, for a random value order, different guesses
+            self.stats.restarts += 1;
+            config.restart_after = config.restart_after.map(|max_nodes| max_nodes.max(1).saturating_mul(2));
+            config.seed = config.seed.wrapping_add(1);
+        };
+        Some(match (solution, solution2) {
+            (None, _) => Err(SolverError::NotSolvable),
+            (Some(solution), Some(solution2)) => {
+                assert!(board.is_subset_of(&solution));
                 assert!(board.is_subset_of(&solution2));
                 assert_ne!(solution, solution2);
                 Err(SolverError::Ambigious)
-            } else {
+            }
+            (Some(solution), None) => {
+                assert!(board.is_subset_of(&solution));
                 assert!(solution.is_filled());
-                assert!(!solution.has_conflicts());
+                assert!(!solution.has_conflicts_with(&self.constraints));
                 Ok(solution)
             }
+        })
+    }
+
+    /// The statistics of all sudokus solved with this context so far
+    pub fn stats(&self) -> SolveStats {
+        self.stats
+    }
+}
+
+impl Default for SolverContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The algorithms [solve_with_backend] can use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// Backtracking with simple strategies applied after each guess. This is what [solve] uses.
+    #[default]
+    Backtracking,
+
+    /// Algorithm X with dancing links
+    Dlx,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 2] = [Backend::Backtracking, Backend::Dlx];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Backtracking => "backtracking",
+            Backend::Dlx => "dlx",
         }
     }
 }
 
+impl Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Backend::ALL
+            .into_iter()
+            .find(|backend| backend.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown backend '{s}'"))
+    }
+}
+
+/// What a backend did to solve a sudoku, see [solve_with_backend]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// The number of nodes in the search tree. For the backtracking backend, this is the number of guesses.
+    pub nodes: u64,
+
+    /// How often the search started over, see [SearchConfig::restart_after]
+    pub restarts: u64,
+}
+
+/// Like [solve], but with the given backend, and also returns statistics about the search, e.g. to compare backends.
+/// The search continues after the first solution to check that it's unique, so the statistics include that.
+pub fn solve_with_backend(board: Board, backend: Backend) -> (Result<Board, SolverError>, SolveStats) {
+    if board.has_conflicts() {
+        return (Err(SolverError::Conflicting), SolveStats::default());
+    }
+    let (solutions, nodes) = match backend {
+        Backend::Backtracking => {
+            let mut solver = Solver::new(board, vec![]);
+            let solutions: Vec<Board> = core::iter::from_fn(|| solver.next_solution()).take(2).collect();
+            (solutions, solver.nodes())
+        }
+        Backend::Dlx => {
+            let mut solver = DlxSolver::new(&board);
+            (solver.solutions(2), solver.nodes())
+        }
+    };
+    let result = match solutions[..] {
+        [] => Err(SolverError::NotSolvable),
+        [solution] => Ok(solution),
+        _ => Err(SolverError::Ambigious),
+    };
+    (result, SolveStats { nodes, ..SolveStats::default() })
+}
+
+/// Counts the solutions of the board with the dancing links backend, but stops at `limit`, since e.g. an empty board
+/// has billions of them. Boards with conflicts have none.
+pub fn count_solutions(board: &Board, limit: usize) -> usize {
+    if board.has_conflicts() {
+        return 0;
+    }
+    DlxSolver::new(board).solutions(limit).len()
+}
+
+/// Returns up to `limit` solutions of the board, which must not have conflicts
+#[cfg(feature = "std")]
+pub(crate) fn find_solutions(board: Board, limit: usize) -> Vec<Board> {
+    solutions(board).take(limit).collect()
+}
+
+/// An iterator over the solutions of a board, see [solutions]
+pub struct Solutions {
+    // `None` if the board has conflicts
+    solver: Option<Solver>,
+}
+
+impl Iterator for Solutions {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        self.solver.as_mut()?.next_solution()
+    }
+}
+
+/// Enumerates the solutions of the board lazily, one search step at a time, e.g. `solutions(board).take(10)` for the
+/// first few solutions of an ambiguous puzzle. Boards with conflicts have no solutions.
+/// For [solutions_with_constraints], neither do boards with an invalid constraint, see [check_constraints].
+pub fn solutions(board: Board) -> Solutions {
+    solutions_with_constraints(board, &[])
+}
+
+/// Like [solutions], but for a sudoku variant that has to fulfill the given constraints in addition to the classic rules
+pub fn solutions_with_constraints(board: Board, constraints: &[Constraint]) -> Solutions {
+    let solvable = first_invalid(constraints).is_none() && !board.has_conflicts_with(constraints);
+    let solver = solvable.then(|| Solver::new(board, constraints.to_vec()));
+    Solutions { solver }
+}
+
+#[cfg(all(feature = "std", feature = "rand"))]
 pub fn generate_solved() -> Board {
-    Generator::new().generate()
+    Generator::new(vec![]).generate()
+}
+
+/// Like [generate_solved], but the generated board also fulfills the given constraints.
+/// Returns an error if a constraint is invalid or the constraints can't be fulfilled, see [check_constraints].
+#[cfg(all(feature = "std", feature = "rand"))]
+pub fn generate_solved_with_constraints(constraints: &[Constraint]) -> Result<Board, ConstraintsError> {
+    if let Some(invalid) = first_invalid(constraints) {
+        return Err(ConstraintsError::Invalid(invalid));
+    }
+    Generator::new(constraints.to_vec()).try_generate().ok_or(ConstraintsError::Unsatisfiable)
+}
+
+/// Like [generate_solved_with_constraints], but all random choices come from the given random number generator.
+/// Unlike the other generating functions, this doesn't need the `std` feature.
+#[cfg(feature = "rand")]
+pub fn generate_solved_with_rng(constraints: &[Constraint], rng: &mut impl rand::Rng) -> Result<Board, ConstraintsError> {
+    if let Some(invalid) = first_invalid(constraints) {
+        return Err(ConstraintsError::Invalid(invalid));
+    }
+    Generator::with_rng(constraints.to_vec(), rng).try_generate().ok_or(ConstraintsError::Unsatisfiable)
+}
+
+/// Like [generate_solved_with_rng] for a classic sudoku, which can't fail
+#[cfg(feature = "rand")]
+pub(crate) fn generate_classic_with_rng(rng: &mut impl rand::Rng) -> Board {
+    Generator::with_rng(vec![], rng).generate()
 }
 
 #[cfg(test)]
 mod tests {
+    use core::num::NonZeroU8;
+
     use super::*;
+    use crate::constraints::{Cage, KropkiDot};
 
     #[test]
     fn solvable_difficult() {
@@ -158,9 +510,157 @@ mod tests {
         assert_eq!(Err(SolverError::Ambigious), actual_solution);
     }
 
+    #[test]
+    fn enumerate_solutions() {
+        let board = Board::from_str(
+            "
+            __4 6__ _19
+            __3 __9 2_5
+            _6_ ___ __4
+
+            6__ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        let all: Vec<Board> = solutions(board).collect();
+        assert_eq!(10, all.len());
+        assert_eq!(all[..3], solutions(board).take(3).collect::<Vec<_>>());
+        assert!(all.iter().all(|solution| board.is_subset_of(solution) && !solution.has_conflicts()));
+        assert_eq!(100, solutions(Board::new_empty()).take(100).count());
+
+        let mut conflicting = Board::new_empty();
+        conflicting.field_mut(0, 0).set(NonZeroU8::new(1));
+        conflicting.field_mut(1, 1).set(NonZeroU8::new(1));
+        assert_eq!(None, solutions(conflicting).next());
+        let mut anti_king = Board::new_empty();
+        anti_king.field_mut(2, 0).set(NonZeroU8::new(1));
+        anti_king.field_mut(3, 1).set(NonZeroU8::new(1));
+        assert!(solutions(anti_king).next().is_some());
+        assert_eq!(None, solutions_with_constraints(anti_king, &[Constraint::AntiKing]).next());
+    }
+
+    #[test]
+    fn backends_agree() {
+        let solvable = Board::from_str(
+            "
+            __4 68_ _19
+            __3 __9 2_5
+            _6_ ___ __4
+
+            6__ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        let mut not_solvable = solvable;
+        not_solvable.field_mut(4, 4).set(std::num::NonZeroU8::new(2));
+        for board in [solvable, not_solvable, Board::new_empty()] {
+            for backend in Backend::ALL {
+                let (result, stats) = solve_with_backend(board, backend);
+                assert_eq!(solve(board), result, "{backend}");
+                assert!(stats.nodes > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn search_configs_agree() {
+        let board = Board::from_str(
+            "
+            __4 68_ _19
+            __3 __9 2_5
+            _6_ ___ __4
+
+            6__ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        let expected = solve(board);
+        for branching in Branching::ALL {
+            for value_order in ValueOrder::ALL {
+                for propagation_passes in [0, 1, 3] {
+                    let config = SearchConfig {
+                        branching,
+                        value_order,
+                        propagation_passes,
+                        ..SearchConfig::default()
+                    };
+                    assert_eq!(expected, solve_with_config(board, &[], config).0, "{config:?}");
+                }
+            }
+        }
+        assert_eq!(Err(SolverError::Ambigious), solve_with_config(Board::new_empty(), &[], SearchConfig::default()).0);
+    }
+
+    #[test]
+    fn restarts() {
+        let config = SearchConfig {
+            value_order: ValueOrder::Random,
+            propagation_passes: 0,
+            restart_after: Some(1),
+            ..SearchConfig::default()
+        };
+        let (result, stats) = solve_with_config(Board::new_empty(), &[], config);
+        assert_eq!(Err(SolverError::Ambigious), result);
+        assert!(stats.restarts > 0);
+    }
+
+    #[test]
+    fn restart_after_zero() {
+        let board = Board::from_str(
+            "
+            53_ _7_ ___
+            6__ 195 ___
+            _98 ___ _6_
+
+            8__ _6_ __3
+            4__ 8_3 __1
+            7__ _2_ __6
+
+            _6_ ___ 28_
+            ___ 419 __5
+            ___ _8_ _79
+        ",
+        );
+        let config = SearchConfig {
+            restart_after: Some(0),
+            ..SearchConfig::default()
+        };
+        let (result, _) = solve_with_config(board, &[], config);
+        assert_eq!(solve(board), result);
+        let (result, _) = solve_with_config(Board::new_empty(), &[], config);
+        assert_eq!(Err(SolverError::Ambigious), result);
+
+        // A search that needs exactly as many guesses as allowed isn't cut short
+        let (_, stats) = solve_with_config(board, &[], SearchConfig::default());
+        assert!(stats.nodes > 0);
+        let config = SearchConfig {
+            restart_after: Some(stats.nodes),
+            ..SearchConfig::default()
+        };
+        let (result, stats) = solve_with_config(board, &[], config);
+        assert_eq!(solve(board), result);
+        assert_eq!(0, stats.restarts);
+    }
+
     // TODO More tests
 
     #[test]
+    #[cfg(all(feature = "std", feature = "rand"))]
     fn generate_solved_100() {
         for _ in 0..100 {
             let solution = generate_solved();
@@ -168,4 +668,217 @@ mod tests {
             assert!(!solution.has_conflicts());
         }
     }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "rand"))]
+    fn generate_solved_with_constraints_10() {
+        for constraints in [
+            vec![Constraint::AntiKnight],
+            vec![Constraint::AntiKing],
+            vec![Constraint::AntiKnight, Constraint::AntiKing],
+            vec![Constraint::Diagonal],
+            vec![Constraint::Windows],
+            vec![Constraint::Diagonal, Constraint::Windows],
+        ] {
+            for _ in 0..10 {
+                let solution = generate_solved_with_constraints(&constraints).unwrap();
+                assert!(solution.is_filled());
+                assert!(!solution.has_conflicts_with(&constraints));
+            }
+        }
+    }
+
+    #[test]
+    fn check_constraints_satisfiable() {
+        assert_eq!(Ok(()), check_constraints(&[]));
+        assert_eq!(Ok(()), check_constraints(&[Constraint::AntiKing, Constraint::NonConsecutive]));
+        assert_eq!(
+            Ok(()),
+            check_constraints(&[Constraint::Diagonal, Constraint::Windows, Constraint::AntiKing])
+        );
+    }
+
+    #[test]
+    fn kropki() {
+        // Only 1 and 2 are both consecutive and one the double of the other
+        let cells = [(4, 4), (4, 5)];
+        let constraints = [
+            Constraint::Kropki { dot: KropkiDot::White, cells },
+            Constraint::Kropki { dot: KropkiDot::Black, cells },
+        ];
+        let solution = solve_with_constraints(Board::new_empty(), &constraints);
+        assert_eq!(Err(SolverError::Ambigious), solution);
+        let solution = solutions_with_constraints(Board::new_empty(), &constraints).next().unwrap();
+        let mut values = cells.map(|(x, y)| solution.field(x, y).get().unwrap().get());
+        values.sort();
+        assert_eq!([1, 2], values);
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn killer() {
+        // Pairs of horizontally adjacent cells with the sums of a solved board. The last column forms a cage of 9.
+        let solved = generate_classic_with_rng(&mut crate::generator::seeded_rng(1144));
+        let value = |x: usize, y: usize| solved.field(x, y).get().unwrap().get();
+        let mut constraints: Vec<Constraint> = (0..9)
+            .flat_map(|y| (0..8).step_by(2).map(move |x| (x, y)))
+            .map(|(x, y)| Constraint::Killer {
+                cage: Cage::new([(x, y), (x + 1, y)]).unwrap(),
+                sum: value(x, y) + value(x + 1, y),
+            })
+            .collect();
+        constraints.push(Constraint::Killer { cage: Cage::new((0..9).map(|y| (8, y))).unwrap(), sum: 45 });
+        assert_eq!(Ok(()), check_constraints(&constraints));
+        for solution in solutions_with_constraints(Board::new_empty(), &constraints).take(10) {
+            assert!(solution.is_filled());
+            assert!(!solution.has_conflicts_with(&constraints));
+        }
+
+        // With the first value of each pair given, the sums determine the rest
+        let mut board = solved;
+        for (x, y) in (0..9).flat_map(|y| (1..9).step_by(2).map(move |x| (x, y))) {
+            board.field_mut(x, y).set(None);
+        }
+        assert_eq!(Ok(solved), solve_with_constraints(board, &constraints));
+        constraints.push(Constraint::Killer { cage: Cage::new([(1, 0)]).unwrap(), sum: value(1, 0) % 9 + 1 });
+        assert_eq!(Err(SolverError::NotSolvable), solve_with_constraints(board, &constraints));
+    }
+
+    #[test]
+    fn solve_cancelled() {
+        // Showing that an empty non-consecutive board is ambiguous takes a few thousand guesses
+        let constraints = [Constraint::NonConsecutive];
+        let board = Board::new_empty();
+        assert_eq!(
+            Some(solve_with_constraints(board, &constraints)),
+            solve_with_cancel(board, &constraints, &AtomicBool::new(false))
+        );
+        assert_eq!(None, solve_with_cancel(board, &constraints, &AtomicBool::new(true)));
+        let config = SearchConfig { restart_after: Some(100), ..SearchConfig::default() };
+        let mut context = SolverContext::with_config(&constraints, config);
+        assert_eq!(Some(Err(SolverError::Ambigious)), context.solve_with_cancel(board, &AtomicBool::new(false)));
+        assert!(context.stats().restarts > 0);
+    }
+
+    #[test]
+    fn check_constraints_unsatisfiable() {
+        assert_eq!(
+            Err(ConstraintsError::Unsatisfiable),
+            check_constraints(&[
+                Constraint::GreaterThan { greater: (0, 0), smaller: (1, 0) },
+                Constraint::GreaterThan { greater: (1, 0), smaller: (0, 0) },
+            ])
+        );
+    }
+
+    #[test]
+    fn check_constraints_invalid() {
+        let invalid = Constraint::GreaterThan { greater: (0, 0), smaller: (0, 9) };
+        assert_eq!(
+            Err(ConstraintsError::Invalid(invalid)),
+            check_constraints(&[Constraint::AntiKing, invalid])
+        );
+    }
+
+    #[test]
+    fn invalid_constraint_is_an_error() {
+        let invalid = Constraint::GreaterThan { greater: (9, 0), smaller: (8, 0) };
+        let constraints = [Constraint::AntiKing, invalid];
+        assert_eq!(
+            Err(SolverError::InvalidConstraint(invalid)),
+            solve_with_constraints(Board::new_empty(), &constraints)
+        );
+        assert_eq!(None, solutions_with_constraints(Board::new_empty(), &constraints).next());
+        #[cfg(all(feature = "std", feature = "rand"))]
+        assert_eq!(Err(ConstraintsError::Invalid(invalid)), generate_solved_with_constraints(&constraints));
+        #[cfg(feature = "rand")]
+        assert_eq!(
+            Err(ConstraintsError::Invalid(invalid)),
+            generate_solved_with_rng(&constraints, &mut crate::generator::seeded_rng(1141))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn generate_unsatisfiable_is_an_error() {
+        let constraints = [
+            Constraint::GreaterThan { greater: (0, 0), smaller: (1, 0) },
+            Constraint::GreaterThan { greater: (1, 0), smaller: (0, 0) },
+        ];
+        assert_eq!(
+            Err(ConstraintsError::Unsatisfiable),
+            generate_solved_with_rng(&constraints, &mut crate::generator::seeded_rng(1141))
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "rand"))]
+    fn solvable_anti_knight() {
+        // Remove every field that keeps the solution unique with the anti-knight constraint. Far fewer clues are
+        // left than a classic sudoku needs, so the puzzle is only unique with the constraint.
+        let constraints = [Constraint::AntiKnight];
+        let solution = generate_solved_with_rng(&constraints, &mut crate::generator::seeded_rng(1134)).unwrap();
+        let mut board = solution;
+        for y in 0..9 {
+            for x in 0..9 {
+                board.field_mut(x, y).set(None);
+                if solve_with_constraints(board, &constraints).is_err() {
+                    board.field_mut(x, y).set(solution.field(x, y).get());
+                }
+            }
+        }
+        assert_eq!(Ok(solution), solve_with_constraints(board, &constraints));
+        assert!(count_solutions(&board, 2) > 1);
+    }
+
+    #[test]
+    fn conflict_anti_king() {
+        let board = Board::from_str(
+            "
+            ___ ___ ___
+            ___ ___ ___
+            __1 ___ ___
+
+            ___ 1__ ___
+            ___ ___ ___
+            ___ ___ ___
+
+            ___ ___ ___
+            ___ ___ ___
+            ___ ___ ___
+        ",
+        );
+        assert_eq!(Err(SolverError::Conflicting), solve_with_constraints(board, &[Constraint::AntiKing]));
+        assert_eq!(Err(SolverError::Ambigious), solve(board));
+    }
+
+    #[test]
+    fn context_reuses_buffers() {
+        let solvable = Board::from_str(
+            "
+            __4 68_ _19
+            __3 __9 2_5
+            _6_ ___ __4
+
+            6__ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        let mut ambiguous = solvable;
+        ambiguous.field_mut(4, 0).set(None);
+        let mut conflicting = solvable;
+        conflicting.field_mut(0, 0).set(std::num::NonZeroU8::new(4));
+
+        let mut context = SolverContext::new();
+        for board in [solvable, ambiguous, conflicting, solvable] {
+            assert_eq!(solve(board), context.solve(board));
+        }
+        let (_, stats) = solve_with_backend(solvable, Backend::Backtracking);
+        assert!(context.stats().nodes >= 2 * stats.nodes);
+    }
 }
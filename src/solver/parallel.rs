@@ -0,0 +1,147 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::Arc;
+use std::thread;
+
+use rayon::{Scope, ThreadPoolBuilder};
+
+use super::possible_values::PossibleValues;
+use super::first_invalid;
+use super::solver::Solver;
+use crate::board::{Board, HEIGHT, WIDTH};
+use crate::constraints::Constraint;
+
+/// How many cells are filled in by splitting the search before a single thread searches the remaining subtree.
+/// Each split creates a task per possible value, so idle threads can steal subtrees from busy ones.
+const SPLIT_DEPTH: usize = 4;
+
+/// How many solutions can wait in the channel before the search threads block until they are consumed
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// An iterator over the solutions found by [solutions_parallel], in no particular order.
+/// Dropping it stops the search.
+pub struct ParallelSolutions {
+    receiver: Receiver<Board>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Iterator for ParallelSolutions {
+    type Item = Board;
+
+    fn next(&mut self) -> Option<Board> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl Drop for ParallelSolutions {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Enumerates all solutions of the board on all cores and streams them as they are found.
+///
+/// The search space is partitioned by filling in the cells with the fewest possible values, one value per task, so
+/// the partitions are disjoint and each solution is returned exactly once. The search runs on its own thread pool,
+/// so a slow consumer blocks the search instead of the global rayon pool. Boards with conflicts or an invalid
+/// constraint have no solutions.
+pub fn solutions_parallel(board: Board, constraints: &[Constraint]) -> ParallelSolutions {
+    let (sender, receiver) = sync_channel(CHANNEL_CAPACITY);
+    let stop = Arc::new(AtomicBool::new(false));
+    if first_invalid(constraints).is_none() && !board.has_conflicts_with(constraints) {
+        let constraints = constraints.to_vec();
+        let stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            let pool = ThreadPoolBuilder::new().build().expect("Creating a thread pool can't fail");
+            pool.scope(|scope| split(scope, board, &constraints, SPLIT_DEPTH, &sender, &stop));
+        });
+    }
+    ParallelSolutions { receiver, stop }
+}
+
+/// Like [count_solutions](super::count_solutions), but searches on all cores, see [solutions_parallel]
+pub fn count_solutions_parallel(board: &Board, limit: usize) -> usize {
+    solutions_parallel(*board, &[]).take(limit).count()
+}
+
+fn split<'scope>(
+    scope: &Scope<'scope>,
+    mut board: Board,
+    constraints: &'scope [Constraint],
+    depth: usize,
+    sender: &'scope SyncSender<Board>,
+    stop: &'scope AtomicBool,
+) {
+    if stop.load(Ordering::Relaxed) {
+        return;
+    }
+    if depth == 0 {
+        let mut solver = Solver::new(board, constraints.to_vec());
+        while let Some(solution) = solver.next_solution() {
+            if stop.load(Ordering::Relaxed) || sender.send(solution).is_err() {
+                stop.store(true, Ordering::Relaxed);
+                return;
+            }
+        }
+        return;
+    }
+    let possible_values = PossibleValues::from_board(&board, constraints);
+    let empty_cells = (0..HEIGHT).flat_map(|y| (0..WIDTH).map(move |x| (x, y)));
+    let Some((x, y)) = empty_cells
+        .filter(|&(x, y)| board.field(x, y).is_empty())
+        .min_by_key(|&(x, y)| possible_values.candidate_count(x, y))
+    else {
+        // Only possible values were filled in, so the full board is a solution
+        if sender.send(board).is_err() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        return;
+    };
+    for value in possible_values.possible_values_for_field(x, y) {
+        board.field_mut(x, y).set(Some(value));
+        scope.spawn(move |scope| split(scope, board, constraints, depth - 1, sender, stop));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::num::NonZeroU8;
+
+    use super::*;
+    use crate::solver::find_solutions;
+
+    #[test]
+    fn same_solutions_as_sequential() {
+        let board = Board::from_str(
+            "
+            ___ 6__ _1_
+            __3 ___ 2__
+            _6_ ___ __4
+
+            6__ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        let sequential = find_solutions(board, usize::MAX);
+        assert!(sequential.len() > 10);
+        let parallel: Vec<Board> = solutions_parallel(board, &[]).collect();
+        assert_eq!(sequential.len(), parallel.len());
+        let sequential: HashSet<Board> = sequential.into_iter().collect();
+        assert_eq!(sequential, parallel.into_iter().collect());
+    }
+
+    #[test]
+    fn count() {
+        assert_eq!(1000, count_solutions_parallel(&Board::new_empty(), 1000));
+        let mut conflicting = Board::new_empty();
+        conflicting.field_mut(0, 0).set(NonZeroU8::new(1));
+        conflicting.field_mut(1, 0).set(NonZeroU8::new(1));
+        assert_eq!(0, count_solutions_parallel(&conflicting, 1000));
+    }
+}
@@ -1,45 +1,235 @@
-use bitvec::prelude::*;
-use std::num::NonZeroU8;
+use alloc::vec::Vec;
+use core::num::NonZeroU8;
+use core::ops::RangeInclusive;
 
-use crate::board::{Board, HEIGHT, NUM_FIELDS, WIDTH};
+use crate::bitboard::ALL_FIELDS;
+use crate::board::{Board, HEIGHT, MAX_VALUE, NUM_FIELDS, WIDTH};
+use crate::constraints::{Cage, Constraint};
+use crate::geometry::{field_in_region, peers, position_in_region, region_of};
 
-const NUM_VALUES_PER_FIELD: usize = 9;
+/// A mask with the bits of all values 1..=9 set
+const ALL_VALUES: u16 = (1 << MAX_VALUE) - 1;
 
-#[derive(Clone, Copy)]
+/// A mask with the bits of all 9 positions in a house set
+const ALL_POSITIONS: u16 = (1 << 9) - 1;
+
+/// The number of classic houses: 9 rows, followed by 9 columns, followed by 9 3x3 regions.
+/// Regions are numbered left-to-right, then top-to-bottom, and their cells the same way.
+pub const NUM_HOUSES: usize = 27;
+
+/// The values that are still possible in each field of a board, as far as the solver knows. A [Guesser] gets this to
+/// choose the value to try next.
+///
+/// [Guesser]: super::Guesser
+#[derive(Clone)]
 pub struct PossibleValues {
-    // Stores 9 bits for each cell. If the bit is set, the value is considered possible.
-    values: BitArr!(for NUM_FIELDS*NUM_VALUES_PER_FIELD),
+    // One mask per cell, bit `value - 1` is set if the value is considered possible.
+    // Cells are stored column by column like in [Board]. Filled cells don't have any possible values.
+    masks: [u16; NUM_FIELDS],
+
+    // For each classic house and value, bit `i` is set if the value is possible in the `i`-th cell of the house.
+    // This is the same information as in `masks`, but lets hidden singles be found without looking at every cell.
+    positions: [[u16; MAX_VALUE as usize]; NUM_HOUSES],
+
+    // For each classic house, bit `value - 1` is set if the value was placed in the house
+    placed: [u16; NUM_HOUSES],
+
+    // For each value, bit `x * HEIGHT + y` is set if the value is possible in the field (x, y).
+    // Again the same information as in `masks`, but whole rows and columns can be compared with a few shifts.
+    digits: [u128; MAX_VALUE as usize],
+
+    // Bit `house` is set if the possible values in the classic house changed since it was last marked clean.
+    // A house that didn't change can't have new hidden singles, so the strategies can skip it.
+    dirty: u32,
+
+    // If enabled with [PossibleValues::start_journal], every change is recorded here so it can be undone with
+    // [PossibleValues::rollback]. This lets the solver backtrack without keeping a copy per branching point.
+    journal: Option<Journal>,
+}
+
+/// The changes recorded by [PossibleValues] after [PossibleValues::start_journal]. It can be taken back with
+/// [PossibleValues::take_journal] to reuse its allocation.
+#[derive(Clone, Default)]
+pub struct Journal(Vec<Undo>);
+
+/// A point in the journal of [PossibleValues] to roll back to, see [PossibleValues::checkpoint]
+#[derive(Clone, Copy, Debug)]
+pub struct Checkpoint {
+    journal_len: usize,
+    dirty: u32,
+}
+
+/// An entry in the journal of [PossibleValues]
+#[derive(Clone, Copy)]
+enum Undo {
+    /// The values in `removed` were removed from the field with the given index
+    Removed { index: u8, removed: u16 },
+
+    /// The value was placed in the field with the given index
+    Placed { index: u8, value: NonZeroU8 },
 }
 
 impl PossibleValues {
-    pub const fn new_all_is_possible() -> Self {
+    pub(crate) const fn new_all_is_possible() -> Self {
         Self {
-            values: bitarr![const 1; NUM_FIELDS*NUM_VALUES_PER_FIELD],
+            masks: [ALL_VALUES; NUM_FIELDS],
+            positions: [[ALL_POSITIONS; MAX_VALUE as usize]; NUM_HOUSES],
+            placed: [0; NUM_HOUSES],
+            digits: [ALL_FIELDS; MAX_VALUE as usize],
+            dirty: (1 << NUM_HOUSES) - 1,
+            journal: None,
         }
     }
 
-    pub fn from_board(board: &Board) -> PossibleValues {
+    /// The values that don't conflict with any of the filled fields of the board, under the classic rules and the
+    /// given constraints
+    pub fn from_board(board: &Board, constraints: &[Constraint]) -> PossibleValues {
         let mut possible_values = PossibleValues::new_all_is_possible();
         for x in 0..WIDTH {
             for y in 0..HEIGHT {
                 let field = board.field(x, y);
                 if let Some(value) = field.get() {
-                    possible_values.remove_conflicting(x, y, value);
+                    possible_values.remove_conflicting(board, x, y, value, constraints);
                 }
             }
         }
+        // Placing a value only restricts the cage it's in, so cages without values need to be restricted up front
+        for constraint in constraints {
+            if let Constraint::Killer { cage, sum } = *constraint {
+                possible_values.restrict_cage(board, cage, sum);
+            }
+        }
         possible_values
     }
 
-    fn field_start_index(x: usize, y: usize) -> usize {
-        assert!(x <= WIDTH && y <= HEIGHT);
-        NUM_VALUES_PER_FIELD * (x * HEIGHT + y)
+    fn field_index(x: usize, y: usize) -> usize {
+        assert!(x < WIDTH && y < HEIGHT);
+        x * HEIGHT + y
+    }
+
+    fn bit(value: NonZeroU8) -> u16 {
+        assert!(value.get() <= MAX_VALUE);
+        1 << (value.get() - 1)
+    }
+
+    /// The classic houses of the field, each with the position of the field in it
+    fn houses_of(x: usize, y: usize) -> [(usize, usize); 3] {
+        [(y, x), (HEIGHT + x, y), (HEIGHT + WIDTH + region_of(x, y), position_in_region(x, y))]
+    }
+
+    /// The field at the given position of a classic house, see [NUM_HOUSES]
+    pub(crate) fn field_in_house(house: usize, position: usize) -> (usize, usize) {
+        assert!(house < NUM_HOUSES && position < 9);
+        match house {
+            row if row < HEIGHT => (position, row),
+            column if column < HEIGHT + WIDTH => (column - HEIGHT, position),
+            region => field_in_region(region - HEIGHT - WIDTH, position),
+        }
+    }
+
+    /// The positions in the classic house where the value is possible, bit `i` stands for the `i`-th cell of the house
+    pub(crate) fn positions_in_house(&self, house: usize, value: NonZeroU8) -> u16 {
+        self.positions[house][usize::from(value.get() - 1)]
+    }
+
+    /// True if the possible values in the classic house changed since [PossibleValues::mark_clean] was last called for it
+    pub(crate) fn is_dirty(&self, house: usize) -> bool {
+        self.dirty & (1 << house) != 0
+    }
+
+    pub(crate) fn mark_clean(&mut self, house: usize) {
+        self.dirty &= !(1 << house);
+    }
+
+    /// True if the value was already placed in the classic house
+    pub(crate) fn is_placed_in_house(&self, house: usize, value: NonZeroU8) -> bool {
+        self.placed[house] & Self::bit(value) != 0
+    }
+
+    /// The fields where the value is possible as a bitboard, see [crate::bitboard]
+    pub(crate) fn fields_with_value(&self, value: NonZeroU8) -> u128 {
+        self.digits[usize::from(value.get() - 1)]
+    }
+
+    /// Removes the values in `bits` from the field and keeps the house positions up to date
+    fn clear(&mut self, x: usize, y: usize, bits: u16) {
+        let index = Self::field_index(x, y);
+        let mask = &mut self.masks[index];
+        let mut removed = *mask & bits;
+        *mask &= !bits;
+        if let (Some(journal), true) = (&mut self.journal, removed != 0) {
+            journal.0.push(Undo::Removed {
+                index: index as u8,
+                removed,
+            });
+        }
+        while removed != 0 {
+            let value_index = removed.trailing_zeros() as usize;
+            removed &= removed - 1;
+            self.digits[value_index] &= !(1 << index);
+            for (house, position) in Self::houses_of(x, y) {
+                self.positions[house][value_index] &= !(1 << position);
+                self.dirty |= 1 << house;
+            }
+        }
+    }
+
+    /// Starts recording all changes in the given journal, so they can be undone with [PossibleValues::rollback].
+    /// Anything already in the journal is discarded, only its allocation is reused.
+    pub(crate) fn start_journal(&mut self, mut journal: Journal) {
+        journal.0.clear();
+        self.journal = Some(journal);
+    }
+
+    /// Stops recording changes and returns the journal
+    pub(crate) fn take_journal(&mut self) -> Journal {
+        self.journal.take().unwrap_or_default()
+    }
+
+    /// The current state, to pass to [PossibleValues::rollback] later
+    pub(crate) fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            journal_len: self.journal.as_ref().expect("Journal wasn't started").0.len(),
+            dirty: self.dirty,
+        }
+    }
+
+    /// Undoes all changes since the checkpoint was taken. Values placed with [PossibleValues::remove_conflicting]
+    /// since then are also removed from the board.
+    pub(crate) fn rollback(&mut self, checkpoint: Checkpoint, board: &mut Board) {
+        let journal = self.journal.as_mut().expect("Journal wasn't started");
+        self.dirty = checkpoint.dirty;
+        for undo in journal.0.drain(checkpoint.journal_len..).rev() {
+            match undo {
+                Undo::Removed { index, removed } => {
+                    let index = usize::from(index);
+                    let (x, y) = (index / HEIGHT, index % HEIGHT);
+                    self.masks[index] |= removed;
+                    let mut restored = removed;
+                    while restored != 0 {
+                        let value_index = restored.trailing_zeros() as usize;
+                        restored &= restored - 1;
+                        self.digits[value_index] |= 1 << index;
+                        for (house, position) in Self::houses_of(x, y) {
+                            self.positions[house][value_index] |= 1 << position;
+                        }
+                    }
+                }
+                Undo::Placed { index, value } => {
+                    let index = usize::from(index);
+                    let (x, y) = (index / HEIGHT, index % HEIGHT);
+                    for (house, _) in Self::houses_of(x, y) {
+                        self.placed[house] &= !Self::bit(value);
+                    }
+                    board.field_mut(x, y).set(None);
+                }
+            }
+        }
     }
 
-    fn index(x: usize, y: usize, value: NonZeroU8) -> usize {
-        assert!(value.get() <= 9);
-        let start_index = Self::field_start_index(x, y);
-        start_index + usize::from(value.get()) - 1
+    /// The possible values of the field as a mask where bit `value - 1` is set if the value is possible
+    pub(crate) fn mask(&self, x: usize, y: usize) -> u16 {
+        self.masks[Self::field_index(x, y)]
     }
 
     pub fn possible_values_for_field(
@@ -47,58 +237,283 @@ impl PossibleValues {
         x: usize,
         y: usize,
     ) -> impl Iterator<Item = NonZeroU8> + '_ {
-        let start_index = Self::field_start_index(x, y);
-        (1u8..=9u8)
-            .filter(move |i| self.values[start_index + usize::from(*i) - 1])
-            .map(|i| NonZeroU8::new(i).unwrap())
+        let mut mask = self.mask(x, y);
+        core::iter::from_fn(move || {
+            let value = lowest_value(mask)?;
+            mask &= mask - 1;
+            Some(value)
+        })
+    }
+
+    /// The number of possible values of the field
+    pub fn candidate_count(&self, x: usize, y: usize) -> usize {
+        self.mask(x, y).count_ones() as usize
+    }
+
+    /// The `n`-th possible value of the field in ascending order, i.e. what `possible_values_for_field(x, y).nth(n)` returns
+    pub fn nth_candidate(&self, x: usize, y: usize, n: usize) -> Option<NonZeroU8> {
+        let mut mask = self.mask(x, y);
+        for _ in 0..n {
+            mask &= mask.checked_sub(1)?;
+        }
+        lowest_value(mask)
     }
 
     pub fn first_possible_value_for_field(&self, x: usize, y: usize) -> Option<NonZeroU8> {
-        // TODO Faster with bit operations that find the first set bit in one assembly instruction?
-        self.possible_values_for_field(x, y).next()
+        lowest_value(self.mask(x, y))
+    }
+
+    pub fn last_possible_value_for_field(&self, x: usize, y: usize) -> Option<NonZeroU8> {
+        let mask = self.mask(x, y);
+        NonZeroU8::new((u16::BITS - mask.leading_zeros()) as u8)
     }
 
-    // TODO Test
     pub fn is_possible(&self, x: usize, y: usize, value: NonZeroU8) -> bool {
-        let index = Self::index(x, y, value);
-        self.values[index]
+        self.mask(x, y) & Self::bit(value) != 0
     }
 
-    // TODO Test
-    pub fn remove(&mut self, x: usize, y: usize, value: NonZeroU8) {
-        let index = Self::index(x, y, value);
-        assert!(self.values[index]);
-        self.values.set(index, false);
+    pub(crate) fn remove(&mut self, x: usize, y: usize, value: NonZeroU8) {
+        assert!(self.is_possible(x, y, value));
+        self.remove_if_set(x, y, value);
     }
 
-    fn remove_if_set(&mut self, x: usize, y: usize, value: NonZeroU8) {
-        let index = Self::index(x, y, value);
-        self.values.set(index, false);
+    pub(crate) fn remove_if_set(&mut self, x: usize, y: usize, value: NonZeroU8) {
+        self.clear(x, y, Self::bit(value));
     }
 
-    pub fn remove_conflicting(&mut self, x: usize, y: usize, value: NonZeroU8) {
-        self.remove_value_from_col(value, x);
-        self.remove_value_from_row(value, y);
-        self.remove_value_from_region(value, x / 3, y / 3);
+    /// Places the value, which must already be set on the board, and removes the values that conflict with it.
+    pub(crate) fn remove_conflicting(
+        &mut self,
+        board: &Board,
+        x: usize,
+        y: usize,
+        value: NonZeroU8,
+        constraints: &[Constraint],
+    ) {
+        self.clear(x, y, ALL_VALUES);
+        for (house, _) in Self::houses_of(x, y) {
+            self.placed[house] |= Self::bit(value);
+            self.dirty |= 1 << house;
+        }
+        if let Some(journal) = &mut self.journal {
+            journal.0.push(Undo::Placed {
+                index: Self::field_index(x, y) as u8,
+                value,
+            });
+        }
+        for (peer_x, peer_y) in peers(x, y) {
+            self.remove_if_set(peer_x, peer_y, value);
+        }
+        for constraint in constraints {
+            self.remove_value_from_constraint_peers(board, constraint, value, x, y);
+        }
     }
 
-    fn remove_value_from_col(&mut self, value: NonZeroU8, x: usize) {
-        for y in 0..HEIGHT {
-            self.remove_if_set(x, y, value);
+    fn remove_value_from_constraint_peers(
+        &mut self,
+        board: &Board,
+        constraint: &Constraint,
+        value: NonZeroU8,
+        x: usize,
+        y: usize,
+    ) {
+        for (peer_x, peer_y) in constraint.peers(x, y) {
+            self.remove_if_set(peer_x, peer_y, value);
+        }
+        match *constraint {
+            Constraint::GreaterThan { greater, smaller } => {
+                if (x, y) == greater {
+                    self.remove_values_in_range(smaller.0, smaller.1, value.get()..=MAX_VALUE);
+                } else if (x, y) == smaller {
+                    self.remove_values_in_range(greater.0, greater.1, 1..=value.get());
+                }
+            }
+            Constraint::NonConsecutive => {
+                for (neighbor_x, neighbor_y) in Constraint::orthogonal_neighbors(x, y) {
+                    if value.get() > 1 {
+                        self.remove_if_set(neighbor_x, neighbor_y, NonZeroU8::new(value.get() - 1).unwrap());
+                    }
+                    if value.get() < MAX_VALUE {
+                        self.remove_if_set(neighbor_x, neighbor_y, NonZeroU8::new(value.get() + 1).unwrap());
+                    }
+                }
+            }
+            Constraint::Kropki { dot, cells } => {
+                if let Some(&(other_x, other_y)) = cells.iter().find(|&&cell| cell != (x, y)) {
+                    if cells.contains(&(x, y)) {
+                        let impossible = (1..=MAX_VALUE)
+                            .filter(|&other| !dot.allows(value.get(), other))
+                            .fold(0, |mask, other| mask | Self::bit(NonZeroU8::new(other).unwrap()));
+                        self.clear(other_x, other_y, impossible);
+                    }
+                }
+            }
+            Constraint::Killer { cage, sum } => {
+                // The peers above keep the values in the cage different
+                if cage.contains((x, y)) {
+                    self.restrict_cage(board, cage, sum);
+                }
+            }
+            Constraint::AntiKnight | Constraint::AntiKing | Constraint::Diagonal | Constraint::Windows => {
+                // Fully handled by the peers above
+            }
         }
     }
 
-    fn remove_value_from_row(&mut self, value: NonZeroU8, y: usize) {
-        for x in 0..WIDTH {
-            self.remove_if_set(x, y, value);
+    /// Removes the values from the empty cells of the cage that can't be completed to the sum with different values
+    /// that aren't in the cage yet. A cell whose value is the last one missing in the cage is left with that value only.
+    fn restrict_cage(&mut self, board: &Board, cage: Cage, sum: u8) {
+        let (mut unused, mut remaining, mut empty) = (ALL_VALUES, i32::from(sum), 0);
+        for (x, y) in cage.cells() {
+            match board.field(x, y).get() {
+                Some(value) => {
+                    unused &= !Self::bit(value);
+                    remaining -= i32::from(value.get());
+                }
+                None => empty += 1,
+            }
+        }
+        for (x, y) in cage.cells().filter(|&(x, y)| board.field(x, y).is_empty()) {
+            let impossible = (1..=MAX_VALUE).filter_map(NonZeroU8::new).filter(|&value| {
+                let others = unused & !Self::bit(value);
+                let rest = remaining - i32::from(value.get());
+                match sums_of_values(others, empty - 1) {
+                    Some((lowest, highest)) => !(lowest..=highest).contains(&rest),
+                    None => true,
+                }
+            });
+            let impossible = impossible.fold(0, |mask, value| mask | Self::bit(value));
+            self.clear(x, y, impossible);
         }
     }
 
-    fn remove_value_from_region(&mut self, value: NonZeroU8, cell_x: usize, cell_y: usize) {
-        for x in 0..3 {
-            for y in 0..3 {
-                self.remove_if_set(3 * cell_x + x, 3 * cell_y + y, value);
+    /// Removes all values in the given range from the possible values of the field and returns true if any of them were possible before.
+    pub(crate) fn remove_values_in_range(
+        &mut self,
+        x: usize,
+        y: usize,
+        range: RangeInclusive<u8>,
+    ) -> bool {
+        let range_mask = range
+            .filter_map(NonZeroU8::new)
+            .fold(0, |mask, value| mask | Self::bit(value));
+        let removed_something = self.mask(x, y) & range_mask != 0;
+        self.clear(x, y, range_mask);
+        removed_something
+    }
+}
+
+/// The sums of the `count` lowest and of the `count` highest values whose bits are set in the mask,
+/// or `None` if there are fewer values
+fn sums_of_values(mask: u16, count: usize) -> Option<(i32, i32)> {
+    if (mask.count_ones() as usize) < count {
+        return None;
+    }
+    let values = || (1..=MAX_VALUE).filter(move |value| mask & 1 << (value - 1) != 0).map(i32::from);
+    Some((values().take(count).sum(), values().rev().take(count).sum()))
+}
+
+/// The lowest value whose bit is set in the mask
+fn lowest_value(mask: u16) -> Option<NonZeroU8> {
+    (mask != 0).then(|| NonZeroU8::new(mask.trailing_zeros() as u8 + 1).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitboard::fields_of_bitboard;
+
+    fn value(value: u8) -> NonZeroU8 {
+        NonZeroU8::new(value).unwrap()
+    }
+
+    #[test]
+    fn remove_values() {
+        let mut possible_values = PossibleValues::new_all_is_possible();
+        assert_eq!(0b111111111, possible_values.mask(8, 8));
+        possible_values.remove(8, 8, value(1));
+        possible_values.remove_if_set(8, 8, value(1));
+        assert!(!possible_values.is_possible(8, 8, value(1)));
+        assert!(possible_values.is_possible(8, 7, value(1)));
+        assert!(possible_values.remove_values_in_range(8, 8, 7..=9));
+        assert!(!possible_values.remove_values_in_range(8, 8, 8..=9));
+        assert_eq!(
+            vec![2, 3, 4, 5, 6],
+            possible_values.possible_values_for_field(8, 8).map(|value| value.get()).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(value(2)), possible_values.first_possible_value_for_field(8, 8));
+        assert_eq!(Some(value(6)), possible_values.last_possible_value_for_field(8, 8));
+        assert_eq!(5, possible_values.candidate_count(8, 8));
+        assert_eq!(Some(value(2)), possible_values.nth_candidate(8, 8, 0));
+        assert_eq!(Some(value(6)), possible_values.nth_candidate(8, 8, 4));
+        assert_eq!(None, possible_values.nth_candidate(8, 8, 5));
+        assert_eq!(None, possible_values.nth_candidate(8, 8, 20));
+        assert_eq!(0b111110, possible_values.mask(8, 8));
+        possible_values.remove_values_in_range(8, 8, 1..=9);
+        assert_eq!(None, possible_values.first_possible_value_for_field(8, 8));
+        assert_eq!(None, possible_values.last_possible_value_for_field(8, 8));
+    }
+
+    #[test]
+    fn house_masks_match_cells() {
+        let mut possible_values = PossibleValues::new_all_is_possible();
+        let mut board = Board::new_empty();
+        board.field_mut(4, 4).set(Some(value(5)));
+        possible_values.remove_conflicting(&board, 4, 4, value(5), &[]);
+        possible_values.remove(0, 0, value(5));
+        possible_values.remove_values_in_range(7, 1, 1..=3);
+        for house in 0..NUM_HOUSES {
+            for value in (1..=MAX_VALUE).filter_map(NonZeroU8::new) {
+                let expected = (0..9).filter(|&position| {
+                    let (x, y) = PossibleValues::field_in_house(house, position);
+                    possible_values.is_possible(x, y, value)
+                });
+                assert_eq!(
+                    expected.fold(0, |mask, position| mask | 1 << position),
+                    possible_values.positions_in_house(house, value),
+                    "house {house}, value {value}"
+                );
             }
         }
+        for value in (1..=MAX_VALUE).filter_map(NonZeroU8::new) {
+            let expected = (0..WIDTH)
+                .flat_map(|x| (0..HEIGHT).map(move |y| (x, y)))
+                .filter(|&(x, y)| possible_values.is_possible(x, y, value));
+            assert_eq!(
+                expected.collect::<Vec<_>>(),
+                fields_of_bitboard(possible_values.fields_with_value(value)).collect::<Vec<_>>()
+            );
+        }
+        assert!(possible_values.is_placed_in_house(4, value(5)));
+        assert!(possible_values.is_placed_in_house(HEIGHT + 4, value(5)));
+        assert!(possible_values.is_placed_in_house(HEIGHT + WIDTH + 4, value(5)));
+        assert!(!possible_values.is_placed_in_house(0, value(5)));
+        assert_eq!(0, possible_values.mask(4, 4));
+        assert_eq!((5, 7), PossibleValues::field_in_house(HEIGHT + WIDTH + 7, 5));
+    }
+
+    #[test]
+    fn rollback() {
+        let mut board = Board::new_empty();
+        board.field_mut(0, 0).set(Some(value(1)));
+        let mut possible_values = PossibleValues::from_board(&board, &[]);
+        possible_values.start_journal(Journal::default());
+        possible_values.remove(5, 5, value(3));
+        let checkpoint = possible_values.checkpoint();
+        let before = (possible_values.clone(), board);
+
+        board.field_mut(4, 4).set(Some(value(2)));
+        possible_values.remove_conflicting(&board, 4, 4, value(2), &[Constraint::AntiKnight]);
+        possible_values.remove_values_in_range(8, 8, 1..=5);
+        assert!(!possible_values.is_possible(4, 5, value(2)));
+        possible_values.rollback(checkpoint, &mut board);
+
+        assert_eq!(before.1, board);
+        assert_eq!(before.0.masks, possible_values.masks);
+        assert_eq!(before.0.positions, possible_values.positions);
+        assert_eq!(before.0.placed, possible_values.placed);
+        assert_eq!(before.0.digits, possible_values.digits);
+        assert!(!possible_values.is_possible(5, 5, value(3)));
+        assert_eq!(checkpoint.journal_len, possible_values.checkpoint().journal_len);
     }
 }
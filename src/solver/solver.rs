@@ -1,148 +1,366 @@
-use std::num::NonZeroU8;
-use rand::{seq::SliceRandom, rngs::ThreadRng, thread_rng};
+use alloc::vec::Vec;
+use core::num::NonZeroU8;
+#[cfg(feature = "rand")]
+use rand::Rng;
+#[cfg(all(feature = "std", feature = "rand"))]
+use rand::{rngs::ThreadRng, thread_rng};
 
 use super::{
-    possible_values::PossibleValues,
-    strategies::{solve_simple_strategies, SimpleSolverResult},
+    config::{Branching, SearchConfig, ValueOrder},
+    possible_values::{Checkpoint, Journal, PossibleValues},
+    strategies::solve_simple_strategies,
 };
-use crate::board::Board;
+use crate::board::{Board, HEIGHT, WIDTH};
+use crate::constraints::Constraint;
+use crate::utils::SplitMix64;
 
-pub struct Solver {
-    solver_impl: SolverImpl<GuessFirstPossibleValue>,
+/// A [Solver] enumerates the solutions of a board one by one. By default, it guesses values as configured in the
+/// [SearchConfig], but any [Guesser] can be plugged in with [Solver::with_guesser].
+pub struct Solver<G: Guesser = GuessInOrder> {
+    solver_impl: SolverImpl<G>,
 }
 
 impl Solver {
-    pub fn new(board: Board) -> Self {
+    pub fn new(board: Board, constraints: Vec<Constraint>) -> Self {
+        Self::with_config(board, constraints, SearchConfig::default(), SolverBuffers::default())
+    }
+
+    /// Like [Solver::new], but with the given heuristics, and reusing the allocations of a previous solver,
+    /// see [Solver::into_buffers]
+    pub(crate) fn with_config(
+        board: Board,
+        constraints: Vec<Constraint>,
+        config: SearchConfig,
+        buffers: SolverBuffers,
+    ) -> Self {
+        let guesser = GuessInOrder {
+            order: config.value_order,
+            rng: SplitMix64(config.seed),
+        };
+        let mut solver_impl = SolverImpl::with_buffers(board, constraints, guesser, buffers, &config);
+        // A limit of 0 would give up before the first guess, on every restart
+        solver_impl.max_nodes = config.restart_after.map(|max_nodes| max_nodes.max(1));
+        Self { solver_impl }
+    }
+}
+
+impl<G: Guesser> Solver<G> {
+    /// Like [Solver::new], but the guesser chooses which value to try first whenever the solver has to guess.
+    /// The order only changes in which order the solutions are found, unless the guesser skips values.
+    pub fn with_guesser(board: Board, constraints: Vec<Constraint>, guesser: G) -> Self {
         Self {
-            solver_impl: SolverImpl::new(board, GuessFirstPossibleValue),
+            solver_impl: SolverImpl::new(board, constraints, guesser),
         }
     }
 
+    /// True if the solver stopped early because it needed more guesses than [SearchConfig::restart_after] allows.
+    /// Otherwise, [Solver::next_solution] returning `None` means that there are no more solutions.
+    pub fn exceeded_node_limit(&self) -> bool {
+        self.solver_impl.exceeded_node_limit()
+    }
+
+    /// Lets a solver that exceeded its node limit continue where it stopped, until it made `max_nodes` guesses in
+    /// total. Without a limit, it searches until [Solver::next_solution] returns.
+    pub(crate) fn set_max_nodes(&mut self, max_nodes: Option<u64>) {
+        self.solver_impl.max_nodes = max_nodes;
+        self.solver_impl.gave_up = false;
+    }
+
+    /// Returns the allocations of the solver and its constraints, to reuse them for the next board
+    pub(crate) fn into_buffers(self) -> (SolverBuffers, Vec<Constraint>) {
+        self.solver_impl.into_buffers()
+    }
+
     pub fn next_solution(&mut self) -> Option<Board> {
         self.solver_impl.next_solution()
     }
+
+    /// The number of guesses made so far, i.e. the number of nodes in the search tree
+    pub fn nodes(&self) -> u64 {
+        self.solver_impl.nodes
+    }
+}
+
+/// A [Generator] generates a solved board by solving an empty board with random guesses, or with the guesses of any
+/// [Guesser], see [Generator::with_guesser].
+pub struct Generator<G: Guesser> {
+    solver_impl: SolverImpl<G>,
+}
+
+#[cfg(all(feature = "std", feature = "rand"))]
+impl Generator<GuessRandomPossibleValue<ThreadRng>> {
+    pub fn new(constraints: Vec<Constraint>) -> Self {
+        Self::with_rng(constraints, thread_rng())
+    }
 }
 
-pub struct Generator {
-    solver_impl: SolverImpl<GuessRandomPossibleValue>,
+#[cfg(feature = "rand")]
+impl <R: Rng> Generator<GuessRandomPossibleValue<R>> {
+    /// Like [Generator::new], but the random guesses come from the given random number generator.
+    /// With a seeded generator, this always generates the same board.
+    pub fn with_rng(constraints: Vec<Constraint>, rng: R) -> Self {
+        Self::with_guesser(constraints, GuessRandomPossibleValue { rng })
+    }
 }
 
-impl Generator {
-    pub fn new() -> Self {
+impl<G: Guesser> Generator<G> {
+    /// Like [Generator::new], but the guesser chooses the values, e.g. to bias which solved boards are generated.
+    /// A guesser that doesn't pick randomly always generates the same board.
+    pub fn with_guesser(constraints: Vec<Constraint>, guesser: G) -> Self {
         Self {
-            solver_impl: SolverImpl::new(Board::new_empty(), GuessRandomPossibleValue { rng: thread_rng() }),
+            solver_impl: SolverImpl::new(Board::new_empty(), constraints, guesser),
         }
     }
 
     // We're taking `self` by value because this should only be called once. If we call `solver_impl.next_solution` multiple times,
     // the two solutions would be very similar.
-    pub fn generate(mut self) -> Board {
-        self.solver_impl.next_solution().expect("An empty sudoku must have at least one solution")
+    pub fn generate(self) -> Board {
+        self.try_generate().expect("An empty sudoku must have at least one solution")
+    }
+
+    /// Like [Generator::generate], but returns `None` instead of panicking if the constraints can't be fulfilled
+    pub fn try_generate(mut self) -> Option<Board> {
+        self.solver_impl.next_solution()
     }
 }
 
-/// A [Guesser] can be used to parameterize a [SolverImpl] so that it either guesses the first possible value for a field, or a random one.
+/// A [Guesser] decides which value the [Solver] or [Generator] tries next when it has to guess, e.g. the first
+/// possible value for a field, or a random one.
 /// Guessing random values is useful for generating new sudokus by running the solver on an empty sudoku with random guesses.
 /// For solving a given sudoku, guessing the first possible value is faster.
-trait Guesser {
+pub trait Guesser {
+    /// Returns one of the possible values of the empty field (x, y), or `None` to give up on the field, which makes the
+    /// solver backtrack. Values that were already tried at this point of the search aren't possible anymore, so
+    /// returning `None` while there are possible values left skips solutions.
+    ///
+    /// Panics in the solver if the value isn't possible.
     fn guess_value(&mut self, possible_values: &PossibleValues, x: usize, y: usize) -> Option<NonZeroU8>;
 }
 
-struct GuessFirstPossibleValue;
-impl Guesser for GuessFirstPossibleValue {
+/// Guesses the values in the order of [SearchConfig::value_order]. This is what [Solver::new] uses.
+pub struct GuessInOrder {
+    order: ValueOrder,
+    // Only used for [ValueOrder::Random]
+    rng: SplitMix64,
+}
+impl Guesser for GuessInOrder {
     fn guess_value(&mut self, possible_values: &PossibleValues, x: usize, y: usize) -> Option<NonZeroU8> {
-        possible_values.first_possible_value_for_field(x, y)
+        match self.order {
+            ValueOrder::Ascending => possible_values.first_possible_value_for_field(x, y),
+            ValueOrder::Descending => possible_values.last_possible_value_for_field(x, y),
+            ValueOrder::Random => {
+                let count = possible_values.candidate_count(x, y);
+                if count == 0 {
+                    return None;
+                }
+                let index = (self.rng.next() % count as u64) as usize;
+                possible_values.nth_candidate(x, y, index)
+            }
+        }
     }
 }
 
-struct GuessRandomPossibleValue {
-    rng: ThreadRng,
+/// Guesses a random possible value, see [Generator::with_rng]
+#[cfg(feature = "rand")]
+pub struct GuessRandomPossibleValue<R: Rng> {
+    rng: R,
 }
-impl Guesser for GuessRandomPossibleValue {
+#[cfg(feature = "rand")]
+impl <R: Rng> Guesser for GuessRandomPossibleValue<R> {
     fn guess_value(&mut self, possible_values: &PossibleValues, x: usize, y: usize) -> Option<NonZeroU8> {
-        // TODO Do this without first collecting into Vec. Should be possible if the iterator is ExactSizeIterator.
-        let values: Vec<NonZeroU8> = possible_values.possible_values_for_field(x, y).collect();
-        values.choose(&mut self.rng).copied()
+        // This draws from the rng exactly like choosing from a slice of the possible values would,
+        // so seeded generation keeps producing the same sudokus
+        let count = possible_values.candidate_count(x, y);
+        if count == 0 {
+            return None;
+        }
+        let index = self.rng.gen_range(0..count as u32) as usize;
+        possible_values.nth_candidate(x, y, index)
     }
 }
 
 struct SolverImpl<G: Guesser> {
-    // [board_stack] contains all the branching points after any given guess, with any simple strategies already applied to add additional deterministic fields.
-    // At any point, we can find more solutions by taking the top from the stack and applying more guesses, until we get to a fully solved sudoku.
-    // When a fully solved sudoku is found, or there are no possible solutions for the top board of the stack, then we should backtrack by removing the top board from the stack.
+    // [board] and [possible_values] are the state of the branching point we're currently exploring, i.e. the board after
+    // the last guess with any simple strategies already applied to add additional deterministic fields.
+    // At any point, we can find more solutions by applying more guesses, until we get to a fully solved sudoku.
+    // When a fully solved sudoku is found, or there are no possible solutions for the current branching point, then we
+    // should backtrack to the branching point before it.
+    // The possible values are equivalent to [PossibleValues::from_board](board), with the difference that we removed values
+    // we already guessed to create previous solutions. This ensures we don't guess the same value again after backtracking.
+    board: Board,
+    possible_values: PossibleValues,
+
+    // [stack] contains a journal checkpoint of [possible_values] for each branching point from the initial board to the
+    // current one. Rolling back to the checkpoint of the current branching point restores the branching point before it,
+    // so we only store the changes each guess caused instead of a copy of the board and possible values per guess.
     // If the stack is empty, then there are no more solutions left.
-    // The PossibleValues part of the tuple is equivalent to [PossibleValues::from_board](board), with the difference that we removed values we already guessed to
-    // create previous solutions. This ensures we don't guess the same value again after backtracking.
-    board_stack: Vec<(Board, PossibleValues)>,
+    stack: Vec<Checkpoint>,
+
+    // Additional rules of the sudoku variant we're solving. Empty for classic sudokus.
+    constraints: Vec<Constraint>,
 
     guesser: G,
+
+    // See [SearchConfig]
+    branching: Branching,
+    propagation_passes: usize,
+
+    // Number of guesses made so far
+    nodes: u64,
+
+    // If set, the solver gives up after this many guesses
+    max_nodes: Option<u64>,
+
+    // True if the solver gave up because of [max_nodes] while there was still something left to search
+    gave_up: bool,
+}
+
+/// The allocations of a solver that can be reused for solving the next board, see [Solver::with_buffers]
+#[derive(Default)]
+pub struct SolverBuffers {
+    stack: Vec<Checkpoint>,
+    journal: Journal,
 }
 
 impl <G: Guesser> SolverImpl<G> {
-    pub fn new(board: Board, guesser: G) -> Self {
-        let possible_values = PossibleValues::from_board(&board);
+    pub fn new(board: Board, constraints: Vec<Constraint>, guesser: G) -> Self {
+        Self::with_buffers(board, constraints, guesser, SolverBuffers::default(), &SearchConfig::default())
+    }
+
+    pub fn with_buffers(
+        board: Board,
+        constraints: Vec<Constraint>,
+        guesser: G,
+        buffers: SolverBuffers,
+        config: &SearchConfig,
+    ) -> Self {
+        let SolverBuffers { mut stack, journal } = buffers;
+        stack.clear();
+        let mut possible_values = PossibleValues::from_board(&board, &constraints);
+        possible_values.start_journal(journal);
         let mut res = Self {
-            board_stack: vec![],
+            board,
+            possible_values,
+            stack,
+            constraints,
             guesser,
+            branching: config.branching,
+            propagation_passes: config.propagation_passes,
+            nodes: 0,
+            max_nodes: None,
+            gave_up: false,
         };
-        res.push(board, possible_values);
+        let checkpoint = res.possible_values.checkpoint();
+        res.push(checkpoint);
         res
     }
 
-    fn push(&mut self, board: Board, possible_values: PossibleValues) {
-        match solve_simple_strategies(board, possible_values) {
-            SimpleSolverResult::FoundSomething {
-                board: new_board,
-                possible_values: new_possible_values,
-            } => {
-                debug_assert!(board.is_subset_of(&new_board));
-                self.board_stack.push((new_board, new_possible_values));
-            }
-            SimpleSolverResult::FoundNothing => {
-                self.board_stack.push((board, possible_values));
+    pub fn into_buffers(mut self) -> (SolverBuffers, Vec<Constraint>) {
+        let buffers = SolverBuffers {
+            stack: self.stack,
+            journal: self.possible_values.take_journal(),
+        };
+        (buffers, self.constraints)
+    }
+
+    /// Applies the simple strategies to the current board, which was just changed after the checkpoint was taken.
+    /// If that shows that the board isn't solvable, the changes are rolled back instead of adding a branching point.
+    fn push(&mut self, checkpoint: Checkpoint) {
+        for _ in 0..self.propagation_passes {
+            match solve_simple_strategies(&mut self.board, &mut self.possible_values, &self.constraints) {
+                Some(true) => {}
+                Some(false) => break,
+                None => {
+                    self.possible_values.rollback(checkpoint, &mut self.board);
+                    return;
+                }
             }
-            SimpleSolverResult::NotSolvable => {
-                // This board is not solvable. Don't even add it.
+        }
+        self.stack.push(checkpoint);
+    }
+
+    fn exceeded_node_limit(&self) -> bool {
+        self.gave_up
+    }
+
+    /// Backtracks to the branching point before the current one
+    fn pop(&mut self) {
+        let checkpoint = self.stack.pop().unwrap();
+        self.possible_values.rollback(checkpoint, &mut self.board);
+    }
+
+    /// Returns the empty field we should guess a value for next, or `None` if the board is filled.
+    /// For classic sudokus, the simple strategies leave few enough choices that we just take the first empty field by default.
+    /// Variant constraints mostly add eliminations that the simple strategies can't turn into placements, so there
+    /// we guess the field with the fewest possible values to keep the search tree small.
+    fn field_to_guess(&self, board: &Board, possible_values: &PossibleValues) -> Option<(usize, usize)> {
+        let first_empty = match self.branching {
+            Branching::Auto => self.constraints.is_empty(),
+            Branching::FirstEmpty => true,
+            Branching::FewestCandidates => false,
+        };
+        if first_empty {
+            return board.first_empty_field_index();
+        }
+        let mut best: Option<((usize, usize), usize)> = None;
+        for x in 0..WIDTH {
+            for y in 0..HEIGHT {
+                if board.field(x, y).is_empty() {
+                    let num_possible = possible_values.possible_values_for_field(x, y).count();
+                    if best.is_none_or(|(_, best_num_possible)| num_possible < best_num_possible) {
+                        best = Some(((x, y), num_possible));
+                        if num_possible <= 1 {
+                            return Some((x, y));
+                        }
+                    }
+                }
             }
         }
+        best.map(|(field, _)| field)
     }
 
     pub fn next_solution(&mut self) -> Option<Board> {
-        let Some((board, possible_values)) = self.board_stack.last() else {
-            // No more solutions left
-            return None;
-        };
-        let board = *board;
-        let possible_values = *possible_values;
-        match board.first_empty_field_index() {
-            None => {
-                // No empty fields left. The sudoku is fully solved.
-                self.board_stack.pop().unwrap();
-                return Some(board);
+        // This is a loop instead of a recursion because sudoku variants with additional constraints
+        // can require enough backtracking to overflow the call stack.
+        loop {
+            if self.stack.is_empty() {
+                // No more solutions left
+                return None;
             }
-            Some((x, y)) => {
-                match self.guesser.guess_value(&possible_values, x, y) {
-                    None => {
-                        // No possible values left for this field. This means that the board on top doesn't have any more solutions.
-                        // Remove it and continue guessing for boards below it.
-                        self.board_stack.pop().unwrap();
-                        return self.next_solution();
-                    }
-                    Some(value) => {
-                        // Remove this from the possible values of the *current* board so we don't try it again after backtracking to this stack entry
-                        self.board_stack.last_mut().unwrap().1.remove(x, y, value);
-
-                        // Make a guess for the value of this field
-                        let mut board = board;
-                        let mut field = board.field_mut(x, y);
-                        assert!(field.is_empty());
-                        field.set(Some(value));
-                        debug_assert!(!board.has_conflicts());
-                        let mut new_possible_values = possible_values;
-                        new_possible_values.remove_conflicting(x, y, value);
-                        self.push(board, new_possible_values);
-
-                        return self.next_solution();
+            match self.field_to_guess(&self.board, &self.possible_values) {
+                None => {
+                    // No empty fields left. The sudoku is fully solved.
+                    let solution = self.board;
+                    self.pop();
+                    return Some(solution);
+                }
+                Some((x, y)) => {
+                    match self.guesser.guess_value(&self.possible_values, x, y) {
+                        None => {
+                            // No possible values left for this field. This means that the current branching point doesn't have any more solutions.
+                            // Backtrack and continue guessing for the branching points before it.
+                            self.pop();
+                        }
+                        Some(value) => {
+                            if self.max_nodes.is_some_and(|max_nodes| self.nodes >= max_nodes) {
+                                // There may be more solutions, but we're not allowed to guess any further
+                                self.gave_up = true;
+                                return None;
+                            }
+
+                            // Remove this from the possible values of the *current* branching point so we don't try it again after backtracking to it
+                            self.possible_values.remove(x, y, value);
+
+                            // Make a guess for the value of this field
+                            self.nodes += 1;
+                            let checkpoint = self.possible_values.checkpoint();
+                            let mut field = self.board.field_mut(x, y);
+                            assert!(field.is_empty());
+                            field.set(Some(value));
+                            debug_assert!(!self.board.has_conflicts_with(&self.constraints));
+                            self.possible_values.remove_conflicting(&self.board, x, y, value, &self.constraints);
+                            self.push(checkpoint);
+                        }
                     }
                 }
             }
@@ -171,7 +389,7 @@ mod tests {
             _2_ _91 ___
         ",
         );
-        let mut solver = Solver::new(board);
+        let mut solver = Solver::new(board, vec![]);
         let mut solutions = vec![];
         while let Some(solution) = solver.next_solution() {
             assert!(solution.is_filled());
@@ -187,5 +405,45 @@ mod tests {
         assert_eq!(10, solutions.len());
     }
 
+    /// Guesses the highest possible value and counts the guesses
+    struct GuessHighest(usize);
+    impl Guesser for GuessHighest {
+        fn guess_value(&mut self, possible_values: &PossibleValues, x: usize, y: usize) -> Option<NonZeroU8> {
+            self.0 += 1;
+            possible_values.last_possible_value_for_field(x, y)
+        }
+    }
+
+    #[test]
+    fn custom_guesser() {
+        let board = Board::from_str(
+            "
+            __4 6__ _19
+            __3 __9 2_5
+            _6_ ___ __4
+
+            6__ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        let mut solver = Solver::new(board, vec![]);
+        let first = solver.next_solution().unwrap();
+        let mut custom = Solver::with_guesser(board, vec![], GuessHighest(0));
+        let custom_first = custom.next_solution().unwrap();
+        assert_ne!(first, custom_first);
+        assert!(custom.solver_impl.guesser.0 > 0);
+        assert_eq!(10, 1 + core::iter::from_fn(|| custom.next_solution()).count());
+
+        let generated = Generator::with_guesser(vec![], GuessHighest(0)).generate();
+        assert!(generated.is_filled());
+        assert!(!generated.has_conflicts());
+        assert_eq!(generated, Generator::with_guesser(vec![], GuessHighest(0)).generate());
+    }
+
     // TODO More tests, including generating based on half-solved sudokus
 }
@@ -1,30 +1,62 @@
-use std::num::NonZeroU8;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::num::NonZeroU8;
 
-use super::possible_values::PossibleValues;
+use super::possible_values::{PossibleValues, NUM_HOUSES};
 use crate::board::{Board, HEIGHT, MAX_VALUE, WIDTH};
+use crate::constraints::Constraint;
 
-pub enum SimpleSolverResult {
-    FoundSomething {
-        board: Board,
-        possible_values: PossibleValues,
-    },
-    FoundNothing,
-    NotSolvable,
+/// [solve_simple_strategies] tries some fast strategies to add values on the board that can easily be deduced from other values.
+/// It changes the board and possible values in place and returns
+/// - `Some(true)` if it found something
+/// - `Some(false)` if it found nothing, the board and possible values are unchanged then
+/// - `None` if the board is unsolvable. The board and possible values may be partially changed then.
+pub fn solve_simple_strategies(
+    board: &mut Board,
+    possible_values: &mut PossibleValues,
+    constraints: &[Constraint],
+) -> Option<bool> {
+    let found_inequalities = solve_inequalities(board, possible_values, constraints)?;
+    let found_hidden_candidates = solve_hidden_candidates(board, possible_values, constraints)?;
+    Some(found_inequalities || found_hidden_candidates)
 }
 
-/// [solve_simple_strategies] tries some fast strategies to add values on the board that can easily be deduced from other values.
+/// [solve_inequalities] narrows down the possible values of empty cells connected by a greater-than sign.
+/// The greater cell must be larger than the smallest possible value of the smaller cell, and the smaller cell must be
+/// smaller than the largest possible value of the greater cell. This is repeated until the intervals don't change anymore.
 /// It returns
-pub fn solve_simple_strategies(
-    mut board: Board,
-    mut possible_values: PossibleValues,
-) -> SimpleSolverResult {
-    match solve_hidden_candidates(&mut board, &mut possible_values) {
-        Some(true) => SimpleSolverResult::FoundSomething {
-            board,
-            possible_values,
-        },
-        Some(false) => SimpleSolverResult::FoundNothing,
-        None => return SimpleSolverResult::NotSolvable,
+/// - `Some(true)` if it removed some possible values
+/// - `Some(false)` if it found nothing
+/// - `None` if the board is unsolvable
+fn solve_inequalities(
+    board: &Board,
+    possible_values: &mut PossibleValues,
+    constraints: &[Constraint],
+) -> Option<bool> {
+    let mut found_something = false;
+    loop {
+        let mut changed = false;
+        for constraint in constraints {
+            let Constraint::GreaterThan { greater, smaller } = *constraint else {
+                continue;
+            };
+            if !board.field(greater.0, greater.1).is_empty() || !board.field(smaller.0, smaller.1).is_empty() {
+                // Signs next to filled cells were already handled when the value was placed
+                continue;
+            }
+            let max_greater = possible_values.last_possible_value_for_field(greater.0, greater.1)?;
+            let min_smaller = possible_values.first_possible_value_for_field(smaller.0, smaller.1)?;
+            if possible_values.remove_values_in_range(smaller.0, smaller.1, max_greater.get()..=MAX_VALUE) {
+                changed = true;
+            }
+            if possible_values.remove_values_in_range(greater.0, greater.1, 1..=min_smaller.get()) {
+                changed = true;
+            }
+        }
+        if !changed {
+            return Some(found_something);
+        }
+        found_something = true;
     }
 }
 
@@ -36,33 +68,104 @@ pub fn solve_simple_strategies(
 fn solve_hidden_candidates(
     board: &mut Board,
     possible_values: &mut PossibleValues,
+    constraints: &[Constraint],
 ) -> Option<bool> {
     let mut found_something = false;
 
-    // Check each row for values that can only be placed in one field
-    for row in 0u8..HEIGHT as u8 {
-        let cells = (0u8..WIDTH as u8).map(|x| (x, row));
-        if _solve_hidden_candidates(board, possible_values, cells)? {
+    // Check each row, then each col, then each 3x3 region for values that can only be placed in one field.
+    // Regions are checked column by column. Houses whose possible values didn't change since they were last checked
+    // can't have new hidden candidates, so only the dirty ones are checked. Placements mark the houses of their peers
+    // dirty again, the ones we already passed are checked by the next call.
+    let regions = (0..3).flat_map(|region_x| (0..3).map(move |region_y| HEIGHT + WIDTH + 3 * region_y + region_x));
+    for house in (0..HEIGHT + WIDTH).chain(regions) {
+        if !possible_values.is_dirty(house) {
+            continue;
+        }
+        possible_values.mark_clean(house);
+        if _solve_hidden_candidates_in_house(board, possible_values, constraints, house)? {
             found_something = true;
         }
     }
 
-    // Check each col for values that can only be placed in one field
-    for col in 0u8..WIDTH as u8 {
-        let cells = (0u8..HEIGHT as u8).map(|y| (col, y));
-        if _solve_hidden_candidates(board, possible_values, cells)? {
-            found_something = true;
+    // Check each house added by the constraints, e.g. diagonals
+    for constraint in constraints {
+        for house in constraint.houses() {
+            let cells = house.iter().map(|&(x, y)| (x as u8, y as u8));
+            if _solve_hidden_candidates(board, possible_values, constraints, cells)? {
+                found_something = true;
+            }
+        }
+    }
+
+    Some(found_something)
+}
+
+/// [find_singles] returns the naked singles (empty cells with only one possible value) and hidden singles (values with only
+/// one possible cell in a row, column or 3x3 region) of the board, without placing them. Unlike [solve_simple_strategies],
+/// this doesn't look for singles that only appear after placing other singles.
+/// Each cell appears at most once, with the value found first.
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+pub fn find_singles(board: &Board, possible_values: &PossibleValues) -> Vec<(usize, usize, NonZeroU8)> {
+    let mut singles: Vec<(usize, usize, NonZeroU8)> = vec![];
+    let mut add = |x: usize, y: usize, value: NonZeroU8| {
+        if !singles.iter().any(|&(single_x, single_y, _)| (single_x, single_y) == (x, y)) {
+            singles.push((x, y, value));
+        }
+    };
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            if board.field(x, y).is_empty() {
+                let mut values = possible_values.possible_values_for_field(x, y);
+                if let (Some(value), None) = (values.next(), values.next()) {
+                    add(x, y, value);
+                }
+            }
         }
     }
+    for house in 0..NUM_HOUSES {
+        for value in (1..=MAX_VALUE).filter_map(NonZeroU8::new) {
+            if possible_values.is_placed_in_house(house, value) {
+                continue;
+            }
+            let positions = possible_values.positions_in_house(house, value);
+            if positions.count_ones() == 1 {
+                let (x, y) = PossibleValues::field_in_house(house, positions.trailing_zeros() as usize);
+                add(x, y, value);
+            }
+        }
+    }
+    singles
+}
+
+/// Like [_solve_hidden_candidates], but for a classic house (see [NUM_HOUSES]), using the position masks
+/// of [PossibleValues] instead of looking at each field of the house.
+#[must_use]
+fn _solve_hidden_candidates_in_house(
+    board: &mut Board,
+    possible_values: &mut PossibleValues,
+    constraints: &[Constraint],
+    house: usize,
+) -> Option<bool> {
+    let mut found_something = false;
 
-    // Check each 3x3 region for values that can only be placed in one field
-    for region_x in 0u8..3u8 {
-        for region_y in 0u8..3u8 {
-            let cells = (0u8..3u8)
-                .flat_map(move |x| (0u8..3u8).map(move |y| (region_x * 3 + x, region_y * 3 + y)));
-            if _solve_hidden_candidates(board, possible_values, cells)? {
+    for value in (1..=MAX_VALUE).filter_map(NonZeroU8::new) {
+        if possible_values.is_placed_in_house(house, value) {
+            continue;
+        }
+        let positions = possible_values.positions_in_house(house, value);
+        match positions.count_ones() {
+            // We found no place where we can put this value
+            0 => return None,
+            1 => {
+                // We found exactly one place where we can put this value
+                let (x, y) = PossibleValues::field_in_house(house, positions.trailing_zeros() as usize);
+                debug_assert!(board.field(x, y).is_empty());
+                board.field_mut(x, y).set(Some(value));
+                possible_values.remove_conflicting(board, x, y, value, constraints);
                 found_something = true;
+                debug_assert!(!board.has_conflicts_with(constraints));
             }
+            _ => {}
         }
     }
 
@@ -73,6 +176,7 @@ fn solve_hidden_candidates(
 fn _solve_hidden_candidates(
     board: &mut Board,
     possible_values: &mut PossibleValues,
+    constraints: &[Constraint],
     field_coords: impl Iterator<Item = (u8, u8)> + Clone,
 ) -> Option<bool> {
     let mut found_something = false;
@@ -103,9 +207,9 @@ fn _solve_hidden_candidates(
             let x = x as usize;
             let y = y as usize;
             board.field_mut(x, y).set(Some(value));
-            possible_values.remove_conflicting(x, y, value);
+            possible_values.remove_conflicting(board, x, y, value, constraints);
             found_something = true;
-            debug_assert!(!board.has_conflicts());
+            debug_assert!(!board.has_conflicts_with(constraints));
         } else {
             // We found no place where we can put this value
             return None;
@@ -0,0 +1,123 @@
+//! Distributions over a collection of puzzles, e.g. to compare puzzle sources or to check a generator's output.
+
+use std::collections::{BTreeMap, HashSet};
+
+use crate::board::Board;
+use crate::generator::Symmetry;
+use crate::hints::Technique;
+use crate::rating::{rate_batch, Difficulty};
+
+/// The result of [analyze].
+///
+/// With the `serde` feature, this can be exported, e.g. as JSON.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CorpusStats {
+    pub num_puzzles: usize,
+
+    /// Puzzles that can be transformed into an earlier puzzle, see [Board::fingerprint]
+    pub num_duplicates: usize,
+
+    /// Puzzles without a unique solution. They aren't part of [CorpusStats::difficulties] and [CorpusStats::techniques].
+    pub num_invalid: usize,
+
+    /// Puzzles the hint engine can't solve without guessing
+    pub num_requiring_guessing: usize,
+
+    /// How many puzzles have each number of given fields
+    pub clues: BTreeMap<usize, usize>,
+
+    pub difficulties: BTreeMap<Difficulty, usize>,
+
+    /// How often the hint engine needs each technique, for techniques used at least once
+    pub techniques: BTreeMap<Technique, TechniqueStats>,
+
+    /// How many puzzles have a pattern of given fields with each symmetry. A pattern can have several symmetries,
+    /// so this counts it for each of them. Only puzzles without any symmetry count for [Symmetry::None].
+    pub symmetries: BTreeMap<Symmetry, usize>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TechniqueStats {
+    /// The number of puzzles that need the technique at least once
+    pub puzzles: usize,
+
+    /// The number of steps that use the technique, over all puzzles
+    pub steps: usize,
+}
+
+/// Rates all puzzles and collects their distributions
+pub fn analyze(puzzles: impl IntoIterator<Item = Board>) -> CorpusStats {
+    let puzzles: Vec<Board> = puzzles.into_iter().collect();
+    let reports = rate_batch(puzzles.iter().copied());
+
+    let mut stats = CorpusStats {
+        num_puzzles: puzzles.len(),
+        ..CorpusStats::default()
+    };
+    let mut seen = HashSet::new();
+    for puzzle in &puzzles {
+        if !seen.insert(puzzle.fingerprint()) {
+            stats.num_duplicates += 1;
+        }
+        *stats.clues.entry(puzzle.num_filled()).or_default() += 1;
+        let symmetries: Vec<Symmetry> = Symmetry::ALL[1..]
+            .iter()
+            .copied()
+            .filter(|symmetry| symmetry.matches(puzzle))
+            .collect();
+        if symmetries.is_empty() {
+            *stats.symmetries.entry(Symmetry::None).or_default() += 1;
+        }
+        for symmetry in symmetries {
+            *stats.symmetries.entry(symmetry).or_default() += 1;
+        }
+    }
+    for report in reports {
+        let Ok(report) = report else {
+            stats.num_invalid += 1;
+            continue;
+        };
+        if report.requires_guessing {
+            stats.num_requiring_guessing += 1;
+        }
+        *stats.difficulties.entry(report.difficulty).or_default() += 1;
+        for (technique, count) in report.technique_counts() {
+            let technique_stats = stats.techniques.entry(technique).or_default();
+            technique_stats.puzzles += 1;
+            technique_stats.steps += count;
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canonical::canonical_form;
+    use crate::formats::parse_line;
+
+    const PUZZLES: [&str; 3] = [
+        ".3.1..2..9..8.74........9.6...56.....123....4........1..4....5.79......2.2..45..9",
+        "62.4......1.7..9..3..5....1.......3....2.174...6.3..5....98.5..........8.75......",
+        ".1.......4..8.2.1.......849....2.69.1.9.743.......6.....6...7..7.4.8....8.......5",
+    ];
+
+    #[test]
+    fn analyze_puzzles() {
+        let puzzles: Vec<Board> = PUZZLES.iter().map(|puzzle| parse_line(puzzle).unwrap()).collect();
+        let stats = analyze(puzzles.iter().copied().chain([canonical_form(&puzzles[0]), Board::new_empty()]));
+        assert_eq!(5, stats.num_puzzles);
+        assert_eq!(1, stats.num_duplicates);
+        assert_eq!(1, stats.num_invalid);
+        assert_eq!(Some(&1), stats.clues.get(&0));
+        assert_eq!(Some(&2), stats.clues.get(&25));
+        assert_eq!(Some(&2), stats.difficulties.get(&Difficulty::Easy));
+        assert_eq!(4, stats.difficulties.values().sum::<usize>());
+        assert_eq!(4, stats.techniques[&Technique::HiddenSingle].puzzles);
+        // The empty board has every symmetry
+        assert!(Symmetry::ALL[1..].iter().all(|symmetry| stats.symmetries[symmetry] >= 1));
+        assert!(stats.symmetries.values().sum::<usize>() >= 5);
+    }
+}
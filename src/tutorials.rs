@@ -0,0 +1,150 @@
+//! Hand-picked positions that demonstrate each [Technique] in isolation, e.g. for trainer UIs or documentation.
+//! In each position, the technique is the easiest logical step, so it's exactly what [hint](crate::hint) finds.
+//!
+//! Most positions were taken from generated puzzles at the point where the solver needed the technique,
+//! so they have a unique solution.
+
+use crate::board::Board;
+use crate::constraints::{parse_constraints, Constraint};
+use crate::formats::parse_line;
+use crate::hints::{hint, Step, Technique};
+
+/// The technique, the board in the line format and the constraints in the format of [parse_constraints]
+const EXAMPLES: [(Technique, &str, &str); 18] = [
+    (
+        Technique::HiddenSingle,
+        "..89...2..6..5..8.5.....4...5.6....3..9.348..3....2.....6............9.7..2.46...",
+        "",
+    ),
+    (
+        Technique::HiddenSingle,
+        "...9....45.3....2.......8.51.854...3......5......6..8.9.6.873.......3........4169",
+        "",
+    ),
+    (
+        Technique::NakedSingle,
+        "17....5...5...4.67.6.5738.....9.5378..7.....5895..7..49.675..8.581...7..7.2.89.5.",
+        "",
+    ),
+    (
+        Technique::NakedSingle,
+        "812.7..........21.56...248...6..5...48..9.....5.7.....32.....68..89..5....52..7.4",
+        "",
+    ),
+    (
+        Technique::GreaterThanBounds,
+        ".................................................................................",
+        "greater-than r1c1>r1c2\ngreater-than r1c2>r1c3",
+    ),
+    (
+        Technique::NonConsecutiveNeighbors,
+        ".6.........................42.....6.85...................................4......8",
+        "non-consecutive",
+    ),
+    (
+        Technique::NonConsecutiveNeighbors,
+        "........58......2.................1...2.....................................7....",
+        "non-consecutive",
+    ),
+    (
+        Technique::KropkiDots,
+        ".................................................................................",
+        "black-dot r1c1-r1c2",
+    ),
+    (
+        Technique::LockedCandidates,
+        "..89...2.26.45..8959....4...546.92.3629.348..3..5.2694..6.95.42..5...9679.2.46..8",
+        "",
+    ),
+    (
+        Technique::LockedCandidates,
+        "8.2.7..........21.56...248...6..5...48..9.....5.7.....32.....68..89..5....52..7.4",
+        "",
+    ),
+    (
+        Technique::VariantPeers,
+        "6.....1.......2....4.....5..8.......2....5..3......6...1....7.............9....6.",
+        "anti-knight",
+    ),
+    (
+        Technique::VariantPeers,
+        "......73....2....8...1......2..........4......6....1..2........59....6.........9.",
+        "anti-knight",
+    ),
+    (
+        Technique::NakedPair,
+        "42.395..1713.6.95.9..1.7..4631.....9...671483847932615..4719..61..8.3.4737...619.",
+        "",
+    ),
+    (
+        Technique::NakedPair,
+        "756.4.921342719685981.2.4731.84375..593..174..74.9.138.3.17.8.441....2.78.7.5431.",
+        "",
+    ),
+    (
+        Technique::XWing,
+        "95...2....2.3.49.583.659..131542.6.9269.....4748936152.8.....9..9.2.....47..93..8",
+        "",
+    ),
+    (
+        Technique::XWing,
+        "8..6132..5..4.7..6...9.5...2..7361.81872.436....8.14279..3685...581796.2..1542...",
+        "",
+    ),
+    (
+        Technique::Swordfish,
+        "365.78...89.2.567327.6935..912....6565.9.17..74.526..1436...25.589362147127.5..36",
+        "",
+    ),
+    (
+        Technique::Swordfish,
+        ".......7372..6351.3.17...861......2.96.15273..7..9416..1....35783.2..691..7...842",
+        "",
+    ),
+];
+
+/// A position that demonstrates a technique
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tutorial {
+    pub board: Board,
+    pub constraints: Vec<Constraint>,
+
+    /// The step that uses the technique. Its house, cells, placement and eliminations are the parts of the board to
+    /// highlight.
+    pub step: Step,
+}
+
+/// Returns the positions that demonstrate the technique. There is at least one for each technique.
+pub fn examples(technique: Technique) -> Vec<Tutorial> {
+    EXAMPLES
+        .iter()
+        .filter(|(example_technique, _, _)| *example_technique == technique)
+        .map(|(_, board, constraints)| {
+            let board = parse_line(board).expect("Embedded boards are valid");
+            let constraints = parse_constraints(constraints).expect("Embedded constraints are valid");
+            let step = hint(&board, &constraints).expect("Embedded boards have a logical step");
+            Tutorial { board, constraints, step }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve_with_constraints;
+
+    #[test]
+    fn examples_demonstrate_their_technique() {
+        for technique in Technique::ALL {
+            let examples = examples(technique);
+            assert!(!examples.is_empty(), "No example for {technique}");
+            for example in examples {
+                assert_eq!(technique, example.step.technique);
+                assert!(example.step.placement.is_some() || !example.step.eliminations.is_empty());
+                if example.constraints.is_empty() {
+                    assert!(solve_with_constraints(example.board, &example.constraints).is_ok());
+                }
+            }
+        }
+    }
+}
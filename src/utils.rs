@@ -1,3 +1,20 @@
 pub const fn div_ceil(a: usize, b: usize) -> usize {
-    (a + b - 1) / b
+    a.div_ceil(b)
+}
+
+/// A tiny random number generator, e.g. for [ValueOrder::Random](crate::ValueOrder::Random), so solving doesn't need
+/// the `rand` feature. It's also a good 64 bit hash: the first output of `SplitMix64(x)` for different `x` looks
+/// unrelated even if the `x` are close together.
+#[cfg(feature = "alloc")]
+pub struct SplitMix64(pub u64);
+
+#[cfg(feature = "alloc")]
+impl SplitMix64 {
+    pub fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
 }
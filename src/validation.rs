@@ -0,0 +1,249 @@
+use crate::board::{Board, HEIGHT, WIDTH};
+use crate::constraints::Constraint;
+use crate::hints::HouseId;
+
+/// The rule a [Violation] breaks
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// A value appears more than once in a row, column or 3x3 region
+    House(HouseId),
+
+    /// A variant constraint is violated
+    Constraint(Constraint),
+}
+
+/// A [Violation] is one specific place where the board breaks a rule
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub rule: Rule,
+
+    /// The cells that together break the rule, e.g. the two cells with the same value in a row
+    pub cells: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Constraints that are malformed, e.g. referencing cells outside of the board. These aren't checked against the board.
+    pub invalid_constraints: Vec<Constraint>,
+
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.invalid_constraints.is_empty() && self.violations.is_empty()
+    }
+}
+
+/// [validate_with] checks a (potentially partially filled) board against the classic rules and the given constraints
+/// and reports every violation. Unlike [Board::has_conflicts_with], this tells a UI which rule is violated where,
+/// e.g. to highlight the cells while a user is authoring a variant puzzle.
+pub fn validate_with(board: &Board, constraints: &[Constraint]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    for house in HouseId::all(&[]) {
+        check_house(board, Rule::House(house), house, &mut report.violations);
+    }
+    for constraint in constraints {
+        if !constraint.is_valid() {
+            report.invalid_constraints.push(*constraint);
+            continue;
+        }
+        let rule = Rule::Constraint(*constraint);
+        match *constraint {
+            Constraint::Diagonal | Constraint::Windows => {
+                for house in HouseId::all(&[*constraint]) {
+                    if matches!(house, HouseId::Diagonal(_) | HouseId::Window(_)) {
+                        check_house(board, rule, house, &mut report.violations);
+                    }
+                }
+            }
+            Constraint::AntiKnight | Constraint::AntiKing => {
+                check_pairs(
+                    board,
+                    rule,
+                    |x, y| constraint.peers(x, y).collect(),
+                    |a, b| a == b,
+                    &mut report.violations,
+                );
+            }
+            Constraint::NonConsecutive => {
+                check_pairs(
+                    board,
+                    rule,
+                    |x, y| Constraint::orthogonal_neighbors(x, y).collect(),
+                    |a, b| a.abs_diff(b) == 1,
+                    &mut report.violations,
+                );
+            }
+            Constraint::GreaterThan { greater, smaller } => {
+                if constraint.has_conflicts(board) {
+                    report.violations.push(Violation {
+                        rule,
+                        cells: vec![greater, smaller],
+                    });
+                }
+            }
+            Constraint::Kropki { cells, .. } => {
+                if constraint.has_conflicts(board) {
+                    report.violations.push(Violation {
+                        rule,
+                        cells: cells.to_vec(),
+                    });
+                }
+            }
+            Constraint::Killer { cage, .. } => {
+                if constraint.has_conflicts(board) {
+                    report.violations.push(Violation {
+                        rule,
+                        cells: cage.cells().collect(),
+                    });
+                }
+            }
+        }
+    }
+    report
+}
+
+fn check_house(board: &Board, rule: Rule, house: HouseId, violations: &mut Vec<Violation>) {
+    let mut cells_by_value: [Vec<(usize, usize)>; 9] = Default::default();
+    for (x, y) in house.cells() {
+        if let Some(value) = board.field(x, y).get() {
+            cells_by_value[usize::from(value.get()) - 1].push((x, y));
+        }
+    }
+    for cells in cells_by_value {
+        if cells.len() > 1 {
+            violations.push(Violation { rule, cells });
+        }
+    }
+}
+
+fn check_pairs(
+    board: &Board,
+    rule: Rule,
+    related_cells: impl Fn(usize, usize) -> Vec<(usize, usize)>,
+    conflicting_values: impl Fn(u8, u8) -> bool,
+    violations: &mut Vec<Violation>,
+) {
+    for x in 0..WIDTH {
+        for y in 0..HEIGHT {
+            let Some(value) = board.field(x, y).get() else {
+                continue;
+            };
+            for (other_x, other_y) in related_cells(x, y) {
+                // Only report each pair once
+                if (other_x, other_y) <= (x, y) {
+                    continue;
+                }
+                if let Some(other_value) = board.field(other_x, other_y).get() {
+                    if conflicting_values(value.get(), other_value.get()) {
+                        violations.push(Violation {
+                            rule,
+                            cells: vec![(x, y), (other_x, other_y)],
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid() {
+        let board = Board::from_str(
+            "
+            __4 68_ _19
+            __3 __9 2_5
+            _6_ ___ __4
+
+            6__ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        let report = validate_with(&board, &[]);
+        assert!(report.is_valid());
+        assert_eq!(ValidationReport::default(), report);
+    }
+
+    #[test]
+    fn classic_violations() {
+        let board = Board::from_str(
+            "
+            __4 68_ _19
+            __3 __9 2_5
+            _6_ ___ __4
+
+            67_ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        let report = validate_with(&board, &[]);
+        assert!(!report.is_valid());
+        assert_eq!(
+            vec![Violation {
+                rule: Rule::House(HouseId::Row(3)),
+                cells: vec![(1, 3), (6, 3)],
+            }],
+            report.violations
+        );
+    }
+
+    #[test]
+    fn constraint_violations() {
+        let board = Board::from_str(
+            "
+            ___ ___ ___
+            ___ ___ ___
+            __1 ___ ___
+
+            ___ 1__ ___
+            _2_ ___ ___
+            _4_ ___ ___
+
+            ___ ___ ___
+            ___ ___ ___
+            ___ ___ ___
+        ",
+        );
+        let greater_than = Constraint::GreaterThan {
+            greater: (1, 4),
+            smaller: (1, 5),
+        };
+        let invalid = Constraint::GreaterThan {
+            greater: (0, 0),
+            smaller: (0, 0),
+        };
+        let report = validate_with(
+            &board,
+            &[Constraint::AntiKing, Constraint::NonConsecutive, greater_than, invalid],
+        );
+        assert_eq!(vec![invalid], report.invalid_constraints);
+        assert_eq!(
+            vec![
+                Violation {
+                    rule: Rule::Constraint(Constraint::AntiKing),
+                    cells: vec![(2, 2), (3, 3)],
+                },
+                Violation {
+                    rule: Rule::Constraint(greater_than),
+                    cells: vec![(1, 4), (1, 5)],
+                },
+            ],
+            report.violations
+        );
+    }
+}
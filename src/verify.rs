@@ -0,0 +1,160 @@
+//! Differential verification of the solver backends, e.g. to run over a large corpus of puzzles before switching
+//! from one backend to another.
+
+use crate::board::{Board, HEIGHT, WIDTH};
+use crate::solver::{solve_with_backend, Backend, SolveStats, SolverError};
+use crate::validation::{validate_with, Violation};
+
+/// What one backend returned for the board, see [cross_check]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackendOutcome {
+    pub backend: Backend,
+    pub result: Result<Board, SolverError>,
+    pub stats: SolveStats,
+}
+
+/// Something that is wrong with the outcome of a backend, or with the outcomes of several backends together
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Discrepancy {
+    /// The two backends returned different results
+    Disagreement { first: Backend, second: Backend },
+
+    /// The solution of the backend changed or removed given fields
+    GivensChanged { backend: Backend, cells: Vec<(usize, usize)> },
+
+    /// The solution of the backend has empty fields
+    NotFilled { backend: Backend },
+
+    /// The solution of the backend breaks the rules
+    RulesViolated { backend: Backend, violations: Vec<Violation> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrossCheckReport {
+    pub outcomes: Vec<BackendOutcome>,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl CrossCheckReport {
+    /// True if all backends agree and their solutions are valid
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// [cross_check] solves the board with every [Backend] and reports each place where they disagree
+/// or where a solution doesn't keep the givens or doesn't follow the rules.
+/// Only classic sudokus are supported, because not all backends support variant constraints.
+pub fn cross_check(board: &Board) -> CrossCheckReport {
+    let outcomes: Vec<BackendOutcome> = Backend::ALL
+        .into_iter()
+        .map(|backend| {
+            let (result, stats) = solve_with_backend(*board, backend);
+            BackendOutcome { backend, result, stats }
+        })
+        .collect();
+    let mut discrepancies = Vec::new();
+    for outcome in &outcomes {
+        if let Ok(solution) = &outcome.result {
+            check_solution(board, outcome.backend, solution, &mut discrepancies);
+        }
+    }
+    for (first, second) in outcomes.iter().zip(outcomes.iter().skip(1)) {
+        if first.result != second.result {
+            discrepancies.push(Discrepancy::Disagreement {
+                first: first.backend,
+                second: second.backend,
+            });
+        }
+    }
+    CrossCheckReport { outcomes, discrepancies }
+}
+
+fn check_solution(board: &Board, backend: Backend, solution: &Board, discrepancies: &mut Vec<Discrepancy>) {
+    let changed_givens: Vec<(usize, usize)> = (0..WIDTH)
+        .flat_map(|x| (0..HEIGHT).map(move |y| (x, y)))
+        .filter(|&(x, y)| {
+            let given = board.field(x, y).get();
+            given.is_some() && given != solution.field(x, y).get()
+        })
+        .collect();
+    if !changed_givens.is_empty() {
+        discrepancies.push(Discrepancy::GivensChanged {
+            backend,
+            cells: changed_givens,
+        });
+    }
+    if !solution.is_filled() {
+        discrepancies.push(Discrepancy::NotFilled { backend });
+    }
+    let report = validate_with(solution, &[]);
+    if !report.is_valid() {
+        discrepancies.push(Discrepancy::RulesViolated {
+            backend,
+            violations: report.violations,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backends_agree() {
+        let solvable = Board::from_str(
+            "
+            __4 68_ _19
+            __3 __9 2_5
+            _6_ ___ __4
+
+            6__ ___ 7_2
+            ___ __7 ___
+            ___ 9__ __1
+
+            8__ _5_ __7
+            _41 3_8 ___
+            _2_ _91 ___
+        ",
+        );
+        for board in [solvable, Board::new_empty()] {
+            let report = cross_check(&board);
+            assert!(report.is_consistent(), "{report:?}");
+            assert_eq!(Backend::ALL.len(), report.outcomes.len());
+        }
+    }
+
+    #[test]
+    fn wrong_solution() {
+        let board = Board::from_str(
+            "
+            1__ ___ ___
+            ___ ___ ___
+            ___ ___ ___
+
+            ___ ___ ___
+            ___ ___ ___
+            ___ ___ ___
+
+            ___ ___ ___
+            ___ ___ ___
+            ___ ___ ___
+        ",
+        );
+        let mut solution = Board::new_empty();
+        solution.field_mut(0, 0).set(std::num::NonZeroU8::new(2));
+        solution.field_mut(1, 0).set(std::num::NonZeroU8::new(2));
+        let mut discrepancies = Vec::new();
+        check_solution(&board, Backend::Dlx, &solution, &mut discrepancies);
+        assert_eq!(3, discrepancies.len());
+        assert_eq!(
+            Discrepancy::GivensChanged {
+                backend: Backend::Dlx,
+                cells: vec![(0, 0)],
+            },
+            discrepancies[0]
+        );
+        assert_eq!(Discrepancy::NotFilled { backend: Backend::Dlx }, discrepancies[1]);
+        assert!(matches!(discrepancies[2], Discrepancy::RulesViolated { .. }));
+    }
+}
@@ -0,0 +1,137 @@
+//! JavaScript bindings, built with the `wasm` feature. The manifest only builds an rlib, because a cdylib would break
+//! `no_std` builds, so the WebAssembly module is built as a cdylib explicitly and then passed to `wasm-bindgen`:
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --no-default-features --features wasm --crate-type cdylib`.
+//! Boards are passed either as strings in the line format of [parse_line], or as `Uint8Array`s with 81 values
+//! row by row, where 0 is an empty field. Errors are thrown as strings.
+//! Generating only uses seeded random number generators, so the same seed gives the same sudoku as the native
+//! `generate_with_difficulty_seeded`. The `sudoku generate --seed` command doesn't use its seed directly, it derives
+//! one seed per puzzle from it, and `--manifest` records the derived seeds, which reproduce the puzzles here.
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::board::{Board, NUM_FIELDS};
+use crate::formats::{format_line, parse_line};
+use crate::generator::{generate_with_difficulty_seeded, Symmetry};
+use crate::hints::Step;
+use crate::rating::Difficulty;
+
+/// A deduction of the hint engine, see [Step]
+#[wasm_bindgen(getter_with_clone)]
+pub struct Hint {
+    pub technique: String,
+    pub explanation: String,
+
+    /// The field and value the hint places as `[x, y, value]`, or empty if it only eliminates candidates
+    pub placement: Vec<u8>,
+}
+
+impl From<Step> for Hint {
+    fn from(step: Step) -> Self {
+        Self {
+            technique: step.technique.to_string(),
+            explanation: step.explanation(),
+            placement: step
+                .placement
+                .map(|candidate| vec![candidate.x as u8, candidate.y as u8, candidate.value.get()])
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Solves a sudoku in the line format, see [solve](crate::solve)
+#[wasm_bindgen]
+pub fn solve(puzzle: &str) -> Result<String, String> {
+    Ok(format_line(&solve_board(parse(puzzle)?)?))
+}
+
+/// Like [solve], but for a board as 81 values row by row
+#[wasm_bindgen(js_name = solveCells)]
+pub fn solve_cells(cells: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(to_cells(&solve_board(from_cells(cells)?)?))
+}
+
+/// Generates a sudoku with the given difficulty and symmetry names, e.g. `"hard"` and `"rotational"`.
+/// The same seed always gives the same sudoku, see [generate_with_difficulty_seeded].
+#[wasm_bindgen]
+pub fn generate(difficulty: &str, symmetry: &str, seed: u64) -> Result<String, String> {
+    Ok(format_line(&generate_board(difficulty, symmetry, seed)?))
+}
+
+/// Like [generate], but returns the board as 81 values row by row
+#[wasm_bindgen(js_name = generateCells)]
+pub fn generate_cells(difficulty: &str, symmetry: &str, seed: u64) -> Result<Vec<u8>, String> {
+    Ok(to_cells(&generate_board(difficulty, symmetry, seed)?))
+}
+
+/// The name of the difficulty of a sudoku in the line format, see [rate](crate::rate)
+#[wasm_bindgen]
+pub fn rate(puzzle: &str) -> Result<String, String> {
+    let report = crate::rate(&parse(puzzle)?).map_err(|error| error.to_string())?;
+    Ok(report.difficulty.to_string())
+}
+
+/// The next deduction for a sudoku in the line format, or `undefined` if the hint engine is stuck, see [hint](crate::hint)
+#[wasm_bindgen]
+pub fn hint(puzzle: &str) -> Result<Option<Hint>, String> {
+    Ok(crate::hint(&parse(puzzle)?, &[]).map(Hint::from))
+}
+
+fn parse(puzzle: &str) -> Result<Board, String> {
+    parse_line(puzzle).map_err(|error| error.to_string())
+}
+
+fn solve_board(board: Board) -> Result<Board, String> {
+    crate::solve(board).map_err(|error| error.to_string())
+}
+
+fn generate_board(difficulty: &str, symmetry: &str, seed: u64) -> Result<Board, String> {
+    let difficulty: Difficulty = difficulty.parse()?;
+    let symmetry: Symmetry = symmetry.parse()?;
+    Ok(generate_with_difficulty_seeded(difficulty, symmetry, seed))
+}
+
+fn from_cells(cells: &[u8]) -> Result<Board, String> {
+    Board::try_from(cells).map_err(|error| error.to_string())
+}
+
+fn to_cells(board: &Board) -> Vec<u8> {
+    <[u8; NUM_FIELDS]>::from(board).to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num::NonZeroU8;
+
+    const PUZZLE: &str = "..468..19..3..92.5.6......46.....7.2.....7......9....18...5...7.413.8....2..91...";
+
+    #[test]
+    fn solve_formats_agree() {
+        let solution = solve(PUZZLE).unwrap();
+        let cells = to_cells(&parse(PUZZLE).unwrap());
+        assert_eq!(to_cells(&parse(&solution).unwrap()), solve_cells(&cells).unwrap());
+        assert_eq!(Err("Sudoku has multiple valid solutions".to_string()), solve(&".".repeat(NUM_FIELDS)));
+        assert!(solve_cells(&[0; 80]).is_err());
+        assert!(solve_cells(&[10; 81]).is_err());
+    }
+
+    #[test]
+    fn generate_matches_native() {
+        let board = generate("easy", "rotational", 1224).unwrap();
+        assert_eq!(
+            format_line(&generate_with_difficulty_seeded(Difficulty::Easy, Symmetry::Rotational, 1224)),
+            board
+        );
+        assert_eq!("easy", rate(&board).unwrap());
+        assert!(generate("trivial", "none", 0).is_err());
+    }
+
+    #[test]
+    fn hint_places_solution_value() {
+        let solution = parse(&solve(PUZZLE).unwrap()).unwrap();
+        let first = hint(PUZZLE).unwrap().unwrap();
+        let [x, y, value] = first.placement[..] else { panic!("Expected a placement") };
+        assert_eq!(NonZeroU8::new(value), solution.field(usize::from(x), usize::from(y)).get());
+        assert!(hint(&solve(PUZZLE).unwrap()).unwrap().is_none());
+    }
+}